@@ -5,7 +5,7 @@ use hakana_code_info::assertion::Assertion;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use rand::Rng;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
 use std::hash::Hash;
 
@@ -20,6 +20,22 @@ fn keys_match<T: Eq + Ord, U, V>(map1: &BTreeMap<T, U>, map2: &BTreeMap<T, V>) -
     map1.len() == map2.len() && map1.keys().all(|k| map2.contains_key(k))
 }
 
+// Maps each variable key to the (ascending) indices of the clauses that reference it,
+// so that pairwise comparisons can skip clauses that have no keys in common
+fn get_clause_indices_by_key<'a>(
+    clauses: impl Iterator<Item = &'a Clause>,
+) -> FxHashMap<&'a String, Vec<usize>> {
+    let mut indices_by_key: FxHashMap<_, Vec<_>> = FxHashMap::default();
+
+    for (i, clause) in clauses.enumerate() {
+        for key in clause.possibilities.keys() {
+            indices_by_key.entry(key).or_default().push(i);
+        }
+    }
+
+    indices_by_key
+}
+
 // This is a very simple simplification heuristic
 // for CNF formulae.
 //
@@ -58,6 +74,18 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
     let mut removed_clauses = FxHashSet::default();
     let mut added_clauses = vec![];
 
+    // clauses can only be merged with other clauses that have exactly the same keys
+    let mut clause_indices_by_key_set: FxHashMap<Vec<&String>, Vec<usize>> = FxHashMap::default();
+
+    for (i, clause) in unique_clauses.iter().enumerate() {
+        clause_indices_by_key_set
+            .entry(clause.possibilities.keys().collect())
+            .or_default()
+            .push(i);
+    }
+
+    let clause_indices_by_key = get_clause_indices_by_key(unique_clauses.iter().copied());
+
     // remove impossible types
     'outer: for clause_a in &unique_clauses {
         if !clause_a.reconcilable || clause_a.wedge {
@@ -77,8 +105,11 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
         }
 
         if !is_clause_a_simple {
-            'inner: for clause_b in &unique_clauses {
-                if clause_a == clause_b || !clause_b.reconcilable || clause_b.wedge {
+            let same_key_indices =
+                &clause_indices_by_key_set[&clause_a.possibilities.keys().collect::<Vec<_>>()];
+
+            'inner: for clause_b in same_key_indices.iter().map(|i| unique_clauses[*i]) {
+                if clause_a == &clause_b || !clause_b.reconcilable || clause_b.wedge {
                     continue;
                 }
 
@@ -129,8 +160,11 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
             let negated_clause_type = only_type.get_negation();
             let negated_hash = negated_clause_type.to_hash();
 
-            for clause_b in &unique_clauses {
-                if clause_a == clause_b || !clause_b.reconcilable || clause_b.wedge {
+            for clause_b in clause_indices_by_key[clause_var]
+                .iter()
+                .map(|i| unique_clauses[*i])
+            {
+                if clause_a == &clause_b || !clause_b.reconcilable || clause_b.wedge {
                     continue;
                 }
 
@@ -141,7 +175,7 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
 
                         clause_var_possibilities.retain(|k, _| k != &negated_hash);
 
-                        removed_clauses.insert(*clause_b);
+                        removed_clauses.insert(clause_b);
 
                         if clause_var_possibilities.is_empty() {
                             let maybe_updated_clause = clause_b.remove_possibilities(clause_var);
@@ -172,10 +206,30 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
 
     let mut simplified_clauses = vec![];
 
+    // a clause can only be contained by clauses that have all of its keys, so indexing
+    // each clause by its first key means checking every key of clause_a finds all candidates
+    let mut clause_indices_by_first_key: FxHashMap<Option<&String>, Vec<usize>> =
+        FxHashMap::default();
+
+    for (i, clause) in unique_clauses.iter().enumerate() {
+        clause_indices_by_first_key
+            .entry(clause.possibilities.keys().next())
+            .or_default()
+            .push(i);
+    }
+
     for clause_a in &unique_clauses {
         let mut is_redundant = false;
 
-        for clause_b in &unique_clauses {
+        let candidate_indices = clause_a
+            .possibilities
+            .keys()
+            .map(Some)
+            .chain([None])
+            .filter_map(|key| clause_indices_by_first_key.get(&key))
+            .flatten();
+
+        for clause_b in candidate_indices.map(|i| &unique_clauses[*i]) {
             if clause_a == clause_b || !clause_b.reconcilable || clause_b.wedge || clause_a.wedge {
                 continue;
             }
@@ -200,8 +254,24 @@ pub fn simplify_cnf(clauses: Vec<&Clause>) -> Vec<Clause> {
 
         let mut removed_clauses = FxHashSet::default();
 
+        let clause_indices_by_key = get_clause_indices_by_key(simplified_clauses.iter());
+
         for clause_a in &simplified_clauses {
-            for clause_b in &simplified_clauses {
+            // only clauses that share a key can conflict
+            let mut candidate_indices = clause_a
+                .possibilities
+                .keys()
+                .flat_map(|key| &clause_indices_by_key[key])
+                .copied()
+                .collect::<Vec<_>>();
+
+            candidate_indices.sort_unstable();
+            candidate_indices.dedup();
+
+            for clause_b in candidate_indices
+                .into_iter()
+                .map(|i| &simplified_clauses[i])
+            {
                 if clause_a == clause_b
                     || compared_clauses.contains(&(clause_b.hash, clause_a.hash))
                 {
@@ -349,7 +419,10 @@ pub fn get_truths_from_formula(
     (truths, active_truths)
 }
 
-fn group_impossibilities(mut clauses: Vec<Clause>) -> Result<Vec<Clause>, String> {
+fn group_impossibilities(
+    mut clauses: Vec<Clause>,
+    complexity_budget: usize,
+) -> Result<Vec<Clause>, String> {
     let mut complexity = 1;
 
     let mut seed_clauses = vec![];
@@ -401,7 +474,7 @@ fn group_impossibilities(mut clauses: Vec<Clause>) -> Result<Vec<Clause>, String
             i += p.len();
         }
         upper_bound *= i;
-        if upper_bound > 20000 {
+        if upper_bound > complexity_budget {
             return Err("Complicated".to_string());
         }
     }
@@ -440,7 +513,7 @@ fn group_impossibilities(mut clauses: Vec<Clause>) -> Result<Vec<Clause>, String
 
                     complexity += 1;
 
-                    if complexity > 20000 {
+                    if complexity > complexity_budget {
                         return Err("Complicated".to_string());
                     }
                 }
@@ -459,6 +532,7 @@ pub fn combine_ored_clauses(
     left_clauses: Vec<Clause>,
     right_clauses: Vec<Clause>,
     conditional_object_id: (u32, u32),
+    clause_budget: usize,
 ) -> Result<Vec<Clause>, String> {
     let mut clauses = vec![];
 
@@ -470,7 +544,7 @@ pub fn combine_ored_clauses(
 
     let upper_bound_output = left_clauses_len * right_clauses_len;
 
-    if upper_bound_output > 2048 {
+    if upper_bound_output > clause_budget {
         return Err("too many clauses".to_string());
     }
 
@@ -583,7 +657,10 @@ pub fn combine_ored_clauses(
 //   (!$a || !$c || !$d) &&
 //   (!$a || !$c || !$e) &&
 //   (!$a || !$c || !$f)
-pub fn negate_formula(mut clauses: Vec<Clause>) -> Result<Vec<Clause>, String> {
+pub fn negate_formula(
+    mut clauses: Vec<Clause>,
+    complexity_budget: usize,
+) -> Result<Vec<Clause>, String> {
     clauses.retain(|clause| clause.reconcilable);
 
     if clauses.is_empty() {
@@ -600,7 +677,7 @@ pub fn negate_formula(mut clauses: Vec<Clause>) -> Result<Vec<Clause>, String> {
         )]);
     }
 
-    let impossible_clauses = group_impossibilities(clauses)?;
+    let impossible_clauses = group_impossibilities(clauses, complexity_budget)?;
 
     if impossible_clauses.is_empty() {
         let mut rng = rand::thread_rng();
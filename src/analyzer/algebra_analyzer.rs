@@ -23,7 +23,10 @@ pub(crate) fn check_for_paradox(
     pos: &Pos,
     calling_functionlike_id: &Option<FunctionLikeIdentifier>,
 ) {
-    let negated_formula_2 = hakana_algebra::negate_formula(formula_2.clone());
+    let negated_formula_2 = hakana_algebra::negate_formula(
+        formula_2.clone(),
+        statements_analyzer.get_config().algebra_complexity_budget,
+    );
 
     if negated_formula_2.is_err() {
         return;
@@ -106,7 +109,10 @@ pub(crate) fn check_for_paradox(
             }
 
             if negated_clause_2_contains_1_possibilities {
-                let mini_formula_2 = hakana_algebra::negate_formula(vec![negated_clause_2.clone()]);
+                let mini_formula_2 = hakana_algebra::negate_formula(
+                    vec![negated_clause_2.clone()],
+                    statements_analyzer.get_config().algebra_complexity_budget,
+                );
 
                 if let Ok(mini_formula_2) = mini_formula_2 {
                     let mut paradox_message = String::new();
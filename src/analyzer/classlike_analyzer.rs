@@ -92,8 +92,18 @@ impl<'a> ClassLikeAnalyzer<'a> {
             None,
         );
 
-        if let Some(issue_filter) = &statements_analyzer.get_config().allowed_issues {
-            analysis_data.issue_filter = Some(issue_filter.clone());
+        analysis_data.issue_filter = statements_analyzer
+            .get_config()
+            .get_allowed_issues_for_file(statements_analyzer.get_file_path_actual());
+
+        if let Some(file_info) = statements_analyzer
+            .codebase
+            .files
+            .get(statements_analyzer.get_file_path())
+        {
+            analysis_data
+                .line_ignores
+                .clone_from(&file_info.line_ignores);
         }
 
         if stmt.kind.is_cclass()
@@ -202,6 +212,23 @@ impl<'a> ClassLikeAnalyzer<'a> {
         }
 
         for var in &stmt.vars {
+            if var.type_.1.is_none() && var.xhp_attr.is_none() {
+                analysis_data.maybe_add_issue(
+                    Issue::new(
+                        IssueKind::MissingPropertyType,
+                        format!(
+                            "Property {}::${} has no declared type",
+                            statements_analyzer.interner.lookup(&name),
+                            var.id.1
+                        ),
+                        statements_analyzer.get_hpos(&var.id.0),
+                        &Some(FunctionLikeIdentifier::Function(name)),
+                    ),
+                    statements_analyzer.get_config(),
+                    statements_analyzer.get_file_path_actual(),
+                );
+            }
+
             if let Some(default) = &var.expr {
                 expression_analyzer::analyze(
                     statements_analyzer,
@@ -215,7 +242,7 @@ impl<'a> ClassLikeAnalyzer<'a> {
         update_analysis_result_with_tast(
             analysis_data,
             analysis_result,
-            statements_analyzer.get_file_path(),
+            statements_analyzer,
             false,
         );
 
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use hakana_str::Interner;
+use serde::Serialize;
+
+use super::Config;
+
+/// A serializable snapshot of a fully-resolved Config, for debugging config precedence.
+/// Collections are sorted so the output is stable between runs.
+#[derive(Serialize)]
+pub struct EffectiveConfig {
+    pub root_dir: String,
+    pub root_dirs: Vec<String>,
+    pub graph_kind: String,
+    pub allowed_issues: Option<Vec<String>>,
+    pub find_unused_expressions: bool,
+    pub find_unused_definitions: bool,
+    pub find_unused_suppressions: bool,
+    pub ignore_mixed_issues: bool,
+    pub show_mixed_argument_declarations: bool,
+    pub ast_diff: bool,
+    pub ignore_files: Vec<String>,
+    pub test_files: Vec<String>,
+    pub include_test_files: bool,
+    pub analyze_hhi_signatures: Vec<String>,
+    pub ignore_issue_files: BTreeMap<String, Vec<String>>,
+    pub ignore_all_issues_in_files: Vec<String>,
+    pub hakana_ignore: Vec<String>,
+    pub issue_severities: BTreeMap<String, String>,
+    pub banned_builtin_functions: BTreeMap<String, String>,
+    pub analyze_file_allowlist: Option<Vec<String>>,
+    pub analyze_symbol: Option<String>,
+    pub algebra_complexity_budget: usize,
+    pub algebra_clause_budget: usize,
+    pub custom_issues: Vec<String>,
+    pub max_fixmes: Option<usize>,
+    pub hack_version: Option<String>,
+    pub directory_overlays: BTreeMap<String, EffectiveConfigOverlay>,
+    pub hook_count: usize,
+    pub security_analysis: EffectiveSecurityConfig,
+}
+
+#[derive(Serialize)]
+pub struct EffectiveConfigOverlay {
+    pub enable_issues: Vec<String>,
+    pub disable_issues: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct EffectiveSecurityConfig {
+    pub ignore_files: Vec<String>,
+    pub ignore_sink_files: BTreeMap<String, Vec<String>>,
+    pub max_depth: u8,
+    pub max_depth_by_source: BTreeMap<String, u8>,
+}
+
+impl EffectiveConfig {
+    pub fn new(config: &Config, interner: &Interner) -> Self {
+        Self {
+            root_dir: config.root_dir.clone(),
+            root_dirs: config.root_dirs.clone(),
+            graph_kind: format!("{:?}", config.graph_kind),
+            allowed_issues: config
+                .allowed_issues
+                .as_ref()
+                .map(|allowed_issues| sorted(allowed_issues.iter().map(|k| k.to_string()))),
+            find_unused_expressions: config.find_unused_expressions,
+            find_unused_definitions: config.find_unused_definitions,
+            find_unused_suppressions: config.find_unused_suppressions,
+            ignore_mixed_issues: config.ignore_mixed_issues,
+            show_mixed_argument_declarations: config.show_mixed_argument_declarations,
+            ast_diff: config.ast_diff,
+            ignore_files: config.ignore_files.clone(),
+            test_files: get_pattern_strings(&config.test_files),
+            include_test_files: config.include_test_files,
+            analyze_hhi_signatures: get_pattern_strings(&config.analyze_hhi_signatures),
+            ignore_issue_files: config
+                .ignore_issue_patterns
+                .iter()
+                .map(|(kind, patterns)| (kind.to_string(), get_pattern_strings(patterns)))
+                .collect(),
+            ignore_all_issues_in_files: get_pattern_strings(&config.ignore_all_issues_in_patterns),
+            hakana_ignore: get_pattern_strings(&config.hakana_ignore_patterns),
+            issue_severities: config
+                .issue_severities
+                .iter()
+                .map(|(kind, severity)| (kind.to_string(), severity.to_string()))
+                .collect(),
+            banned_builtin_functions: config
+                .banned_builtin_functions
+                .iter()
+                .map(|(name, replacement)| {
+                    (
+                        interner.lookup(name).to_string(),
+                        interner.lookup(replacement).to_string(),
+                    )
+                })
+                .collect(),
+            analyze_file_allowlist: config
+                .analyze_file_allowlist
+                .as_ref()
+                .map(|allowlist| sorted(allowlist.iter().cloned())),
+            analyze_symbol: config.analyze_symbol.clone(),
+            algebra_complexity_budget: config.algebra_complexity_budget,
+            algebra_clause_budget: config.algebra_clause_budget,
+            custom_issues: sorted(config.all_custom_issues.iter().cloned()),
+            max_fixmes: config.max_fixmes,
+            hack_version: config
+                .hack_version
+                .map(|(major, minor)| format!("{}.{}", major, minor)),
+            directory_overlays: config
+                .directory_overlays
+                .iter()
+                .map(|(dir, overlay)| {
+                    (
+                        dir.clone(),
+                        EffectiveConfigOverlay {
+                            enable_issues: sorted(
+                                overlay.enabled_issues.iter().map(|k| k.to_string()),
+                            ),
+                            disable_issues: sorted(
+                                overlay.disabled_issues.iter().map(|k| k.to_string()),
+                            ),
+                        },
+                    )
+                })
+                .collect(),
+            hook_count: config.hooks.len(),
+            security_analysis: EffectiveSecurityConfig {
+                ignore_files: get_pattern_strings(&config.security_config.ignore_patterns),
+                ignore_sink_files: config
+                    .security_config
+                    .ignore_sink_files
+                    .iter()
+                    .map(|(key, patterns)| (key.clone(), get_pattern_strings(patterns)))
+                    .collect(),
+                max_depth: config.security_config.max_depth,
+                max_depth_by_source: config
+                    .security_config
+                    .max_depth_by_source
+                    .iter()
+                    .map(|(source_type, max_depth)| (source_type.to_string(), *max_depth))
+                    .collect(),
+            },
+        }
+    }
+}
+
+fn get_pattern_strings(patterns: &[glob::Pattern]) -> Vec<String> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.as_str().to_string())
+        .collect()
+}
+
+fn sorted(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut values = values.collect::<Vec<_>>();
+    values.sort();
+    values
+}
@@ -1,4 +1,4 @@
-use std::{error::Error, fs::File, io::BufReader, path::Path};
+use std::{error::Error, fs, fs::File, io::BufReader, path::Path};
 
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
@@ -17,6 +17,30 @@ pub struct JsonConfig {
     pub allowed_issues: Vec<String>,
     #[serde(default)]
     pub test_files: Vec<String>,
+    #[serde(default)]
+    pub include_test_files: bool,
+    #[serde(default)]
+    pub show_mixed_argument_declarations: bool,
+    // .hhi stubs whose signatures are checked for types that can't be resolved
+    #[serde(default)]
+    pub analyze_hhi_signatures: Vec<String>,
+    // issue kind => "info", "warning" or "error"
+    #[serde(default)]
+    pub issue_severities: FxHashMap<String, String>,
+    pub algebra_complexity_budget: Option<usize>,
+    pub algebra_clause_budget: Option<usize>,
+    pub max_fixmes: Option<usize>,
+    // the HHVM version code will run on, e.g. "4.153"
+    pub hack_version: Option<String>,
+}
+
+// a hakana.json nested below the root, which can only turn issue kinds on or off
+#[derive(Deserialize, Debug, Default)]
+pub struct JsonConfigOverlay {
+    #[serde(default)]
+    pub enable_issues: Vec<String>,
+    #[serde(default)]
+    pub disable_issues: Vec<String>,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -24,12 +48,31 @@ pub struct JsonSecurityConfig {
     pub ignore_files: Vec<String>,
     pub ignore_sink_files: FxHashMap<String, Vec<String>>,
     pub max_depth: Option<u8>,
+    // keyed by source type, e.g. "UserData"
+    #[serde(default)]
+    pub max_depth_by_source: FxHashMap<String, u8>,
 }
 
+// hakana.toml uses the same schema as hakana.json
 pub(crate) fn read_from_file(path: &Path) -> Result<JsonConfig, Box<dyn Error>> {
+    if path
+        .extension()
+        .map(|extension| extension == "toml")
+        .unwrap_or(false)
+    {
+        return Ok(toml::from_str(&fs::read_to_string(path)?)?);
+    }
+
     // Open the file in read-only mode with buffer.
     let file = File::open(path)?;
     let reader = BufReader::new(file);
 
     Ok(serde_json::from_reader(reader)?)
 }
+
+pub(crate) fn read_overlay_from_file(path: &Path) -> Result<JsonConfigOverlay, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    Ok(serde_json::from_reader(reader)?)
+}
@@ -1,8 +1,14 @@
-use std::{error::Error, path::Path};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use hakana_code_info::{
     data_flow::{graph::GraphKind, tainted_node::TaintedNode},
-    issue::{Issue, IssueKind},
+    issue::{Issue, IssueKind, IssueSeverity},
     taint::{SinkType, SourceType},
 };
 use hakana_str::{Interner, StrId};
@@ -10,7 +16,9 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::custom_hook::CustomHook;
 
+pub mod effective_config;
 pub mod json_config;
+pub mod validation;
 
 #[derive(Debug)]
 pub struct Config {
@@ -23,18 +31,63 @@ pub struct Config {
     pub issues_to_fix: FxHashSet<IssueKind>,
     pub graph_kind: GraphKind,
     pub ignore_files: Vec<String>,
+    // code in files matching these patterns (or marked <<Hakana\TestOnly>>) isn't production
+    // code. Only two issue kinds depend on that: TestOnlyCall fires when production code calls
+    // test-only code, and OnlyUsedInTests fires when production code is only used by test code
     pub test_files: Vec<glob::Pattern>,
+    // treats files matching test_files as production code, so the only test code left is
+    // whatever's marked <<Hakana\TestOnly>>
+    pub include_test_files: bool,
+    // .hhi stubs matching these patterns have the types in their signatures checked, though
+    // they're still not treated as user-defined code
+    pub analyze_hhi_signatures: Vec<glob::Pattern>,
     pub ignore_issue_patterns: FxHashMap<IssueKind, Vec<glob::Pattern>>,
     pub ignore_all_issues_in_patterns: Vec<glob::Pattern>,
+    // patterns from .hakanaignore, for files that are scanned but never analyzed
+    pub hakana_ignore_patterns: Vec<glob::Pattern>,
     pub banned_builtin_functions: FxHashMap<StrId, StrId>,
     pub security_config: SecurityConfig,
     pub root_dir: String,
+    // every root that's scanned and analyzed, starting with root_dir (which holds the cache)
+    pub root_dirs: Vec<String>,
     pub hooks: Vec<Box<dyn CustomHook>>,
     pub ignore_mixed_issues: bool,
+    // adds where the parameter is declared to MixedArgument and MixedAnyArgument messages
+    pub show_mixed_argument_declarations: bool,
     pub add_fixmes: bool,
     pub remove_fixmes: bool,
+    pub find_unused_suppressions: bool,
     pub all_custom_issues: FxHashSet<String>,
     pub ast_diff: bool,
+    // when set, only these files are analyzed (though all files are still scanned)
+    pub analyze_file_allowlist: Option<FxHashSet<String>>,
+    // when set, only the body of this function or method (e.g. Foo\Bar::baz) is analyzed
+    pub analyze_symbol: Option<String>,
+    // records the inferred type of every analyzed expression, for use in editor hovers
+    pub collect_hover_types: bool,
+    // past these limits, negating or combining conditional formulae gives up, and the
+    // analyzer loses any type narrowing those formulae would have provided
+    pub algebra_complexity_budget: usize,
+    pub algebra_clause_budget: usize,
+    // overrides the default severity of these issue kinds
+    pub issue_severities: FxHashMap<IssueKind, IssueSeverity>,
+    // fails the run when more than this many fixmes suppress an issue
+    pub max_fixmes: Option<usize>,
+    // issue kinds turned on or off by hakana.json files nested below the root, keyed by
+    // the directory they're in
+    pub directory_overlays: BTreeMap<String, ConfigOverlay>,
+    // the HHVM version code will run on, as (major, minor). Calls to builtins that were
+    // added after it are reported as UnavailableBuiltin
+    pub hack_version: Option<(u16, u16)>,
+    // where the scanned builtin stubs are saved between runs. They only change with the
+    // hakana build, so this is used even when the rest of the cache isn't
+    pub stub_cache_dir: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverlay {
+    pub enabled_issues: FxHashSet<IssueKind>,
+    pub disabled_issues: FxHashSet<IssueKind>,
 }
 
 #[derive(Clone, Debug)]
@@ -42,6 +95,8 @@ pub struct SecurityConfig {
     ignore_patterns: Vec<glob::Pattern>,
     ignore_sink_files: FxHashMap<String, Vec<glob::Pattern>>,
     pub max_depth: u8,
+    // overrides max_depth for paths that start at a given source type
+    pub max_depth_by_source: FxHashMap<SourceType, u8>,
 }
 
 impl Default for SecurityConfig {
@@ -56,34 +111,65 @@ impl SecurityConfig {
             ignore_patterns: Vec::new(),
             ignore_sink_files: FxHashMap::default(),
             max_depth: 25,
+            max_depth_by_source: FxHashMap::default(),
         }
     }
+
+    /// When a path carries several source types, the deepest limit among them wins.
+    pub fn get_max_depth(&self, source_types: &[SourceType]) -> u8 {
+        source_types
+            .iter()
+            .map(|source_type| {
+                *self
+                    .max_depth_by_source
+                    .get(source_type)
+                    .unwrap_or(&self.max_depth)
+            })
+            .max()
+            .unwrap_or(self.max_depth)
+    }
 }
 
 impl Config {
     pub fn new(root_dir: String, all_custom_issues: FxHashSet<String>) -> Self {
         Self {
+            root_dirs: vec![root_dir.clone()],
             root_dir,
             find_unused_expressions: false,
             find_unused_definitions: false,
             ignore_mixed_issues: false,
+            show_mixed_argument_declarations: false,
             allowed_issues: None,
             migration_symbols: FxHashMap::default(),
             graph_kind: GraphKind::FunctionBody,
             ignore_files: Vec::new(),
             test_files: Vec::new(),
+            include_test_files: false,
+            analyze_hhi_signatures: Vec::new(),
             ignore_issue_patterns: FxHashMap::default(),
             ignore_all_issues_in_patterns: vec![],
+            hakana_ignore_patterns: vec![],
             security_config: SecurityConfig::new(),
             issues_to_fix: FxHashSet::default(),
             hooks: vec![],
             add_fixmes: false,
             remove_fixmes: false,
+            find_unused_suppressions: false,
             all_custom_issues,
             ast_diff: false,
             in_migration: false,
             in_codegen: false,
             banned_builtin_functions: FxHashMap::default(),
+            analyze_file_allowlist: None,
+            analyze_symbol: None,
+            collect_hover_types: false,
+            algebra_complexity_budget: 20000,
+            algebra_clause_budget: 2048,
+            issue_severities: FxHashMap::default(),
+            max_fixmes: None,
+            directory_overlays: BTreeMap::default(),
+            hack_version: None,
+            stub_cache_dir: None,
         }
     }
 
@@ -107,6 +193,16 @@ impl Config {
             .map(|v| glob::Pattern::new(&format!("{}/{}", cwd, v)).unwrap())
             .collect();
 
+        self.include_test_files = json_config.include_test_files;
+
+        self.show_mixed_argument_declarations = json_config.show_mixed_argument_declarations;
+
+        self.analyze_hhi_signatures = json_config
+            .analyze_hhi_signatures
+            .into_iter()
+            .map(|v| glob::Pattern::new(&format!("{}/{}", cwd, v)).unwrap())
+            .collect();
+
         self.ignore_issue_patterns = json_config
             .ignore_issue_files
             .iter()
@@ -168,11 +264,177 @@ impl Config {
             })
             .collect();
         self.security_config.max_depth = json_config.security_analysis.max_depth.unwrap_or(40);
+        self.security_config.max_depth_by_source = json_config
+            .security_analysis
+            .max_depth_by_source
+            .into_iter()
+            .map(|(k, v)| {
+                SourceType::from_str(&k)
+                    .map(|source_type| (source_type, v))
+                    .map_err(|_| format!("Unknown taint source type {}", k))
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.issue_severities = json_config
+            .issue_severities
+            .into_iter()
+            .map(|(k, v)| {
+                Ok((
+                    IssueKind::from_str_custom(k.as_str(), &self.all_custom_issues)?,
+                    IssueSeverity::from_str(&v)
+                        .map_err(|_| format!("Unknown severity {} for {}", v, k))?,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+
+        if let Some(algebra_complexity_budget) = json_config.algebra_complexity_budget {
+            self.algebra_complexity_budget = algebra_complexity_budget;
+        }
+
+        if let Some(algebra_clause_budget) = json_config.algebra_clause_budget {
+            self.algebra_clause_budget = algebra_clause_budget;
+        }
+
+        if let Some(max_fixmes) = json_config.max_fixmes {
+            self.max_fixmes = Some(max_fixmes);
+        }
+
+        if let Some(hack_version) = json_config.hack_version {
+            self.hack_version = Some(
+                parse_hack_version(&hack_version)
+                    .ok_or_else(|| format!("Invalid hack_version {}", hack_version))?,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Reads gitignore-style patterns from a .hakanaignore file in the root directory, if
+    /// one exists. Matching files are still scanned, but never analyzed or reported on.
+    pub fn update_from_ignore_file(&mut self) -> Result<(), Box<dyn Error>> {
+        let ignore_file_path = Path::new(&self.root_dir).join(".hakanaignore");
+
+        if !ignore_file_path.exists() {
+            return Ok(());
+        }
+
+        let root_dir = glob::Pattern::escape(&self.root_dir);
+
+        for line in fs::read_to_string(ignore_file_path)?.lines() {
+            let line = line.trim();
+
+            // negated patterns aren't supported
+            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+
+            let pattern = line.trim_end_matches('/');
+
+            // as in .gitignore, patterns containing a slash are relative to the root,
+            // and anything else can match at any depth
+            let pattern = if let Some(anchored_pattern) = pattern.strip_prefix('/') {
+                format!("{}/{}", root_dir, anchored_pattern)
+            } else if pattern.contains('/') {
+                format!("{}/{}", root_dir, pattern)
+            } else {
+                format!("{}/**/{}", root_dir, pattern)
+            };
+
+            // a pattern can also name a directory, in which case everything inside it is ignored
+            self.hakana_ignore_patterns
+                .push(glob::Pattern::new(&format!("{}/**", pattern))?);
+            self.hakana_ignore_patterns
+                .push(glob::Pattern::new(&pattern)?);
+        }
 
         Ok(())
     }
 
-    pub fn can_add_issue(&self, issue: &Issue) -> bool {
+    /// Reads hakana.json files from directories below each root. Each one can list issue
+    /// kinds under enable_issues and disable_issues, which apply to every file below that
+    /// directory; any other settings in the file are ignored.
+    ///
+    /// For a given file and issue kind, the nearest overlay above the file that mentions the
+    /// kind decides whether it's reported, and disable_issues wins if an overlay lists the
+    /// kind under both. When no overlay mentions the kind the root config's allowed_issues
+    /// applies as usual. Files matched by ignore_issue_files or .hakanaignore are never
+    /// reported on, whatever the overlays say.
+    pub fn update_from_overlay_files(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut overlay_paths = vec![];
+
+        for root_dir in &self.root_dirs {
+            self.find_overlay_files(Path::new(root_dir), &mut overlay_paths);
+        }
+
+        for overlay_path in overlay_paths {
+            let json_overlay = json_config::read_overlay_from_file(&overlay_path)?;
+
+            let parse_issue_kinds = |kinds: Vec<String>| {
+                kinds
+                    .into_iter()
+                    .map(|kind| {
+                        IssueKind::from_str_custom(&kind, &self.all_custom_issues).map_err(|_| {
+                            format!("Unknown issue kind {} in {}", kind, overlay_path.display())
+                        })
+                    })
+                    .collect::<Result<FxHashSet<_>, _>>()
+            };
+
+            let overlay = ConfigOverlay {
+                enabled_issues: parse_issue_kinds(json_overlay.enable_issues)?,
+                disabled_issues: parse_issue_kinds(json_overlay.disable_issues)?,
+            };
+
+            let overlay_dir = overlay_path.parent().unwrap().to_string_lossy().to_string();
+
+            self.directory_overlays.insert(overlay_dir, overlay);
+        }
+
+        Ok(())
+    }
+
+    // skips the same directories as the scan walk: hidden ones, ones that are ignored,
+    // and ones that can't be read
+    fn find_overlay_files(&self, dir: &Path, overlay_paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut entries = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for path in entries {
+            if !path.is_dir()
+                || path
+                    .file_name()
+                    .map_or(true, |name| name.to_string_lossy().starts_with('.'))
+                || self.ignore_files.iter().any(|ignore_file| {
+                    ignore_file
+                        .strip_suffix("/**")
+                        .map_or(false, |ignore_dir| path == Path::new(ignore_dir))
+                })
+            {
+                continue;
+            }
+
+            let overlay_path = path.join("hakana.json");
+
+            if overlay_path.is_file() {
+                overlay_paths.push(overlay_path);
+            }
+
+            self.find_overlay_files(&path, overlay_paths);
+        }
+    }
+
+    pub fn can_add_issue(&self, issue: &Issue, file: &str) -> bool {
+        if let Some(allowed) = self.get_overlay_issue_setting(&issue.kind, file) {
+            return allowed;
+        }
+
         if let Some(issue_filter) = &self.allowed_issues {
             if !issue_filter.contains(&issue.kind) {
                 return false;
@@ -182,6 +444,26 @@ impl Config {
         true
     }
 
+    /// Returns the most deeply nested root containing the given path, or the main root if
+    /// none of them do.
+    pub fn get_root_dir_for_path(&self, path: &str) -> &str {
+        self.root_dirs
+            .iter()
+            .filter(|root_dir| Path::new(path).starts_with(root_dir))
+            .max_by_key(|root_dir| root_dir.len())
+            .unwrap_or(&self.root_dir)
+    }
+
+    /// The severity of an issue kind, used for editor diagnostics, SARIF output and --fail-on.
+    /// Kinds without an entry in issue_severities use their built-in severity.
+    pub fn get_issue_severity(&self, kind: &IssueKind) -> IssueSeverity {
+        if let Some(severity) = self.issue_severities.get(kind) {
+            *severity
+        } else {
+            kind.get_severity()
+        }
+    }
+
     pub fn allow_issues_in_file(&self, file: &str) -> bool {
         for ignore_pattern in &self.ignore_all_issues_in_patterns {
             if ignore_pattern.matches(file) {
@@ -189,7 +471,29 @@ impl Config {
             }
         }
 
-        true
+        !self.is_in_hakana_ignore(file)
+    }
+
+    /// As in .gitignore, wildcards other than ** don't match a path separator.
+    pub fn is_in_hakana_ignore(&self, file: &str) -> bool {
+        let match_options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+
+        self.hakana_ignore_patterns
+            .iter()
+            .any(|pattern| pattern.matches_with(file, match_options))
+    }
+
+    /// Opt-in issue kinds are only reported when allowed_issues lists them, or when an
+    /// overlay enables them for the file.
+    pub fn allow_opt_in_issue_kind_in_file(&self, issue_kind: &IssueKind, file: &str) -> bool {
+        self.get_overlay_issue_setting(issue_kind, file) == Some(true)
+            || self
+                .allowed_issues
+                .as_ref()
+                .map_or(false, |allowed_issues| allowed_issues.contains(issue_kind))
     }
 
     pub fn allow_issue_kind_in_file(&self, issue_kind: &IssueKind, file: &str) -> bool {
@@ -201,7 +505,64 @@ impl Config {
             }
         }
 
-        true
+        self.get_overlay_issue_setting(issue_kind, file) != Some(false)
+    }
+
+    /// Whether the nearest overlay above the file turns the issue kind on or off, if any does.
+    pub fn get_overlay_issue_setting(&self, issue_kind: &IssueKind, file: &str) -> Option<bool> {
+        if self.directory_overlays.is_empty() {
+            return None;
+        }
+
+        Path::new(file).ancestors().skip(1).find_map(|dir| {
+            let overlay = self.directory_overlays.get(dir.to_str()?)?;
+
+            if overlay.disabled_issues.contains(issue_kind) {
+                Some(false)
+            } else if overlay.enabled_issues.contains(issue_kind) {
+                Some(true)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The root config's allowed_issues, adjusted by any overlays above the file.
+    pub fn get_allowed_issues_for_file(&self, file: &str) -> Option<FxHashSet<IssueKind>> {
+        let mut allowed_issues = self.allowed_issues.clone()?;
+
+        if self.directory_overlays.is_empty() {
+            return Some(allowed_issues);
+        }
+
+        let mut overlays = Path::new(file)
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| self.directory_overlays.get(dir.to_str()?))
+            .collect::<Vec<_>>();
+
+        // apply the outermost overlay first, so nearer ones take precedence
+        overlays.reverse();
+
+        for overlay in overlays {
+            allowed_issues.extend(overlay.enabled_issues.iter().cloned());
+
+            for issue_kind in &overlay.disabled_issues {
+                allowed_issues.remove(issue_kind);
+            }
+        }
+
+        Some(allowed_issues)
+    }
+
+    pub fn is_production_file(&self, file: &str) -> bool {
+        self.include_test_files || !self.test_files.iter().any(|pattern| pattern.matches(file))
+    }
+
+    pub fn analyze_hhi_signatures_in_file(&self, file: &str) -> bool {
+        self.analyze_hhi_signatures
+            .iter()
+            .any(|pattern| pattern.matches(file))
     }
 
     pub fn allow_taints_in_file(&self, file: &str) -> bool {
@@ -246,3 +607,36 @@ impl Config {
         true
     }
 }
+
+// hakana.json takes precedence over hakana.toml when both exist
+pub fn get_default_config_path(root_dir: &str) -> PathBuf {
+    let json_config_path = Path::new(root_dir).join("hakana.json");
+
+    if !json_config_path.exists() {
+        let toml_config_path = Path::new(root_dir).join("hakana.toml");
+
+        if toml_config_path.exists() {
+            return toml_config_path;
+        }
+    }
+
+    json_config_path
+}
+
+// parses an HHVM version like "4.153" (or "4.153.2", ignoring the patch number)
+pub fn parse_hack_version(version: &str) -> Option<(u16, u16)> {
+    let mut parts = version.trim().split('.');
+
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    if let Some(patch) = parts.next() {
+        patch.parse::<u16>().ok()?;
+    }
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((major, minor))
+}
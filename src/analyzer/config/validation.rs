@@ -0,0 +1,156 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use hakana_code_info::issue::{IssueKind, IssueSeverity};
+use hakana_code_info::taint::SourceType;
+use rustc_hash::FxHashSet;
+
+use super::json_config::{self, JsonConfig};
+
+/// Checks a config file for mistakes that would otherwise be ignored or cause a panic
+/// once analysis starts: unknown issue kinds, severities and taint sources, globs that
+/// don't compile, and paths that don't exist. Paths are resolved relative to cwd, as
+/// they are when the config is loaded.
+pub fn get_config_problems(
+    cwd: &str,
+    config_path: &Path,
+    all_custom_issues: &FxHashSet<String>,
+) -> Vec<String> {
+    let json_config = match json_config::read_from_file(config_path) {
+        Ok(json_config) => json_config,
+        Err(error) => {
+            return vec![format!(
+                "Could not read {}: {}",
+                config_path.display(),
+                error
+            )];
+        }
+    };
+
+    let mut problems = vec![];
+
+    check_issue_kinds(&json_config, all_custom_issues, &mut problems);
+
+    for (kind, severity) in &json_config.issue_severities {
+        if IssueSeverity::from_str(severity).is_err() {
+            problems.push(format!(
+                "issue_severities: unknown severity {} for {}",
+                severity, kind
+            ));
+        }
+    }
+
+    if let Some(hack_version) = &json_config.hack_version {
+        if super::parse_hack_version(hack_version).is_none() {
+            problems.push(format!(
+                "hack_version: {} is not a version like 4.153",
+                hack_version
+            ));
+        }
+    }
+
+    for source_type in json_config.security_analysis.max_depth_by_source.keys() {
+        if SourceType::from_str(source_type).is_err() {
+            problems.push(format!(
+                "security_analysis.max_depth_by_source: unknown taint source type {}",
+                source_type
+            ));
+        }
+    }
+
+    check_patterns(
+        cwd,
+        "ignore_files",
+        &json_config.ignore_files,
+        &mut problems,
+    );
+    check_patterns(cwd, "test_files", &json_config.test_files, &mut problems);
+    check_patterns(
+        cwd,
+        "analyze_hhi_signatures",
+        &json_config.analyze_hhi_signatures,
+        &mut problems,
+    );
+
+    for (kind, patterns) in &json_config.ignore_issue_files {
+        check_patterns(
+            cwd,
+            &format!("ignore_issue_files.{}", kind),
+            patterns,
+            &mut problems,
+        );
+    }
+
+    check_patterns(
+        cwd,
+        "security_analysis.ignore_files",
+        &json_config.security_analysis.ignore_files,
+        &mut problems,
+    );
+
+    for (key, patterns) in &json_config.security_analysis.ignore_sink_files {
+        check_patterns(
+            cwd,
+            &format!("security_analysis.ignore_sink_files.{}", key),
+            patterns,
+            &mut problems,
+        );
+    }
+
+    problems.sort();
+    problems
+}
+
+fn check_issue_kinds(
+    json_config: &JsonConfig,
+    all_custom_issues: &FxHashSet<String>,
+    problems: &mut Vec<String>,
+) {
+    let issue_kinds = json_config
+        .allowed_issues
+        .iter()
+        .map(|kind| ("allowed_issues", kind))
+        .chain(
+            json_config
+                .ignore_issue_files
+                .keys()
+                .filter(|kind| *kind != "*")
+                .map(|kind| ("ignore_issue_files", kind)),
+        )
+        .chain(
+            json_config
+                .issue_severities
+                .keys()
+                .map(|kind| ("issue_severities", kind)),
+        );
+
+    for (field, kind) in issue_kinds {
+        if IssueKind::from_str_custom(kind, all_custom_issues).is_err() {
+            problems.push(format!("{}: unknown issue kind {}", field, kind));
+        }
+    }
+}
+
+fn check_patterns(cwd: &str, field: &str, patterns: &[String], problems: &mut Vec<String>) {
+    for pattern in patterns {
+        if let Err(error) = glob::Pattern::new(&format!("{}/{}", cwd, pattern)) {
+            problems.push(format!("{}: invalid glob {} ({})", field, pattern, error));
+            continue;
+        }
+
+        // everything before the first wildcard is a directory or file that should exist
+        let literal_prefix = &pattern[..pattern.find(['*', '?', '[']).unwrap_or(pattern.len())];
+        let base_path = if literal_prefix.len() < pattern.len() {
+            &literal_prefix[..literal_prefix.rfind('/').unwrap_or(0)]
+        } else {
+            literal_prefix
+        };
+
+        if !Path::new(&format!("{}/{}", cwd, base_path)).exists() {
+            problems.push(format!(
+                "{}: {} matches nothing, as {} does not exist",
+                field, pattern, base_path
+            ));
+        }
+    }
+}
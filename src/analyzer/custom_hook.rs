@@ -64,6 +64,11 @@ pub trait InternalHook {
         None
     }
 
+    // Shown next to the migration or codegen name when listing hooks with --list
+    fn get_description(&self) -> Option<&str> {
+        None
+    }
+
     // This hook is run after analysing every top-level definition (class, function etc)
     #[allow(unused_variables)]
     fn after_def_analysis(
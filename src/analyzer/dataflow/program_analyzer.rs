@@ -125,8 +125,15 @@ fn find_paths_to_sinks(
         seen_sources.insert(source.get_unique_source_id(interner));
     }
 
+    // sources with their own max_depth may need to run past the global limit
+    let max_depth = config
+        .security_config
+        .max_depth_by_source
+        .values()
+        .fold(config.security_config.max_depth, |a, b| a.max(*b));
+
     if !match_sinks || !graph.sinks.is_empty() {
-        for i in 0..config.security_config.max_depth {
+        for i in 0..max_depth {
             if !sources.is_empty() {
                 let now = if matches!(
                     logger.get_verbosity(),
@@ -152,6 +159,9 @@ fn find_paths_to_sinks(
                     };
                     let source_taints = source.taint_sinks.clone();
                     let source_id = source.id.clone();
+                    let source_max_depth = config
+                        .security_config
+                        .get_max_depth(source.get_taint_sources());
 
                     let generated_sources = get_specialized_sources(graph, source);
                     actual_source_count += generated_sources.len();
@@ -165,7 +175,7 @@ fn find_paths_to_sinks(
                             &mut seen_sources,
                             &mut file_nodes,
                             new_issues,
-                            i == config.security_config.max_depth - 1,
+                            i + 1 >= source_max_depth,
                             match_sinks,
                             interner,
                         ))
@@ -297,12 +307,15 @@ fn get_child_nodes(
                             target_id,
                             generated_source.get_trace(interner, &config.root_dir)
                         );
-                        new_issues.push(Issue::new(
+                        let mut issue = Issue::new(
                             IssueKind::TaintedData(Box::new(t.clone())),
                             message,
                             **generated_source.pos.as_ref().unwrap(),
                             &None,
-                        ));
+                        );
+                        issue.taint_trace =
+                            Some(generated_source.get_trace_steps(interner, &config.root_dir));
+                        new_issues.push(issue);
                     }
                 }
             }
@@ -375,12 +388,15 @@ fn get_child_nodes(
                                 target_id,
                                 generated_source.get_trace(interner, &config.root_dir)
                             );
-                            new_issues.push(Issue::new(
+                            let mut issue = Issue::new(
                                 IssueKind::TaintedData(Box::new(t.clone())),
                                 message,
                                 **generated_source.pos.as_ref().unwrap(),
                                 &None,
-                            ));
+                            );
+                            issue.taint_trace =
+                                Some(generated_source.get_trace_steps(interner, &config.root_dir));
+                            new_issues.push(issue);
                         }
                     }
                 }
@@ -439,12 +455,16 @@ fn get_child_nodes(
                                         matching_sink.get_error_message(),
                                         new_destination.get_trace(interner, &config.root_dir)
                                     );
-                                    new_issues.push(Issue::new(
+                                    let mut issue = Issue::new(
                                         IssueKind::TaintedData(Box::new(matching_sink.clone())),
                                         message,
                                         *sink_pos,
                                         &None,
-                                    ));
+                                    );
+                                    issue.taint_trace = Some(
+                                        new_destination.get_trace_steps(interner, &config.root_dir),
+                                    );
+                                    new_issues.push(issue);
                                 }
                             }
                         }
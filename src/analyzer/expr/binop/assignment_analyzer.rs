@@ -686,6 +686,7 @@ fn handle_assignment_with_boolean_logic(
                 )],
                 right_clauses.into_iter().map(|v| (*v).clone()).collect(),
                 cond_object_id,
+                statements_analyzer.get_config().algebra_clause_budget,
             ) {
             assignment_clauses.into_iter().map(Rc::new).collect()
         } else {
@@ -3,6 +3,7 @@ use std::rc::Rc;
 
 use crate::reconciler;
 use crate::scope::BlockContext;
+use crate::scope_analyzer::ScopeAnalyzer;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt::if_conditional_analyzer;
 use crate::stmt::if_conditional_analyzer::handle_paradoxical_condition;
@@ -124,26 +125,28 @@ pub(crate) fn analyze<'expr>(
         return Err(AnalysisError::UserError);
     };
 
-    let mut negated_left_clauses =
-        if let Ok(good_clauses) = hakana_algebra::negate_formula(left_clauses) {
-            good_clauses
-        } else if let Ok(good_clauses) = formula_generator::get_formula(
-            left_cond_id,
-            left_cond_id,
-            &aast::Expr(
-                (),
-                left.pos().clone(),
-                aast::Expr_::Unop(Box::new((Uop::Unot, left.clone()))),
-            ),
-            &assertion_context,
-            analysis_data,
-            false,
-            false,
-        ) {
-            good_clauses
-        } else {
-            return Err(AnalysisError::UserError);
-        };
+    let mut negated_left_clauses = if let Ok(good_clauses) = hakana_algebra::negate_formula(
+        left_clauses,
+        statements_analyzer.get_config().algebra_complexity_budget,
+    ) {
+        good_clauses
+    } else if let Ok(good_clauses) = formula_generator::get_formula(
+        left_cond_id,
+        left_cond_id,
+        &aast::Expr(
+            (),
+            left.pos().clone(),
+            aast::Expr_::Unop(Box::new((Uop::Unot, left.clone()))),
+        ),
+        &assertion_context,
+        analysis_data,
+        false,
+        false,
+    ) {
+        good_clauses
+    } else {
+        return Err(AnalysisError::UserError);
+    };
 
     if !left_context.reconciled_expression_clauses.is_empty() {
         let left_reconciled_clauses_hashed = left_context
@@ -5,10 +5,14 @@ use crate::scope_analyzer::ScopeAnalyzer;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
 
+use hakana_code_info::codebase_info::symbols::SymbolKind;
+use hakana_code_info::codebase_info::CodebaseInfo;
 use hakana_code_info::issue::{Issue, IssueKind};
 use hakana_code_info::t_atomic::TAtomic;
+use hakana_code_info::t_union::TUnion;
 use hakana_code_info::ttype::comparison::union_type_comparator;
 use hakana_code_info::ttype::{get_bool, get_int};
+use hakana_str::Interner;
 use oxidized::pos::Pos;
 use oxidized::{aast, ast};
 
@@ -148,6 +152,29 @@ pub(crate) fn analyze(
                         statements_analyzer.get_config(),
                         statements_analyzer.get_file_path_actual(),
                     );
+                } else if matches!(
+                    expr.0,
+                    oxidized::ast_defs::Bop::Eqeq | oxidized::ast_defs::Bop::Diff
+                ) && are_loosely_incomparable(
+                    statements_analyzer.codebase,
+                    statements_analyzer.interner,
+                    lhs_type,
+                    rhs_type,
+                ) {
+                    analysis_data.maybe_add_issue(
+                        Issue::new(
+                            IssueKind::SuspiciousLooseEquality,
+                            format!(
+                                "Loose equality between {} and {} is never meaningful",
+                                lhs_type.get_id(Some(interner)),
+                                rhs_type.get_id(Some(interner)),
+                            ),
+                            statements_analyzer.get_hpos(pos),
+                            &context.function_context.calling_functionlike_id,
+                        ),
+                        statements_analyzer.get_config(),
+                        statements_analyzer.get_file_path_actual(),
+                    );
                 }
             }
 
@@ -215,3 +242,87 @@ pub(crate) fn analyze(
 fn is_resolvable(expr: &aast::Expr<(), ()>) -> bool {
     matches!(expr.2, aast::Expr_::Lvar(_) | aast::Expr_::ObjGet(_))
 }
+
+#[derive(PartialEq)]
+enum LooseEqualityCategory {
+    Scalar,
+    Container,
+    Object,
+}
+
+// Only true when every type on one side is in a different category to every type on the
+// other. Null, bools and anything that isn't fully known are left alone, since loose
+// equality between those and almost anything else can be true.
+fn are_loosely_incomparable(
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    lhs_type: &TUnion,
+    rhs_type: &TUnion,
+) -> bool {
+    let (Some(lhs_categories), Some(rhs_categories)) = (
+        get_loose_equality_categories(codebase, interner, lhs_type),
+        get_loose_equality_categories(codebase, interner, rhs_type),
+    ) else {
+        return false;
+    };
+
+    !lhs_categories
+        .iter()
+        .any(|category| rhs_categories.contains(category))
+}
+
+fn get_loose_equality_categories(
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    union: &TUnion,
+) -> Option<Vec<LooseEqualityCategory>> {
+    union
+        .types
+        .iter()
+        .map(|atomic| get_loose_equality_category(codebase, interner, atomic))
+        .collect()
+}
+
+fn get_loose_equality_category(
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    atomic: &TAtomic,
+) -> Option<LooseEqualityCategory> {
+    match atomic {
+        TAtomic::TInt
+        | TAtomic::TLiteralInt { .. }
+        | TAtomic::TFloat
+        | TAtomic::TLiteralFloat { .. }
+        | TAtomic::TNum
+        | TAtomic::TString
+        | TAtomic::TLiteralString { .. }
+        | TAtomic::TStringWithFlags(..)
+        | TAtomic::TArraykey { .. }
+        | TAtomic::TEnum { .. }
+        | TAtomic::TEnumLiteralCase { .. } => Some(LooseEqualityCategory::Scalar),
+        TAtomic::TVec { .. } | TAtomic::TDict(_) | TAtomic::TKeyset { .. } => {
+            Some(LooseEqualityCategory::Container)
+        }
+        // interfaces like Stringish can also be satisfied by scalars, and objects with a
+        // __toString method compare loosely equal to strings
+        TAtomic::TNamedObject {
+            name,
+            extra_types: None,
+            ..
+        } => match codebase.classlike_infos.get(name) {
+            Some(classlike_info)
+                if matches!(classlike_info.kind, SymbolKind::Class)
+                    && !interner.get("__toString").map_or(false, |to_string_id| {
+                        classlike_info
+                            .declaring_method_ids
+                            .contains_key(&to_string_id)
+                    }) =>
+            {
+                Some(LooseEqualityCategory::Object)
+            }
+            _ => None,
+        },
+        TAtomic::TClosure(_) => Some(LooseEqualityCategory::Object),
+        _ => None,
+    }
+}
@@ -335,11 +335,12 @@ pub(crate) fn verify_type(
                             IssueKind::MixedArgument
                         },
                         format!(
-                            "Argument {} of {} expects {}, {} provided",
+                            "Argument {} of {} expects {}, {} provided{}",
                             (argument_offset + 1),
                             functionlike_id.to_string(statements_analyzer.interner),
                             param_type.get_id(Some(statements_analyzer.interner)),
                             input_type.get_id(Some(statements_analyzer.interner)),
+                            get_param_declaration(statements_analyzer, function_param),
                         ),
                         statements_analyzer.get_hpos(input_expr.pos()),
                         &context.function_context.calling_functionlike_id,
@@ -495,6 +496,40 @@ pub(crate) fn verify_type(
     }
 }
 
+// Where a user-defined parameter is declared, so a mixed argument can be traced to the
+// signature that expects something more specific. Only shown when the config asks for it,
+// and never for builtin parameters.
+fn get_param_declaration(
+    statements_analyzer: &StatementsAnalyzer,
+    function_param: &FunctionLikeParameter,
+) -> String {
+    let config = statements_analyzer.get_config();
+
+    if !config.show_mixed_argument_declarations {
+        return "".to_string();
+    }
+
+    let param_pos = function_param
+        .signature_type_location
+        .unwrap_or(function_param.location);
+    let param_file_path = statements_analyzer.interner.lookup(&param_pos.file_path.0);
+
+    if param_file_path.ends_with(".hhi") {
+        return "".to_string();
+    }
+
+    format!(
+        " (parameter {} declared at {}:{}:{})",
+        statements_analyzer.interner.lookup(&function_param.name.0),
+        param_pos.file_path.get_relative_path(
+            statements_analyzer.interner,
+            config.get_root_dir_for_path(param_file_path)
+        ),
+        param_pos.start_line,
+        param_pos.start_column
+    )
+}
+
 fn add_dataflow(
     statements_analyzer: &StatementsAnalyzer,
     functionlike_id: &FunctionLikeIdentifier,
@@ -136,6 +136,10 @@ pub(crate) fn analyze(
             name,
             false,
         );
+
+        analysis_data
+            .symbol_references
+            .add_reference_to_functionlike_call(&context.function_context, (name, StrId::EMPTY));
     }
 
     let mut template_result = TemplateResult::new(IndexMap::new(), IndexMap::new());
@@ -210,6 +214,31 @@ pub(crate) fn analyze(
         );
     }
 
+    if let (Some(min_hack_version), Some(hack_version)) = (
+        function_storage.min_hack_version,
+        statements_analyzer.get_config().hack_version,
+    ) {
+        if hack_version < min_hack_version {
+            analysis_data.maybe_add_issue(
+                Issue::new(
+                    IssueKind::UnavailableBuiltin,
+                    format!(
+                        "Function {} was added in HHVM {}.{}, but the configured hack_version is {}.{}",
+                        statements_analyzer.interner.lookup(&name),
+                        min_hack_version.0,
+                        min_hack_version.1,
+                        hack_version.0,
+                        hack_version.1
+                    ),
+                    statements_analyzer.get_hpos(pos),
+                    &context.function_context.calling_functionlike_id,
+                ),
+                statements_analyzer.get_config(),
+                statements_analyzer.get_file_path_actual(),
+            );
+        }
+    }
+
     if !function_storage.is_production_code
         && function_storage.user_defined
         && context.function_context.is_production(codebase)
@@ -378,6 +378,15 @@ pub(crate) fn check_method_args(
 ) -> Result<(), AnalysisError> {
     let codebase = statements_analyzer.codebase;
 
+    let declaring_method_id = codebase.get_declaring_method_id(method_id);
+
+    analysis_data
+        .symbol_references
+        .add_reference_to_functionlike_call(
+            &context.function_context,
+            (declaring_method_id.0, declaring_method_id.1),
+        );
+
     let functionlike_id = FunctionLikeIdentifier::Method(method_id.0, method_id.1);
 
     arguments_analyzer::check_arguments_match(
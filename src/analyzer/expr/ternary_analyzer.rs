@@ -5,6 +5,7 @@ use crate::function_analysis_data::FunctionAnalysisData;
 use crate::reconciler::{self, assertion_reconciler};
 use crate::scope::if_scope::IfScope;
 use crate::scope::{var_has_root, BlockContext};
+use crate::scope_analyzer::ScopeAnalyzer;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt::if_conditional_analyzer::{self, add_branch_dataflow};
 use crate::stmt_analyzer::AnalysisError;
@@ -160,7 +161,10 @@ pub(crate) fn analyze(
 
     if_scope.reasonable_clauses = ternary_clauses.into_iter().map(Rc::new).collect();
 
-    if let Ok(negated_if_clauses) = hakana_algebra::negate_formula(if_clauses) {
+    if let Ok(negated_if_clauses) = hakana_algebra::negate_formula(
+        if_clauses,
+        statements_analyzer.get_config().algebra_complexity_budget,
+    ) {
         if_scope.negated_clauses = negated_if_clauses;
     } else {
         if_scope.negated_clauses = formula_generator::get_formula(
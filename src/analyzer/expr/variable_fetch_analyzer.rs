@@ -1,7 +1,7 @@
 use crate::{
     function_analysis_data::FunctionAnalysisData, scope::BlockContext,
     scope_analyzer::ScopeAnalyzer, statements_analyzer::StatementsAnalyzer,
-    stmt_analyzer::AnalysisError,
+    stmt::shadowed_variable_analyzer, stmt_analyzer::AnalysisError,
 };
 use hakana_code_info::ttype::get_mixed_any;
 use hakana_code_info::{
@@ -44,7 +44,17 @@ pub(crate) fn analyze(
             EFFECT_READ_GLOBALS,
         );
     } else if let Some(var_type) = context.locals.get(&lid.1 .1) {
-        if var_type.parent_nodes.len() > 1
+        let is_shadowed_variable = !analysis_data.shadowed_variables.is_empty()
+            && shadowed_variable_analyzer::check_variable_read(
+                statements_analyzer,
+                var_type,
+                pos,
+                analysis_data,
+                context,
+            );
+
+        if !is_shadowed_variable
+            && var_type.parent_nodes.len() > 1
             && !context.inside_loop_exprs
             && context.for_loop_init_bounds.0 == 0
             && !context.inside_assignment_op
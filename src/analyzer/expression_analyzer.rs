@@ -34,8 +34,8 @@ use hakana_code_info::t_atomic::TAtomic;
 use hakana_code_info::t_union::TUnion;
 use hakana_code_info::ttype::type_expander::get_closure_from_id;
 use hakana_code_info::ttype::{
-    extend_dataflow_uniquely, get_bool, get_false, get_float, get_int, get_literal_int,
-    get_literal_string, get_mixed_any, get_null, get_true, wrap_atomic,
+    extend_dataflow_uniquely, get_bool, get_false, get_float, get_int, get_literal_float,
+    get_literal_int, get_literal_string, get_mixed_any, get_null, get_true, wrap_atomic,
 };
 use hakana_code_info::EFFECT_IMPURE;
 use hakana_reflector::simple_type_inferer::int_from_string;
@@ -110,10 +110,14 @@ pub(crate) fn analyze(
                 Rc::new(get_literal_string(value.to_string())),
             );
         }
-        aast::Expr_::Float(_) => {
+        aast::Expr_::Float(value) => {
             analysis_data.expr_types.insert(
                 (expr.1.start_offset() as u32, expr.1.end_offset() as u32),
-                Rc::new(get_float()),
+                Rc::new(if let Ok(value) = value.parse::<f64>() {
+                    get_literal_float(value)
+                } else {
+                    get_float()
+                }),
             );
         }
         aast::Expr_::Is(boxed) => {
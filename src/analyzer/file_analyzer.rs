@@ -61,8 +61,14 @@ impl<'a> FileAnalyzer<'a> {
             None,
         );
 
-        if let Some(issue_filter) = &self.get_config().allowed_issues {
-            analysis_data.issue_filter = Some(issue_filter.clone());
+        analysis_data.issue_filter = self
+            .get_config()
+            .get_allowed_issues_for_file(&self.file_source.file_path_actual);
+
+        if let Some(file_info) = self.codebase.files.get(&self.file_source.file_path) {
+            analysis_data
+                .line_ignores
+                .clone_from(&file_info.line_ignores);
         }
 
         let unnamespaced_file_analyzer = self.clone();
@@ -111,10 +117,7 @@ impl<'a> FileAnalyzer<'a> {
         update_analysis_result_with_tast(
             analysis_data,
             analysis_result,
-            &statements_analyzer
-                .file_analyzer
-                .file_source
-                .file_path,
+            &statements_analyzer,
             false,
         );
 
@@ -218,6 +218,7 @@ fn handle_or(
         left_clauses.unwrap(),
         right_clauses.unwrap(),
         conditional_object_id,
+        assertion_context.config.algebra_clause_budget,
     )
 }
 
@@ -337,7 +338,10 @@ fn handle_uop(
             return Some(original_clauses);
         }
 
-        return Some(hakana_algebra::negate_formula(original_clauses.unwrap()));
+        return Some(hakana_algebra::negate_formula(
+            original_clauses.unwrap(),
+            assertion_context.config.algebra_complexity_budget,
+        ));
     }
 
     None
@@ -1,3 +1,4 @@
+use crate::stmt::shadowed_variable_analyzer::ShadowedVariable;
 use crate::{config::Config, scope::CaseScope};
 use hakana_code_info::analysis_result::Replacement;
 use hakana_code_info::code_location::StmtStart;
@@ -41,7 +42,10 @@ pub struct FunctionAnalysisData {
     hh_fixmes: BTreeMap<isize, BTreeMap<isize, Pos>>,
     pub hakana_fixme_or_ignores: BTreeMap<u32, Vec<(IssueKind, (u32, u32, u32, u32, bool))>>,
     pub matched_ignore_positions: FxHashSet<(u32, u32)>,
+    pub matched_hh_fixme_positions: FxHashSet<(u32, u32)>,
+    pub line_ignores: BTreeMap<u32, Vec<IssueKind>>,
     pub previously_used_fixme_positions: FxHashMap<(u32, u32), (u32, u32)>,
+    pub(crate) shadowed_variables: FxHashMap<(u32, u32), ShadowedVariable>,
     pub type_variable_bounds: FxHashMap<String, (Vec<TemplateBound>, Vec<TemplateBound>)>,
     pub migrate_function: Option<bool>,
     pub after_expr_hook_called: FxHashSet<(u32, u32)>,
@@ -87,6 +91,8 @@ impl FunctionAnalysisData {
                 .unwrap_or(get_hakana_fixmes_and_ignores(comments, all_custom_issues)),
             expr_fixme_positions: FxHashMap::default(),
             matched_ignore_positions: FxHashSet::default(),
+            matched_hh_fixme_positions: FxHashSet::default(),
+            line_ignores: BTreeMap::new(),
             issue_counts: FxHashMap::default(),
             type_variable_bounds: FxHashMap::default(),
             migrate_function: None,
@@ -94,6 +100,7 @@ impl FunctionAnalysisData {
             after_expr_hook_called: FxHashSet::default(),
             inside_await: false,
             previously_used_fixme_positions: FxHashMap::default(),
+            shadowed_variables: FxHashMap::default(),
         }
     }
 
@@ -112,6 +119,11 @@ impl FunctionAnalysisData {
             return;
         }
 
+        if issue.kind.is_opt_in() && !config.allow_opt_in_issue_kind_in_file(&issue.kind, file_path)
+        {
+            return;
+        }
+
         issue.insertion_start = if let Some(expr_fixme_position) = self
             .expr_fixme_positions
             .get(&(issue.pos.start_offset, issue.pos.end_offset))
@@ -138,30 +150,7 @@ impl FunctionAnalysisData {
         if let Some(insertion_start) = &issue.insertion_start {
             self.add_replacement(
                 (insertion_start.offset, insertion_start.offset),
-                Replacement::Substitute(
-                    format!(
-                        "/* HAKANA_FIXME[{}]{} */{}",
-                        issue.kind.to_string(),
-                        if let IssueKind::UnusedParameter
-                        | IssueKind::UnusedAssignment
-                        | IssueKind::UnusedAssignmentInClosure
-                        | IssueKind::UnusedAssignmentStatement
-                        | IssueKind::UnusedStatement
-                        | IssueKind::UnusedFunction
-                        | IssueKind::UnusedPrivateMethod = issue.kind
-                        {
-                            "".to_string()
-                        } else {
-                            " ".to_string() + &issue.description
-                        },
-                        if insertion_start.add_newline {
-                            "\n".to_string() + &"\t".repeat(insertion_start.column as usize)
-                        } else {
-                            " ".to_string()
-                        }
-                    )
-                    .to_string(),
-                ),
+                Replacement::Substitute(issue.get_fixme_text(insertion_start)),
             );
 
             true
@@ -202,6 +191,16 @@ impl FunctionAnalysisData {
             return false;
         }
 
+        if let Some(line_ignores) = self.line_ignores.get(&issue.pos.start_line) {
+            if line_ignores.contains(&issue.kind) {
+                if self.recorded_issues.is_empty() {
+                    *self.issue_counts.entry(issue.kind.clone()).or_insert(0) += 1;
+                }
+
+                return false;
+            }
+        }
+
         if let Some(recorded_issues) = self.recorded_issues.last_mut() {
             recorded_issues.push(issue.clone());
             return false;
@@ -262,139 +261,15 @@ impl FunctionAnalysisData {
                         continue;
                     }
                 }
-                if match *hack_error {
-                    // Unify error
-                    4110 => match &issue_kind {
-                        IssueKind::FalsableReturnStatement
-                        | IssueKind::FalseArgument
-                        | IssueKind::ImpossibleAssignment
-                        | IssueKind::InvalidArgument
-                        | IssueKind::InvalidReturnStatement
-                        | IssueKind::InvalidReturnType
-                        | IssueKind::InvalidReturnValue
-                        | IssueKind::LessSpecificArgument
-                        | IssueKind::LessSpecificNestedArgumentType
-                        | IssueKind::LessSpecificNestedReturnStatement
-                        | IssueKind::LessSpecificReturnStatement
-                        | IssueKind::MixedArgument
-                        | IssueKind::MixedArrayAccess
-                        | IssueKind::MixedArrayAssignment
-                        | IssueKind::MixedMethodCall
-                        | IssueKind::MixedReturnStatement
-                        | IssueKind::MixedPropertyAssignment
-                        | IssueKind::MixedPropertyTypeCoercion
-                        | IssueKind::PropertyTypeCoercion
-                        | IssueKind::NonNullableReturnType
-                        | IssueKind::NullablePropertyAssignment
-                        | IssueKind::NullableReturnStatement
-                        | IssueKind::NullableReturnValue
-                        | IssueKind::PossiblyFalseArgument
-                        | IssueKind::PossiblyInvalidArgument
-                        | IssueKind::InvalidPropertyAssignmentValue
-                        | IssueKind::LessSpecificNestedAnyReturnStatement
-                        | IssueKind::LessSpecificNestedAnyArgumentType => true,
-                        _ => false,
-                    },
-                    // type inference failed
-                    4297 => match &issue_kind {
-                        IssueKind::MixedAnyArgument
-                        | IssueKind::MixedAnyArrayAccess
-                        | IssueKind::MixedAnyArrayAssignment
-                        | IssueKind::MixedAnyArrayOffset
-                        | IssueKind::MixedAnyAssignment
-                        | IssueKind::MixedAnyMethodCall
-                        | IssueKind::MixedAnyPropertyAssignment
-                        | IssueKind::MixedAnyPropertyTypeCoercion
-                        | IssueKind::MixedAnyReturnStatement
-                        | IssueKind::MixedArgument
-                        | IssueKind::MixedArrayAccess
-                        | IssueKind::MixedArrayAssignment
-                        | IssueKind::MixedArrayOffset
-                        | IssueKind::MixedMethodCall
-                        | IssueKind::MixedPropertyAssignment
-                        | IssueKind::MixedPropertyTypeCoercion
-                        | IssueKind::MixedReturnStatement => true,
-                        _ => false,
-                    },
-                    // RequiredFieldIsOptional
-                    4163 => match &issue_kind {
-                        IssueKind::InvalidArgument
-                        | IssueKind::InvalidReturnStatement
-                        | IssueKind::InvalidReturnType
-                        | IssueKind::InvalidReturnValue
-                        | IssueKind::LessSpecificArgument
-                        | IssueKind::LessSpecificNestedArgumentType
-                        | IssueKind::LessSpecificNestedReturnStatement
-                        | IssueKind::LessSpecificReturnStatement
-                        | IssueKind::PropertyTypeCoercion
-                        | IssueKind::PossiblyInvalidArgument => true,
-                        _ => false,
-                    },
-                    4324 => match &issue_kind {
-                        IssueKind::InvalidArgument | IssueKind::PossiblyInvalidArgument => true,
-                        _ => false,
-                    },
-                    4063 => match &issue_kind {
-                        IssueKind::MixedArrayAccess | IssueKind::PossiblyNullArrayAccess => true,
-                        _ => false,
-                    },
-                    4064 => match &issue_kind {
-                        IssueKind::PossiblyNullPropertyFetch => true,
-                        _ => false,
-                    },
-                    4005 => match &issue_kind {
-                        IssueKind::MixedArrayAccess => true,
-                        _ => false,
-                    },
-                    2049 => match &issue_kind {
-                        IssueKind::NonExistentMethod
-                        | IssueKind::NonExistentFunction
-                        | IssueKind::NonExistentClass => true,
-                        _ => false,
-                    },
-                    // missing member
-                    4053 => match &issue_kind {
-                        IssueKind::NonExistentMethod | IssueKind::NonExistentXhpAttribute => true,
-                        _ => false,
-                    },
-                    // missing shape field or shape field unknown
-                    4057 | 4138 => match &issue_kind {
-                        IssueKind::InvalidArgument
-                        | IssueKind::PossiblyInvalidArgument
-                        | IssueKind::LessSpecificArgument
-                        | IssueKind::LessSpecificReturnStatement
-                        | IssueKind::InvalidReturnStatement => true,
-                        _ => false,
-                    },
-                    4062 => match &issue_kind {
-                        IssueKind::MixedMethodCall => true,
-                        _ => false,
-                    },
-                    4321 | 4108 => match &issue_kind {
-                        IssueKind::UndefinedStringArrayOffset
-                        | IssueKind::UndefinedIntArrayOffset
-                        | IssueKind::ImpossibleNonnullEntryCheck => true,
-                        _ => false,
-                    },
-                    4165 => match &issue_kind {
-                        IssueKind::PossiblyUndefinedStringArrayOffset
-                        | IssueKind::PossiblyUndefinedIntArrayOffset => true,
-                        _ => false,
-                    },
-                    4249 | 4250 => match &issue_kind {
-                        IssueKind::RedundantKeyCheck | IssueKind::ImpossibleKeyCheck => {
-                            return true
-                        }
-                        _ => false,
-                    },
-                    4107 => match &issue_kind {
-                        IssueKind::NonExistentFunction => true,
-                        _ => false,
-                    },
-                    _ => false,
-                } {
-                    self.previously_used_fixme_positions
-                        .insert(fixme_offsets, (issue_start_offset, issue_end_offset));
+                if get_hh_fixme_issue_kinds(*hack_error).contains(issue_kind) {
+                    self.matched_hh_fixme_positions.insert(fixme_offsets);
+
+                    // HH_FIXMEs for key checks can cover any number of issues
+                    if !matches!(*hack_error, 4249 | 4250) {
+                        self.previously_used_fixme_positions
+                            .insert(fixme_offsets, (issue_start_offset, issue_end_offset));
+                    }
+
                     return true;
                 }
             }
@@ -559,7 +434,14 @@ impl FunctionAnalysisData {
     }
 
     pub(crate) fn get_unused_hakana_fixme_positions(&self) -> Vec<(u32, u32, u32, u32, bool)> {
-        let mut unused_fixme_positions = vec![];
+        self.get_unused_hakana_fixmes()
+            .into_iter()
+            .map(|(_, position)| position)
+            .collect()
+    }
+
+    pub(crate) fn get_unused_hakana_fixmes(&self) -> Vec<(&IssueKind, (u32, u32, u32, u32, bool))> {
+        let mut unused_fixmes = vec![];
 
         for hakana_fixme_or_ignores in &self.hakana_fixme_or_ignores {
             for line_issue in hakana_fixme_or_ignores.1 {
@@ -567,12 +449,41 @@ impl FunctionAnalysisData {
                     .matched_ignore_positions
                     .contains(&(line_issue.1 .0, line_issue.1 .1))
                 {
-                    unused_fixme_positions.push(line_issue.1);
+                    unused_fixmes.push((&line_issue.0, line_issue.1));
+                }
+            }
+        }
+
+        unused_fixmes
+    }
+
+    // HH_FIXMEs between the given offsets that didn't suppress anything, skipping ones for
+    // Hack errors that Hakana never matches against its own issues
+    pub(crate) fn get_unused_hh_fixmes(
+        &self,
+        start_offset: u32,
+        end_offset: u32,
+    ) -> Vec<(isize, &Pos)> {
+        let mut unused_fixmes = vec![];
+
+        for fixmes in self.hh_fixmes.values() {
+            for (hack_error, fixme_pos) in fixmes {
+                let fixme_offsets = (
+                    fixme_pos.start_offset() as u32,
+                    fixme_pos.end_offset() as u32,
+                );
+
+                if fixme_offsets.0 >= start_offset
+                    && fixme_offsets.1 <= end_offset
+                    && !self.matched_hh_fixme_positions.contains(&fixme_offsets)
+                    && !get_hh_fixme_issue_kinds(*hack_error).is_empty()
+                {
+                    unused_fixmes.push((*hack_error, fixme_pos));
                 }
             }
         }
 
-        unused_fixme_positions
+        unused_fixmes
     }
 
     pub fn add_replacement(&mut self, offsets: (u32, u32), replacement: Replacement) -> bool {
@@ -642,3 +553,114 @@ fn get_hakana_fixmes_and_ignores(
     }
     hakana_fixme_or_ignores
 }
+
+// The issue kinds that an HH_FIXME for the given Hack error code can suppress
+pub(crate) fn get_hh_fixme_issue_kinds(hack_error: isize) -> Vec<IssueKind> {
+    match hack_error {
+        // Unify error
+        4110 => vec![
+            IssueKind::FalsableReturnStatement,
+            IssueKind::FalseArgument,
+            IssueKind::ImpossibleAssignment,
+            IssueKind::InvalidArgument,
+            IssueKind::InvalidReturnStatement,
+            IssueKind::InvalidReturnType,
+            IssueKind::InvalidReturnValue,
+            IssueKind::LessSpecificArgument,
+            IssueKind::LessSpecificNestedArgumentType,
+            IssueKind::LessSpecificNestedReturnStatement,
+            IssueKind::LessSpecificReturnStatement,
+            IssueKind::MixedArgument,
+            IssueKind::MixedArrayAccess,
+            IssueKind::MixedArrayAssignment,
+            IssueKind::MixedMethodCall,
+            IssueKind::MixedReturnStatement,
+            IssueKind::MixedPropertyAssignment,
+            IssueKind::MixedPropertyTypeCoercion,
+            IssueKind::PropertyTypeCoercion,
+            IssueKind::NonNullableReturnType,
+            IssueKind::NullablePropertyAssignment,
+            IssueKind::NullableReturnStatement,
+            IssueKind::NullableReturnValue,
+            IssueKind::PossiblyFalseArgument,
+            IssueKind::PossiblyInvalidArgument,
+            IssueKind::InvalidPropertyAssignmentValue,
+            IssueKind::LessSpecificNestedAnyReturnStatement,
+            IssueKind::LessSpecificNestedAnyArgumentType,
+        ],
+        // type inference failed
+        4297 => vec![
+            IssueKind::MixedAnyArgument,
+            IssueKind::MixedAnyArrayAccess,
+            IssueKind::MixedAnyArrayAssignment,
+            IssueKind::MixedAnyArrayOffset,
+            IssueKind::MixedAnyAssignment,
+            IssueKind::MixedAnyMethodCall,
+            IssueKind::MixedAnyPropertyAssignment,
+            IssueKind::MixedAnyPropertyTypeCoercion,
+            IssueKind::MixedAnyReturnStatement,
+            IssueKind::MixedArgument,
+            IssueKind::MixedArrayAccess,
+            IssueKind::MixedArrayAssignment,
+            IssueKind::MixedArrayOffset,
+            IssueKind::MixedMethodCall,
+            IssueKind::MixedPropertyAssignment,
+            IssueKind::MixedPropertyTypeCoercion,
+            IssueKind::MixedReturnStatement,
+        ],
+        // RequiredFieldIsOptional
+        4163 => vec![
+            IssueKind::InvalidArgument,
+            IssueKind::InvalidReturnStatement,
+            IssueKind::InvalidReturnType,
+            IssueKind::InvalidReturnValue,
+            IssueKind::LessSpecificArgument,
+            IssueKind::LessSpecificNestedArgumentType,
+            IssueKind::LessSpecificNestedReturnStatement,
+            IssueKind::LessSpecificReturnStatement,
+            IssueKind::PropertyTypeCoercion,
+            IssueKind::PossiblyInvalidArgument,
+        ],
+        4324 => vec![
+            IssueKind::InvalidArgument,
+            IssueKind::PossiblyInvalidArgument,
+        ],
+        4063 => vec![
+            IssueKind::MixedArrayAccess,
+            IssueKind::PossiblyNullArrayAccess,
+        ],
+        4064 => vec![IssueKind::PossiblyNullPropertyFetch],
+        4005 => vec![IssueKind::MixedArrayAccess],
+        2049 => vec![
+            IssueKind::NonExistentMethod,
+            IssueKind::NonExistentFunction,
+            IssueKind::NonExistentClass,
+        ],
+        // missing member
+        4053 => vec![
+            IssueKind::NonExistentMethod,
+            IssueKind::NonExistentXhpAttribute,
+        ],
+        // missing shape field or shape field unknown
+        4057 | 4138 => vec![
+            IssueKind::InvalidArgument,
+            IssueKind::PossiblyInvalidArgument,
+            IssueKind::LessSpecificArgument,
+            IssueKind::LessSpecificReturnStatement,
+            IssueKind::InvalidReturnStatement,
+        ],
+        4062 => vec![IssueKind::MixedMethodCall],
+        4321 | 4108 => vec![
+            IssueKind::UndefinedStringArrayOffset,
+            IssueKind::UndefinedIntArrayOffset,
+            IssueKind::ImpossibleNonnullEntryCheck,
+        ],
+        4165 => vec![
+            IssueKind::PossiblyUndefinedStringArrayOffset,
+            IssueKind::PossiblyUndefinedIntArrayOffset,
+        ],
+        4249 | 4250 => vec![IssueKind::RedundantKeyCheck, IssueKind::ImpossibleKeyCheck],
+        4107 => vec![IssueKind::NonExistentFunction],
+        _ => vec![],
+    }
+}
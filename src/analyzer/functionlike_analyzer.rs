@@ -12,10 +12,13 @@ use crate::scope_analyzer::ScopeAnalyzer;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt::return_analyzer::handle_inout_at_return;
 use crate::stmt_analyzer::AnalysisError;
-use crate::{file_analyzer::FileAnalyzer, function_analysis_data::FunctionAnalysisData};
+use crate::{
+    file_analyzer::FileAnalyzer,
+    function_analysis_data::{get_hh_fixme_issue_kinds, FunctionAnalysisData},
+};
 use hakana_code_info::analysis_result::{AnalysisResult, Replacement};
 use hakana_code_info::classlike_info::ClassLikeInfo;
-use hakana_code_info::code_location::{FilePath, HPos, StmtStart};
+use hakana_code_info::code_location::{HPos, StmtStart};
 use hakana_code_info::data_flow::graph::{DataFlowGraph, GraphKind};
 use hakana_code_info::data_flow::node::{
     DataFlowNode, DataFlowNodeId, DataFlowNodeKind, VariableSourceKind,
@@ -31,7 +34,8 @@ use hakana_code_info::t_union::TUnion;
 use hakana_code_info::ttype::comparison::type_comparison_result::TypeComparisonResult;
 use hakana_code_info::ttype::type_expander::{self, StaticClassType, TypeExpansionOptions};
 use hakana_code_info::ttype::{
-    add_optional_union_type, comparison, get_mixed_any, get_nothing, get_void, wrap_atomic,
+    add_optional_union_type, comparison, get_mixed_any, get_nothing, get_union_syntax_type,
+    get_void, wrap_atomic,
 };
 use hakana_str::{Interner, StrId};
 use itertools::Itertools;
@@ -78,6 +82,12 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             return Ok(());
         }
 
+        if let Some(analyze_symbol) = &self.file_analyzer.analysis_config.analyze_symbol {
+            if analyze_symbol != self.interner.lookup(&name) {
+                return Ok(());
+            }
+        }
+
         let function_storage = if let Some(f) = self
             .file_analyzer
             .codebase
@@ -232,6 +242,17 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             return Ok(());
         }
 
+        if let Some(analyze_symbol) = &self.file_analyzer.analysis_config.analyze_symbol {
+            if analyze_symbol.split_once("::")
+                != Some((
+                    self.interner.lookup(&classlike_storage.name),
+                    self.interner.lookup(&method_name),
+                ))
+            {
+                return Ok(());
+            }
+        }
+
         let functionlike_storage = if let Some(functionlike_storage) = codebase
             .functionlike_infos
             .get(&(classlike_storage.name, method_name))
@@ -480,8 +501,27 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             }
         }
 
-        if let Some(issue_filter) = &statements_analyzer.get_config().allowed_issues {
-            analysis_data.issue_filter = Some(issue_filter.clone());
+        analysis_data.issue_filter = statements_analyzer
+            .get_config()
+            .get_allowed_issues_for_file(statements_analyzer.get_file_path_actual());
+
+        if let Some(file_info) = statements_analyzer
+            .codebase
+            .files
+            .get(statements_analyzer.get_file_path())
+        {
+            analysis_data
+                .line_ignores
+                .clone_from(&file_info.line_ignores);
+        }
+
+        if !matches!(functionlike_id, FunctionLikeIdentifier::Closure(..)) {
+            report_missing_type_hints(
+                functionlike_id,
+                functionlike_storage,
+                &mut analysis_data,
+                statements_analyzer,
+            );
         }
 
         let mut completed_analysis = false;
@@ -632,6 +672,19 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             );
         }
 
+        if config.find_unused_suppressions
+            && !config.remove_fixmes
+            && parent_analysis_data.is_none()
+            && matches!(analysis_data.data_flow_graph.kind, GraphKind::FunctionBody)
+        {
+            report_unused_suppressions(
+                &mut analysis_data,
+                statements_analyzer,
+                &context.function_context.calling_functionlike_id,
+                functionlike_storage,
+            );
+        }
+
         if config.remove_fixmes && parent_analysis_data.is_none() {
             for unused_fixme_position in analysis_data.get_unused_hakana_fixme_positions() {
                 analysis_data.add_replacement(
@@ -830,6 +883,10 @@ impl<'a> FunctionLikeAnalyzer<'a> {
                 .matched_ignore_positions
                 .extend(analysis_data.matched_ignore_positions);
 
+            parent_analysis_data
+                .matched_hh_fixme_positions
+                .extend(analysis_data.matched_hh_fixme_positions);
+
             parent_analysis_data
                 .expr_effects
                 .extend(analysis_data.expr_effects);
@@ -897,10 +954,7 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             update_analysis_result_with_tast(
                 analysis_data,
                 analysis_result,
-                &statements_analyzer
-                    .file_analyzer
-                    .file_source
-                    .file_path,
+                statements_analyzer,
                 functionlike_storage.ignore_taint_path,
             );
         }
@@ -1462,12 +1516,147 @@ fn handle_unused_assignment(
     }
 }
 
+fn report_unused_suppressions(
+    analysis_data: &mut FunctionAnalysisData,
+    statements_analyzer: &StatementsAnalyzer,
+    calling_functionlike_id: &Option<FunctionLikeIdentifier>,
+    functionlike_storage: &FunctionLikeInfo,
+) {
+    let config = statements_analyzer.get_config();
+    let file_path = statements_analyzer.get_file_path_actual();
+
+    // skip suppressions for issues that could not have been emitted in this run
+    let could_emit_issue = |issue_kind: &IssueKind| {
+        !(matches!(issue_kind, IssueKind::UnusedSuppression)
+            || issue_kind.is_unused_definition()
+            || (issue_kind.is_unused_expression() && !config.find_unused_expressions)
+            || (issue_kind.is_mixed_issue() && config.ignore_mixed_issues)
+            || !config.allow_issue_kind_in_file(issue_kind, file_path))
+    };
+
+    let mut unused_suppressions = vec![];
+
+    for (issue_kind, (start_offset, ..)) in analysis_data.get_unused_hakana_fixmes() {
+        if !could_emit_issue(issue_kind) {
+            continue;
+        }
+
+        if let Some(comment) = statements_analyzer
+            .comments
+            .iter()
+            .find(|comment| comment.0.start_offset() as u32 == start_offset)
+        {
+            unused_suppressions.push((
+                issue_kind.to_string(),
+                statements_analyzer.get_hpos(&comment.0),
+            ));
+        }
+    }
+
+    for (hack_error, fixme_pos) in analysis_data.get_unused_hh_fixmes(
+        functionlike_storage.def_location.start_offset,
+        functionlike_storage.def_location.end_offset,
+    ) {
+        if !get_hh_fixme_issue_kinds(hack_error)
+            .iter()
+            .any(&could_emit_issue)
+        {
+            continue;
+        }
+
+        unused_suppressions.push((
+            format!("Hack error {}", hack_error),
+            statements_analyzer.get_hpos(fixme_pos),
+        ));
+    }
+
+    for (issue_name, comment_pos) in unused_suppressions {
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::UnusedSuppression,
+                format!("Suppression of {} is not used", issue_name),
+                comment_pos,
+                calling_functionlike_id,
+            ),
+            config,
+            file_path,
+        );
+    }
+}
+
+fn report_missing_type_hints(
+    functionlike_id: FunctionLikeIdentifier,
+    functionlike_storage: &FunctionLikeInfo,
+    analysis_data: &mut FunctionAnalysisData,
+    statements_analyzer: &StatementsAnalyzer,
+) {
+    let functionlike_name = functionlike_id.to_string(statements_analyzer.interner);
+
+    for param in &functionlike_storage.params {
+        if param.signature_type_location.is_none() {
+            analysis_data.maybe_add_issue(
+                Issue::new(
+                    IssueKind::MissingParameterType,
+                    format!(
+                        "Parameter {} of {} has no declared type",
+                        statements_analyzer.interner.lookup(&param.name.0),
+                        functionlike_name
+                    ),
+                    param.name_location,
+                    &Some(functionlike_id),
+                ),
+                statements_analyzer.get_config(),
+                statements_analyzer.get_file_path_actual(),
+            );
+        }
+    }
+
+    let is_constructor = matches!(
+        functionlike_id,
+        FunctionLikeIdentifier::Method(_, method_name) if method_name == StrId::CONSTRUCT
+    );
+
+    if functionlike_storage.return_type_location.is_none() && !is_constructor {
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::MissingReturnType,
+                format!("{} has no declared return type", functionlike_name),
+                functionlike_storage
+                    .name_location
+                    .unwrap_or(functionlike_storage.def_location),
+                &Some(functionlike_id),
+            ),
+            statements_analyzer.get_config(),
+            statements_analyzer.get_file_path_actual(),
+        );
+    }
+}
+
 pub(crate) fn update_analysis_result_with_tast(
     analysis_data: FunctionAnalysisData,
     analysis_result: &mut AnalysisResult,
-    file_path: &FilePath,
+    statements_analyzer: &StatementsAnalyzer,
     ignore_taint_path: bool,
 ) {
+    let file_path = statements_analyzer.get_file_path();
+
+    if statements_analyzer.get_config().collect_hover_types && !analysis_data.expr_types.is_empty()
+    {
+        let file_hover_types = analysis_result.hover_types.entry(*file_path).or_default();
+
+        for (offsets, expr_type) in &analysis_data.expr_types {
+            file_hover_types.insert(
+                *offsets,
+                get_union_syntax_type(
+                    expr_type,
+                    statements_analyzer.codebase,
+                    statements_analyzer.interner,
+                    &mut true,
+                ),
+            );
+        }
+    }
+
     if !analysis_data.replacements.is_empty() {
         analysis_result
             .replacements
@@ -1487,6 +1676,13 @@ pub(crate) fn update_analysis_result_with_tast(
         }
     }
 
+    if !analysis_data.matched_ignore_positions.is_empty() {
+        *analysis_result
+            .used_fixme_counts
+            .entry(*file_path)
+            .or_insert(0) += analysis_data.matched_ignore_positions.len();
+    }
+
     let mut issues_to_emit = analysis_data.issues_to_emit;
 
     issues_to_emit.sort_by(|a, b| a.pos.start_offset.partial_cmp(&b.pos.start_offset).unwrap());
@@ -373,6 +373,26 @@ fn handle_literal_negated_equality(
                     }
                 }
             }
+            TAtomic::TFloat => {
+                if let TAtomic::TLiteralFloat { .. } = assertion_type {
+                    did_remove_type = true;
+                }
+
+                acceptable_types.push(existing_atomic_type);
+            }
+            TAtomic::TLiteralFloat {
+                value: existing_value,
+                ..
+            } => {
+                if let TAtomic::TLiteralFloat { value, .. } = assertion_type {
+                    if value == &existing_value {
+                        did_remove_type = true;
+                        continue;
+                    }
+                }
+
+                acceptable_types.push(existing_atomic_type);
+            }
             TAtomic::TArraykey { .. } => {
                 if let TAtomic::TLiteralString { .. }
                 | TAtomic::TLiteralInt { .. }
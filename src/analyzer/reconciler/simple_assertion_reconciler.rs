@@ -49,6 +49,7 @@ pub(crate) fn reconcile(
                 return intersect_simple!(
                     TAtomic::TLiteralClassname { .. }
                         | TAtomic::TLiteralInt { .. }
+                        | TAtomic::TLiteralFloat { .. }
                         | TAtomic::TLiteralString { .. }
                         | TAtomic::TArraykey { .. }
                         | TAtomic::TBool { .. }
@@ -139,7 +140,7 @@ pub(crate) fn reconcile(
             }
             TAtomic::TFloat { .. } => {
                 return intersect_simple!(
-                    TAtomic::TFloat { .. },
+                    TAtomic::TFloat { .. } | TAtomic::TLiteralFloat { .. },
                     TAtomic::TMixed
                         | TAtomic::TMixedWithFlags(..)
                         | TAtomic::TScalar
@@ -1088,7 +1089,7 @@ fn intersect_num(
     let mut did_remove_type = false;
 
     for atomic in &existing_var_type.types {
-        if atomic.is_int() || matches!(atomic, TAtomic::TFloat { .. }) {
+        if atomic.is_int() || atomic.is_float() {
             acceptable_types.push(atomic.clone());
         } else if let TAtomic::TClassTypeConstant { .. } = atomic {
             acceptable_types.push(TAtomic::TNum);
@@ -1880,7 +1881,9 @@ fn reconcile_in_array(
     let intersection = intersect_union_types(typed_value, existing_var_type, codebase);
 
     if let Some(intersection) = intersection {
-        return intersection;
+        if !intersection.is_nothing() {
+            return intersection;
+        }
     }
 
     if let Some(key) = key {
@@ -904,7 +904,7 @@ fn subtract_float(
             }
 
             did_remove_type = true;
-        } else if let TAtomic::TFloat { .. } = atomic {
+        } else if let TAtomic::TFloat { .. } | TAtomic::TLiteralFloat { .. } = atomic {
             did_remove_type = true;
 
             if is_equality {
@@ -999,7 +999,10 @@ fn subtract_num(
             }
 
             did_remove_type = true;
-        } else if let TAtomic::TFloat { .. } | TAtomic::TInt { .. } | TAtomic::TNum { .. } = atomic
+        } else if let TAtomic::TFloat { .. }
+        | TAtomic::TLiteralFloat { .. }
+        | TAtomic::TInt { .. }
+        | TAtomic::TNum { .. } = atomic
         {
             did_remove_type = true;
 
@@ -1771,8 +1774,10 @@ fn reconcile_not_in_array(
 ) -> TUnion {
     let intersection = intersect_union_types(typed_value, existing_var_type, codebase);
 
-    if intersection.is_some() {
-        return existing_var_type.clone();
+    if let Some(intersection) = intersection {
+        if !intersection.is_nothing() {
+            return existing_var_type.clone();
+        }
     }
 
     if let Some(key) = key {
@@ -9,6 +9,7 @@ use crate::{
     function_analysis_data::FunctionAnalysisData,
     reconciler,
     scope::{loop_scope::LoopScope, BlockContext},
+    scope_analyzer::ScopeAnalyzer,
     statements_analyzer::StatementsAnalyzer,
     stmt_analyzer::AnalysisError,
 };
@@ -98,7 +99,13 @@ pub(crate) fn analyze(
             .iter()
             .map(|v| (**v).clone())
             .collect::<Vec<_>>();
-        c.extend(hakana_algebra::negate_formula(while_clauses).unwrap_or_default());
+        c.extend(
+            hakana_algebra::negate_formula(
+                while_clauses,
+                statements_analyzer.get_config().algebra_complexity_budget,
+            )
+            .unwrap_or_default(),
+        );
         c
     };
 
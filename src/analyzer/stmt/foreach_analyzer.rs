@@ -1,4 +1,4 @@
-use super::{control_analyzer::BreakContext, loop_analyzer};
+use super::{control_analyzer::BreakContext, loop_analyzer, shadowed_variable_analyzer};
 use crate::{
     expr::{
         binop::assignment_analyzer, expression_identifier,
@@ -118,6 +118,15 @@ pub(crate) fn analyze(
                 None,
             )
             .ok();
+
+            shadowed_variable_analyzer::check_foreach_binding(
+                statements_analyzer,
+                key_expr,
+                context,
+                &foreach_context,
+                pos.end_offset() as u32,
+                analysis_data,
+            );
         }
         _ => {}
     }
@@ -139,6 +148,15 @@ pub(crate) fn analyze(
         None,
     )?;
 
+    shadowed_variable_analyzer::check_foreach_binding(
+        statements_analyzer,
+        value_expr,
+        context,
+        &foreach_context,
+        pos.end_offset() as u32,
+        analysis_data,
+    );
+
     foreach_context.for_loop_init_bounds = (0, 0);
     foreach_context.inside_loop_exprs = false;
 
@@ -172,7 +172,10 @@ pub(crate) fn analyze(
         .reasonable_clauses
         .clone_from(&if_body_context.clauses);
 
-    if let Ok(negated_if_clauses) = hakana_algebra::negate_formula(if_clauses) {
+    if let Ok(negated_if_clauses) = hakana_algebra::negate_formula(
+        if_clauses,
+        statements_analyzer.get_config().algebra_complexity_budget,
+    ) {
         if_scope.negated_clauses = negated_if_clauses;
     } else {
         if_scope.negated_clauses = formula_generator::get_formula(
@@ -12,6 +12,7 @@ use crate::{
     function_analysis_data::FunctionAnalysisData,
     reconciler,
     scope::{control_action::ControlAction, loop_scope::LoopScope, BlockContext},
+    scope_analyzer::ScopeAnalyzer,
     statements_analyzer::StatementsAnalyzer,
     stmt_analyzer::AnalysisError,
 };
@@ -575,9 +576,11 @@ pub(crate) fn analyze<'a>(
         // if the loop contains an assertion and there are no break statements, we can negate that assertion
         // and apply it to the current context
 
-        let negated_pre_condition_clauses =
-            hakana_algebra::negate_formula(pre_condition_clauses.into_iter().flatten().collect())
-                .unwrap_or_default();
+        let negated_pre_condition_clauses = hakana_algebra::negate_formula(
+            pre_condition_clauses.into_iter().flatten().collect(),
+            statements_analyzer.get_config().algebra_complexity_budget,
+        )
+        .unwrap_or_default();
 
         let (negated_pre_condition_types, _) = hakana_algebra::get_truths_from_formula(
             negated_pre_condition_clauses.iter().collect(),
@@ -12,6 +12,7 @@ pub(crate) mod ifelse_analyzer;
 pub(crate) mod loop_;
 pub(crate) mod loop_analyzer;
 pub(crate) mod return_analyzer;
+pub(crate) mod shadowed_variable_analyzer;
 pub(crate) mod switch_analyzer;
 pub(crate) mod switch_case_analyzer;
 pub(crate) mod try_analyzer;
@@ -0,0 +1,198 @@
+use crate::{
+    function_analysis_data::FunctionAnalysisData, scope::BlockContext,
+    statements_analyzer::StatementsAnalyzer,
+};
+use hakana_code_info::{
+    code_location::HPos,
+    data_flow::{graph::GraphKind, node::DataFlowNodeKind},
+    issue::{Issue, IssueKind},
+    t_union::TUnion,
+    ttype::comparison::union_type_comparator,
+};
+use oxidized::{aast, ast_defs::Pos};
+
+// A foreach or catch variable that overwrites a variable from the enclosing scope with a
+// value of an incompatible type. It's only reported once a read after the nested scope can
+// see both the outer value and the new one, since otherwise the outer binding was dead.
+pub(crate) struct ShadowedVariable {
+    pub message: String,
+    pub pos: HPos,
+    pub scope_end_offset: u32,
+    pub reported: bool,
+}
+
+// Checks every variable bound by a foreach key or value, including ones inside list()
+pub(crate) fn check_foreach_binding(
+    statements_analyzer: &StatementsAnalyzer,
+    target_expr: &aast::Expr<(), ()>,
+    outer_context: &BlockContext,
+    foreach_context: &BlockContext,
+    scope_end_offset: u32,
+    analysis_data: &mut FunctionAnalysisData,
+) {
+    match &target_expr.2 {
+        aast::Expr_::Lvar(lid) => {
+            if let Some(new_type) = foreach_context.locals.get(&lid.1 .1) {
+                check_binding(
+                    statements_analyzer,
+                    &lid.1 .1,
+                    target_expr.pos(),
+                    "Loop variable",
+                    outer_context,
+                    new_type,
+                    scope_end_offset,
+                    analysis_data,
+                );
+            }
+        }
+        aast::Expr_::List(exprs) => {
+            for expr in exprs {
+                check_foreach_binding(
+                    statements_analyzer,
+                    expr,
+                    outer_context,
+                    foreach_context,
+                    scope_end_offset,
+                    analysis_data,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+// Variables initialised to null before a loop, or left over from an earlier loop, are
+// commonly reused like this on purpose, so they're not recorded. A for loop's init
+// assignments always run, so they leave nothing of the outer binding to shadow.
+pub(crate) fn check_binding(
+    statements_analyzer: &StatementsAnalyzer,
+    var_id: &str,
+    var_pos: &Pos,
+    binding_description: &str,
+    outer_context: &BlockContext,
+    new_type: &TUnion,
+    scope_end_offset: u32,
+    analysis_data: &mut FunctionAnalysisData,
+) {
+    // the data flow nodes this relies on are only recorded in function body graphs
+    if analysis_data.data_flow_graph.kind != GraphKind::FunctionBody {
+        return;
+    }
+
+    let config = statements_analyzer.get_config();
+    let file_path = statements_analyzer.get_file_path_actual();
+
+    // when the issue can't be reported, ShadowedLoopVar is reported as usual instead
+    if !config.allow_issue_kind_in_file(&IssueKind::ShadowedVariable, file_path)
+        || !config.allow_opt_in_issue_kind_in_file(&IssueKind::ShadowedVariable, file_path)
+    {
+        return;
+    }
+
+    let existing_type = if let Some(existing_type) = outer_context.locals.get(var_id) {
+        existing_type
+    } else {
+        return;
+    };
+
+    if existing_type.is_null()
+        || existing_type.parent_nodes.iter().any(|parent_node| {
+            matches!(
+                parent_node.kind,
+                DataFlowNodeKind::VariableUseSource {
+                    from_loop_init: true,
+                    ..
+                }
+            )
+        })
+    {
+        return;
+    }
+
+    if union_type_comparator::can_expression_types_be_identical(
+        statements_analyzer.codebase,
+        existing_type,
+        new_type,
+        false,
+    ) {
+        return;
+    }
+
+    let pos = statements_analyzer.get_hpos(var_pos);
+
+    analysis_data
+        .shadowed_variables
+        .entry((pos.start_offset, pos.end_offset))
+        .or_insert_with(|| ShadowedVariable {
+            message: format!(
+                "{} {} shadows an existing variable of incompatible type {}",
+                binding_description,
+                var_id,
+                existing_type.get_id(Some(statements_analyzer.interner))
+            ),
+            pos,
+            scope_end_offset,
+            reported: false,
+        });
+}
+
+// Returns true when the variable read involves a shadowed variable, so that ShadowedLoopVar
+// isn't also reported for it
+pub(crate) fn check_variable_read(
+    statements_analyzer: &StatementsAnalyzer,
+    var_type: &TUnion,
+    read_pos: &Pos,
+    analysis_data: &mut FunctionAnalysisData,
+    context: &BlockContext,
+) -> bool {
+    let source_positions = var_type
+        .parent_nodes
+        .iter()
+        .filter_map(|parent_node| {
+            if let DataFlowNodeKind::VariableUseSource { pos, .. } = &parent_node.kind {
+                Some(*pos)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for binding_pos in &source_positions {
+        let Some(shadowed_variable) = analysis_data
+            .shadowed_variables
+            .get_mut(&(binding_pos.start_offset, binding_pos.end_offset))
+        else {
+            continue;
+        };
+
+        // the outer value has to be able to reach the read too
+        if (read_pos.start_offset() as u32) < shadowed_variable.scope_end_offset
+            || !source_positions
+                .iter()
+                .any(|source_pos| source_pos.start_offset < binding_pos.start_offset)
+        {
+            continue;
+        }
+
+        if !shadowed_variable.reported {
+            shadowed_variable.reported = true;
+
+            let issue = Issue::new(
+                IssueKind::ShadowedVariable,
+                shadowed_variable.message.clone(),
+                shadowed_variable.pos,
+                &context.function_context.calling_functionlike_id,
+            );
+
+            analysis_data.maybe_add_issue(
+                issue,
+                statements_analyzer.get_config(),
+                statements_analyzer.get_file_path_actual(),
+            );
+        }
+
+        return true;
+    }
+
+    false
+}
@@ -1,7 +1,14 @@
+use hakana_code_info::ast::get_id_name;
+use hakana_code_info::code_location::HPos;
+use hakana_code_info::codebase_info::symbols::SymbolKind;
+use hakana_code_info::issue::{Issue, IssueKind};
+use hakana_code_info::t_atomic::{DictKey, TAtomic};
 use hakana_code_info::ttype::{combine_union_types, get_mixed_any};
 
+use hakana_reflector::simple_type_inferer::int_from_string;
 use indexmap::IndexMap;
 use oxidized::{aast, aast::Pos};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::rc::Rc;
 
 use crate::{
@@ -40,6 +47,13 @@ pub(crate) fn analyze(
 
     add_branch_dataflow(statements_analyzer, stmt.0, analysis_data);
 
+    if stmt.2.is_none() {
+        check_enum_cases_handled(statements_analyzer, stmt, analysis_data, context);
+    }
+
+    let duplicate_case_offsets =
+        check_duplicate_case_values(statements_analyzer, stmt.1, analysis_data, context);
+
     let switch_var_id = if let Some(switch_var_id) = expression_identifier::get_var_id(
         stmt.0,
         context.function_context.calling_class.as_ref(),
@@ -105,6 +119,17 @@ pub(crate) fn analyze(
             continue;
         }
 
+        // a duplicate case's condition is always paradoxical too, which DuplicateCaseValue
+        // already explains
+        let is_duplicate_case = duplicate_case_offsets.contains(&case.0.pos().start_offset())
+            || previous_empty_cases
+                .iter()
+                .any(|c| duplicate_case_offsets.contains(&c.0.pos().start_offset()));
+
+        if is_duplicate_case {
+            analysis_data.start_recording_issues();
+        }
+
         analyze_case(
             statements_analyzer,
             stmt,
@@ -123,6 +148,19 @@ pub(crate) fn analyze(
             loop_scope,
         )?;
 
+        if is_duplicate_case {
+            let recorded_issues = analysis_data.clear_currently_recorded_issues();
+            analysis_data.stop_recording_issues();
+
+            let case_pos = statements_analyzer.get_hpos(case.0.pos());
+
+            for recorded_issue in recorded_issues {
+                if !is_case_condition_issue(&recorded_issue, &case_pos) {
+                    analysis_data.bubble_up_issue(recorded_issue);
+                }
+            }
+        }
+
         previous_empty_cases = vec![];
     }
 
@@ -186,3 +224,209 @@ pub(crate) fn analyze(
 
     Ok(())
 }
+
+fn is_case_condition_issue(issue: &Issue, case_pos: &HPos) -> bool {
+    matches!(
+        issue.kind,
+        IssueKind::ParadoxicalCondition
+            | IssueKind::RedundantTypeComparison
+            | IssueKind::ImpossibleTypeComparison
+    ) && issue.pos.start_offset >= case_pos.start_offset
+        && issue.pos.end_offset <= case_pos.end_offset
+}
+
+// A case with the same value as an earlier case can never match. Returns the offsets of
+// those cases.
+fn check_duplicate_case_values(
+    statements_analyzer: &StatementsAnalyzer,
+    cases: &[aast::Case<(), ()>],
+    analysis_data: &mut FunctionAnalysisData,
+    context: &BlockContext,
+) -> FxHashSet<usize> {
+    let mut case_lines = FxHashMap::default();
+    let mut duplicate_case_offsets = FxHashSet::default();
+
+    for case in cases {
+        let Some(case_value) = get_case_value(statements_analyzer, &case.0, context) else {
+            continue;
+        };
+
+        if let Some(previous_line) = case_lines.get(&case_value) {
+            duplicate_case_offsets.insert(case.0.pos().start_offset());
+
+            analysis_data.maybe_add_issue(
+                Issue::new(
+                    IssueKind::DuplicateCaseValue,
+                    format!(
+                        "This case has the same value as the case on line {}, so it can never match",
+                        previous_line
+                    ),
+                    statements_analyzer.get_hpos(case.0.pos()),
+                    &context.function_context.calling_functionlike_id,
+                ),
+                statements_analyzer.get_config(),
+                statements_analyzer.get_file_path_actual(),
+            );
+        } else {
+            case_lines.insert(case_value, case.0.pos().line());
+        }
+    }
+
+    duplicate_case_offsets
+}
+
+// Int and string literals, and class or enum constants that resolve to one
+fn get_case_value(
+    statements_analyzer: &StatementsAnalyzer,
+    case_expr: &aast::Expr<(), ()>,
+    context: &BlockContext,
+) -> Option<DictKey> {
+    match &case_expr.2 {
+        aast::Expr_::Int(value) => int_from_string(value)
+            .ok()
+            .map(|value| DictKey::Int(value as u64)),
+        aast::Expr_::String(value) => Some(DictKey::String(value.to_string())),
+        aast::Expr_::ClassConst(boxed) => {
+            let aast::ClassId_::CIexpr(lhs_expr) = &boxed.0 .2 else {
+                return None;
+            };
+
+            let aast::Expr_::Id(id) = &lhs_expr.2 else {
+                return None;
+            };
+
+            let mut is_static = false;
+
+            let classlike_name = get_id_name(
+                id,
+                &context.function_context.calling_class,
+                context.function_context.calling_class_final,
+                statements_analyzer.codebase,
+                &mut is_static,
+                statements_analyzer.file_analyzer.resolved_names,
+            )?;
+
+            let const_name = statements_analyzer.interner.get(&boxed.1 .1)?;
+
+            match statements_analyzer
+                .codebase
+                .get_classconst_literal_value(&classlike_name, &const_name)?
+            {
+                TAtomic::TLiteralInt { value, .. } => Some(DictKey::Int(*value as u64)),
+                TAtomic::TLiteralString { value, .. } => Some(DictKey::String(value.clone())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// A switch over an enum without a default has to have a case for every member
+fn check_enum_cases_handled(
+    statements_analyzer: &StatementsAnalyzer,
+    stmt: (
+        &aast::Expr<(), ()>,
+        &Vec<aast::Case<(), ()>>,
+        &Option<aast::DefaultCase<(), ()>>,
+    ),
+    analysis_data: &mut FunctionAnalysisData,
+    context: &BlockContext,
+) {
+    let codebase = statements_analyzer.codebase;
+
+    let switch_type = if let Some(switch_type) = analysis_data.get_rc_expr_type(stmt.0.pos()) {
+        switch_type.clone()
+    } else {
+        return;
+    };
+
+    let mut enum_name = None;
+    let mut unhandled_members = vec![];
+
+    for atomic in &switch_type.types {
+        let (atomic_enum_name, members) = match atomic {
+            TAtomic::TEnum { name, .. } => match codebase.classlike_infos.get(name) {
+                Some(classlike_info) if matches!(classlike_info.kind, SymbolKind::Enum) => (
+                    *name,
+                    classlike_info.constants.keys().copied().collect::<Vec<_>>(),
+                ),
+                _ => return,
+            },
+            TAtomic::TEnumLiteralCase {
+                enum_name,
+                member_name,
+                ..
+            } => (*enum_name, vec![*member_name]),
+            _ => return,
+        };
+
+        if *enum_name.get_or_insert(atomic_enum_name) != atomic_enum_name {
+            return;
+        }
+
+        for member in members {
+            if !unhandled_members.contains(&member) {
+                unhandled_members.push(member);
+            }
+        }
+    }
+
+    let enum_name = if let Some(enum_name) = enum_name {
+        enum_name
+    } else {
+        return;
+    };
+
+    for case in stmt.1 {
+        if let aast::Expr_::ClassConst(boxed) = &case.0 .2 {
+            if let aast::ClassId_::CIexpr(lhs_expr) = &boxed.0 .2 {
+                if let aast::Expr_::Id(id) = &lhs_expr.2 {
+                    let mut is_static = false;
+
+                    if get_id_name(
+                        id,
+                        &context.function_context.calling_class,
+                        context.function_context.calling_class_final,
+                        codebase,
+                        &mut is_static,
+                        statements_analyzer.file_analyzer.resolved_names,
+                    ) == Some(enum_name)
+                    {
+                        if let Some(member_name) = statements_analyzer.interner.get(&boxed.1 .1) {
+                            unhandled_members.retain(|member| *member != member_name);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if unhandled_members.is_empty() {
+        return;
+    }
+
+    let enum_name = statements_analyzer.interner.lookup(&enum_name);
+
+    analysis_data.maybe_add_issue(
+        Issue::new(
+            IssueKind::UnhandledEnumCase,
+            format!(
+                "Switch on {} has no default and does not handle {}",
+                enum_name,
+                unhandled_members
+                    .iter()
+                    .map(|member| format!(
+                        "{}::{}",
+                        enum_name,
+                        statements_analyzer.interner.lookup(member)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            statements_analyzer.get_hpos(stmt.0.pos()),
+            &context.function_context.calling_functionlike_id,
+        ),
+        statements_analyzer.get_config(),
+        statements_analyzer.get_file_path_actual(),
+    );
+}
@@ -370,33 +370,36 @@ pub(crate) fn analyze_case(
 
     if !case_clauses.is_empty() {
         if let Some(case_equality_expr) = &case_equality_expr {
-            let negated_case_clauses =
-                if let Ok(negated_case_clauses) = hakana_algebra::negate_formula(case_clauses) {
-                    negated_case_clauses
-                } else {
-                    let case_equality_expr_id = (
-                        case_equality_expr.pos().start_offset() as u32,
-                        case_equality_expr.pos().end_offset() as u32,
-                    );
+            let negated_case_clauses = if let Ok(negated_case_clauses) =
+                hakana_algebra::negate_formula(
+                    case_clauses,
+                    statements_analyzer.get_config().algebra_complexity_budget,
+                ) {
+                negated_case_clauses
+            } else {
+                let case_equality_expr_id = (
+                    case_equality_expr.pos().start_offset() as u32,
+                    case_equality_expr.pos().end_offset() as u32,
+                );
 
-                    formula_generator::get_formula(
-                        case_equality_expr_id,
-                        case_equality_expr_id,
-                        &aast::Expr(
-                            (),
-                            case_equality_expr.pos().clone(),
-                            aast::Expr_::Unop(Box::new((
-                                ast_defs::Uop::Unot,
-                                case_equality_expr.clone(),
-                            ))),
-                        ),
-                        &assertion_context,
-                        analysis_data,
-                        false,
-                        false,
-                    )
-                    .unwrap_or_default()
-                };
+                formula_generator::get_formula(
+                    case_equality_expr_id,
+                    case_equality_expr_id,
+                    &aast::Expr(
+                        (),
+                        case_equality_expr.pos().clone(),
+                        aast::Expr_::Unop(Box::new((
+                            ast_defs::Uop::Unot,
+                            case_equality_expr.clone(),
+                        ))),
+                    ),
+                    &assertion_context,
+                    analysis_data,
+                    false,
+                    false,
+                )
+                .unwrap_or_default()
+            };
 
             switch_scope.negated_clauses.extend(negated_case_clauses);
         }
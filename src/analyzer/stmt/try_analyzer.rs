@@ -15,7 +15,7 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::cell::RefCell;
 use std::{collections::BTreeMap, rc::Rc};
 
-use super::control_analyzer;
+use super::{control_analyzer, shadowed_variable_analyzer};
 
 pub(crate) fn analyze(
     statements_analyzer: &StatementsAnalyzer,
@@ -190,6 +190,23 @@ pub(crate) fn analyze(
 
         catch_type.parent_nodes.push(new_parent_node);
 
+        let catch_end_offset = if let Some(last_stmt) = catch.2 .0.last() {
+            last_stmt.0.end_offset()
+        } else {
+            catch.1 .0.end_offset()
+        };
+
+        shadowed_variable_analyzer::check_binding(
+            statements_analyzer,
+            catch_var_id,
+            &catch.1 .0,
+            "Catch variable",
+            &catch_context,
+            &catch_type,
+            catch_end_offset as u32,
+            analysis_data,
+        );
+
         catch_context
             .locals
             .insert(catch_var_id.clone(), Rc::new(catch_type));
@@ -73,6 +73,21 @@ pub(crate) fn analyze(
         aast::Stmt_::Expr(boxed) => {
             expression_analyzer::analyze(statements_analyzer, boxed, analysis_data, context)?;
 
+            if let aast::Expr_::Call(_) = &boxed.2 {
+                if returns_awaitable(boxed, analysis_data) {
+                    analysis_data.maybe_add_issue(
+                        Issue::new(
+                            IssueKind::UnawaitedAwaitable,
+                            "This call returns an Awaitable that is never awaited".to_string(),
+                            statements_analyzer.get_hpos(boxed.pos()),
+                            &context.function_context.calling_functionlike_id,
+                        ),
+                        statements_analyzer.get_config(),
+                        statements_analyzer.get_file_path_actual(),
+                    );
+                }
+            }
+
             if statements_analyzer.get_config().find_unused_expressions {
                 detect_unused_statement_expressions(
                     boxed,
@@ -384,6 +399,17 @@ fn detect_unused_statement_expressions(
     }
 }
 
+fn returns_awaitable(expr: &aast::Expr<(), ()>, analysis_data: &FunctionAnalysisData) -> bool {
+    if let Some(expr_type) = analysis_data.get_rc_expr_type(expr.pos()) {
+        expr_type
+            .types
+            .iter()
+            .any(|t| matches!(t, TAtomic::TAwaitable { .. }))
+    } else {
+        false
+    }
+}
+
 fn has_unused_must_use(
     boxed: &aast::Expr<(), ()>,
     statements_analyzer: &StatementsAnalyzer,
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+use hakana_code_info::analysis_result::AnalysisResult;
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_code_info::issue::Issue;
+use hakana_str::{Interner, StrId};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub file_path: String,
+    pub kind: String,
+    pub symbol: String,
+    pub signature_hash: u64,
+    pub count: usize,
+}
+
+// file path, issue kind, enclosing symbol, signature hash of enclosing symbol
+type BaselineKey = (String, String, String, u64);
+
+pub struct Baseline {
+    counts: FxHashMap<BaselineKey, usize>,
+}
+
+impl Baseline {
+    pub fn read(path: &str) -> Result<Self, Box<dyn Error>> {
+        let entries: Vec<BaselineEntry> = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        Ok(Self {
+            counts: entries
+                .into_iter()
+                .map(|e| ((e.file_path, e.kind, e.symbol, e.signature_hash), e.count))
+                .collect(),
+        })
+    }
+
+    pub fn from_analysis_result(
+        analysis_result: &AnalysisResult,
+        codebase: &CodebaseInfo,
+        interner: &Interner,
+        root_dir: &str,
+    ) -> Self {
+        let mut counts = FxHashMap::default();
+
+        for (file_path, issues) in analysis_result.get_all_issues(interner, root_dir, true) {
+            for issue in issues {
+                *counts
+                    .entry(get_baseline_key(&file_path, issue, codebase, interner))
+                    .or_insert(0) += 1;
+            }
+        }
+
+        Self { counts }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let entries = self
+            .counts
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, count)| BaselineEntry {
+                file_path: key.0.clone(),
+                kind: key.1.clone(),
+                symbol: key.2.clone(),
+                signature_hash: key.3,
+                count: *count,
+            })
+            .collect::<Vec<_>>();
+
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+
+        Ok(())
+    }
+
+    pub fn issue_count(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Removes every issue already recorded in the baseline, so only net-new issues remain.
+    pub fn filter_issues(
+        mut self,
+        analysis_result: &mut AnalysisResult,
+        codebase: &CodebaseInfo,
+        interner: &Interner,
+        root_dir: &str,
+    ) {
+        for issues_map in [
+            &mut analysis_result.emitted_issues,
+            &mut analysis_result.emitted_definition_issues,
+        ] {
+            for (file_path, issues) in issues_map.iter_mut() {
+                let file_path = file_path.get_relative_path(interner, root_dir);
+
+                issues.retain(|issue| {
+                    if let Some(count) = self
+                        .counts
+                        .get_mut(&get_baseline_key(&file_path, issue, codebase, interner))
+                    {
+                        if *count > 0 {
+                            *count -= 1;
+                            return false;
+                        }
+                    }
+
+                    true
+                });
+            }
+        }
+    }
+}
+
+fn get_baseline_key(
+    file_path: &str,
+    issue: &Issue,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) -> BaselineKey {
+    let (symbol, signature_hash) = if issue.symbol.0 == issue.pos.file_path.0 {
+        // issue is not inside any function or method
+        ("".to_string(), 0)
+    } else {
+        (
+            if issue.symbol.1 == StrId::EMPTY {
+                interner.lookup(&issue.symbol.0).to_string()
+            } else {
+                format!(
+                    "{}::{}",
+                    interner.lookup(&issue.symbol.0),
+                    interner.lookup(&issue.symbol.1)
+                )
+            },
+            get_signature_hash(issue, codebase).unwrap_or(0),
+        )
+    };
+
+    (
+        file_path.to_string(),
+        issue.kind.to_string(),
+        symbol,
+        signature_hash,
+    )
+}
+
+fn get_signature_hash(issue: &Issue, codebase: &CodebaseInfo) -> Option<u64> {
+    let file_info = codebase.files.get(&issue.pos.file_path)?;
+
+    let node = file_info
+        .ast_nodes
+        .iter()
+        .find(|node| node.name == issue.symbol.0)?;
+
+    if issue.symbol.1 == StrId::EMPTY {
+        Some(node.signature_hash)
+    } else {
+        node.children
+            .iter()
+            .find(|child| child.name == issue.symbol.1)
+            .map(|child| child.signature_hash)
+    }
+}
@@ -0,0 +1,127 @@
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_code_info::symbol_references::SymbolReferences;
+use hakana_str::{Interner, StrId};
+use rustc_hash::FxHashSet;
+
+// Prints every function or method reachable from the given one as an indented tree of
+// caller -> callee edges. Functionlikes that have already been expanded are listed again
+// wherever they're called, but their callees are only printed the first time.
+pub fn get_call_graph_lines(
+    from: (StrId, StrId),
+    symbol_references: &SymbolReferences,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    root_dir: &str,
+    max_depth: u8,
+) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} ({})",
+        get_functionlike_name(from, interner),
+        get_location(from, codebase, interner, root_dir)
+    )];
+
+    let mut expanded = FxHashSet::default();
+    expanded.insert(from);
+
+    add_callee_lines(
+        from,
+        symbol_references,
+        codebase,
+        interner,
+        root_dir,
+        1,
+        max_depth,
+        &mut expanded,
+        &mut lines,
+    );
+
+    lines
+}
+
+fn add_callee_lines(
+    caller: (StrId, StrId),
+    symbol_references: &SymbolReferences,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    root_dir: &str,
+    depth: u8,
+    max_depth: u8,
+    expanded: &mut FxHashSet<(StrId, StrId)>,
+    lines: &mut Vec<String>,
+) {
+    if depth > max_depth {
+        return;
+    }
+
+    let mut callees = if let Some(called_functionlikes) = symbol_references
+        .functionlike_references_to_functionlike_calls
+        .get(&caller)
+    {
+        called_functionlikes
+            .iter()
+            .map(|callee| (get_functionlike_name(*callee, interner), *callee))
+            .collect::<Vec<_>>()
+    } else {
+        return;
+    };
+
+    callees.sort();
+
+    let caller_name = get_functionlike_name(caller, interner);
+
+    for (callee_name, callee) in callees {
+        lines.push(format!(
+            "{}{} -> {} ({})",
+            "  ".repeat(depth as usize),
+            caller_name,
+            callee_name,
+            get_location(callee, codebase, interner, root_dir)
+        ));
+
+        if expanded.insert(callee) {
+            add_callee_lines(
+                callee,
+                symbol_references,
+                codebase,
+                interner,
+                root_dir,
+                depth + 1,
+                max_depth,
+                expanded,
+                lines,
+            );
+        }
+    }
+}
+
+fn get_functionlike_name(functionlike_id: (StrId, StrId), interner: &Interner) -> String {
+    if functionlike_id.1 == StrId::EMPTY {
+        interner.lookup(&functionlike_id.0).to_string()
+    } else {
+        format!(
+            "{}::{}",
+            interner.lookup(&functionlike_id.0),
+            interner.lookup(&functionlike_id.1)
+        )
+    }
+}
+
+fn get_location(
+    functionlike_id: (StrId, StrId),
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    root_dir: &str,
+) -> String {
+    if let Some(functionlike_info) = codebase.functionlike_infos.get(&functionlike_id) {
+        format!(
+            "{}:{}",
+            functionlike_info
+                .def_location
+                .file_path
+                .get_relative_path(interner, root_dir),
+            functionlike_info.def_location.start_line
+        )
+    } else {
+        "unknown location".to_string()
+    }
+}
@@ -0,0 +1,215 @@
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_code_info::symbol_references::SymbolReferences;
+use hakana_str::{Interner, StrId};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+const GLOBAL_NAMESPACE: &str = "<global>";
+
+// Finds groups of user-defined classes that all depend on each other, using the references
+// collected during analysis. When a namespace depth is given, classes, functions and type
+// aliases are grouped by their namespace truncated to that many segments instead.
+// Returns every cycle with its members sorted, largest cycles first.
+pub fn find_cycles(
+    symbol_references: &SymbolReferences,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    namespace_depth: Option<usize>,
+) -> Vec<Vec<String>> {
+    let mut node_names = FxHashMap::default();
+    let mut edges: FxHashMap<String, FxHashSet<String>> = FxHashMap::default();
+
+    for (referencing_symbol, referenced_symbols) in
+        symbol_references.symbol_references_to_symbols.iter().chain(
+            symbol_references
+                .symbol_references_to_symbols_in_signature
+                .iter(),
+        )
+    {
+        let Some(from) = get_node_name(
+            referencing_symbol.0,
+            codebase,
+            interner,
+            namespace_depth,
+            &mut node_names,
+        ) else {
+            continue;
+        };
+
+        for referenced_symbol in referenced_symbols {
+            let Some(to) = get_node_name(
+                referenced_symbol.0,
+                codebase,
+                interner,
+                namespace_depth,
+                &mut node_names,
+            ) else {
+                continue;
+            };
+
+            if from != to {
+                edges.entry(from.clone()).or_default().insert(to);
+            }
+        }
+    }
+
+    let mut cycles = get_strongly_connected_components(&edges)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|mut component| {
+            component.sort();
+            component
+        })
+        .collect::<Vec<_>>();
+
+    cycles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    cycles
+}
+
+fn get_node_name(
+    symbol: StrId,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    namespace_depth: Option<usize>,
+    node_names: &mut FxHashMap<StrId, Option<String>>,
+) -> Option<String> {
+    node_names
+        .entry(symbol)
+        .or_insert_with(|| {
+            let is_user_defined_classlike = codebase
+                .classlike_infos
+                .get(&symbol)
+                .map_or(false, |classlike_info| classlike_info.user_defined);
+
+            let Some(namespace_depth) = namespace_depth else {
+                return if is_user_defined_classlike {
+                    Some(interner.lookup(&symbol).to_string())
+                } else {
+                    None
+                };
+            };
+
+            let is_user_defined = is_user_defined_classlike
+                || codebase
+                    .functionlike_infos
+                    .get(&(symbol, StrId::EMPTY))
+                    .map_or(false, |functionlike_info| {
+                        functionlike_info.user_defined && !functionlike_info.is_closure
+                    })
+                || codebase
+                    .type_definitions
+                    .get(&symbol)
+                    .map_or(false, |type_definition_info| {
+                        type_definition_info.user_defined
+                    });
+
+            if !is_user_defined {
+                return None;
+            }
+
+            let segments = interner.lookup(&symbol).split('\\').collect::<Vec<_>>();
+            let namespace_segments = &segments[..segments.len() - 1];
+
+            if namespace_segments.is_empty() {
+                return Some(GLOBAL_NAMESPACE.to_string());
+            }
+
+            let depth = namespace_depth.min(namespace_segments.len());
+
+            Some(namespace_segments[..depth].join("\\"))
+        })
+        .clone()
+}
+
+// Tarjan's algorithm, written iteratively so that long dependency chains can't overflow the stack
+fn get_strongly_connected_components(
+    edges: &FxHashMap<String, FxHashSet<String>>,
+) -> Vec<Vec<String>> {
+    let mut nodes = edges
+        .iter()
+        .flat_map(|(from, to)| std::iter::once(from).chain(to.iter()))
+        .collect::<Vec<_>>();
+    nodes.sort();
+    nodes.dedup();
+
+    let node_offsets = nodes
+        .iter()
+        .enumerate()
+        .map(|(offset, node)| (*node, offset))
+        .collect::<FxHashMap<_, _>>();
+
+    let successors = nodes
+        .iter()
+        .map(|node| {
+            let mut node_successors = edges
+                .get(*node)
+                .map(|to| to.iter().map(|n| node_offsets[n]).collect::<Vec<_>>())
+                .unwrap_or_default();
+            node_successors.sort();
+            node_successors
+        })
+        .collect::<Vec<_>>();
+
+    let mut indexes: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut lowlinks = vec![0; nodes.len()];
+    let mut on_stack = vec![false; nodes.len()];
+    let mut stack = vec![];
+    let mut next_index = 0;
+    let mut components = vec![];
+
+    for root in 0..nodes.len() {
+        if indexes[root].is_some() {
+            continue;
+        }
+
+        // each frame is a node and the offset of the next successor to visit
+        let mut call_stack = vec![(root, 0)];
+        indexes[root] = Some(next_index);
+        lowlinks[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, successor_offset)) = call_stack.pop() {
+            if let Some(&successor) = successors[node].get(successor_offset) {
+                call_stack.push((node, successor_offset + 1));
+
+                if let Some(successor_index) = indexes[successor] {
+                    if on_stack[successor] {
+                        lowlinks[node] = lowlinks[node].min(successor_index);
+                    }
+                } else {
+                    indexes[successor] = Some(next_index);
+                    lowlinks[successor] = next_index;
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack[successor] = true;
+                    call_stack.push((successor, 0));
+                }
+
+                continue;
+            }
+
+            if let Some(&(parent, _)) = call_stack.last() {
+                lowlinks[parent] = lowlinks[parent].min(lowlinks[node]);
+            }
+
+            if Some(lowlinks[node]) == indexes[node] {
+                let mut component = vec![];
+
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(nodes[member].clone());
+
+                    if member == node {
+                        break;
+                    }
+                }
+
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
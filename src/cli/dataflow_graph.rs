@@ -0,0 +1,174 @@
+use hakana_code_info::data_flow::graph::DataFlowGraph;
+use hakana_code_info::data_flow::node::{DataFlowNode, DataFlowNodeId};
+use hakana_code_info::data_flow::path::PathKind;
+use hakana_code_info::functionlike_identifier::{get_functionlike_id, FunctionLikeIdentifier};
+use hakana_str::{Interner, StrId};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::call_graph;
+
+// Finds the nodes that a graph export should start from. A function or Class::method
+// resolves to the nodes for its arguments, calls and return values, and anything else is
+// matched against the node ids themselves.
+pub fn get_start_nodes(
+    symbol: &str,
+    graph: &DataFlowGraph,
+    interner: &Interner,
+) -> FxHashSet<DataFlowNodeId> {
+    let functionlike_id = get_functionlike_id(symbol, interner).map(|(name, member_name)| {
+        if member_name == StrId::EMPTY {
+            FunctionLikeIdentifier::Function(name)
+        } else {
+            FunctionLikeIdentifier::Method(name, member_name)
+        }
+    });
+
+    get_all_nodes(graph)
+        .into_keys()
+        .filter(|node_id| {
+            if let Some(functionlike_id) = &functionlike_id {
+                if get_node_functionlike_id(node_id) == Some(functionlike_id) {
+                    return true;
+                }
+            }
+
+            node_id.to_string(interner) == symbol
+        })
+        .cloned()
+        .collect()
+}
+
+// Serializes the graph in GraphViz's DOT format, with each node labelled by its id and,
+// where it has one, its position. If start nodes are given, only the part of the graph
+// reachable from them is included.
+pub fn get_dot_lines(
+    graph: &DataFlowGraph,
+    start_nodes: Option<&FxHashSet<DataFlowNodeId>>,
+    interner: &Interner,
+    root_dir: &str,
+) -> Vec<String> {
+    let all_nodes = get_all_nodes(graph);
+
+    let included_nodes = if let Some(start_nodes) = start_nodes {
+        get_reachable_nodes(graph, start_nodes)
+    } else {
+        let mut included_nodes = all_nodes.keys().cloned().cloned().collect::<FxHashSet<_>>();
+
+        for (from_id, edges) in &graph.forward_edges {
+            included_nodes.insert(from_id.clone());
+            included_nodes.extend(edges.keys().cloned());
+        }
+
+        included_nodes
+    };
+
+    let mut node_ids = included_nodes.iter().collect::<Vec<_>>();
+    node_ids.sort();
+
+    let node_names = node_ids
+        .iter()
+        .enumerate()
+        .map(|(i, node_id)| (*node_id, format!("n{}", i)))
+        .collect::<FxHashMap<_, _>>();
+
+    let mut lines = vec!["digraph dataflow {".to_string()];
+
+    for node_id in &node_ids {
+        let mut label = node_id.to_string(interner);
+
+        if let Some(pos) = all_nodes.get(node_id).and_then(|node| node.get_pos()) {
+            label += &format!(
+                "\n{}:{}",
+                pos.file_path.get_relative_path(interner, root_dir),
+                pos.start_line
+            );
+        }
+
+        lines.push(format!(
+            "    {} [label=\"{}\"];",
+            node_names[node_id],
+            escape_dot_string(&label)
+        ));
+    }
+
+    for from_id in &node_ids {
+        let Some(edges) = graph.forward_edges.get(*from_id) else {
+            continue;
+        };
+
+        let mut to_ids = edges
+            .keys()
+            .filter(|to_id| included_nodes.contains(*to_id))
+            .collect::<Vec<_>>();
+        to_ids.sort();
+
+        for to_id in to_ids {
+            let path_kind = &edges[to_id].kind;
+
+            lines.push(if let PathKind::Default = path_kind {
+                format!("    {} -> {};", node_names[from_id], node_names[to_id])
+            } else {
+                format!(
+                    "    {} -> {} [label=\"{}\"];",
+                    node_names[from_id],
+                    node_names[to_id],
+                    escape_dot_string(&path_kind.to_string())
+                )
+            });
+        }
+    }
+
+    lines.push("}".to_string());
+
+    lines
+}
+
+// edges can also point at nodes that were never added, e.g. when the other end is a
+// builtin, and those are exported with a label but no position
+fn get_all_nodes(graph: &DataFlowGraph) -> FxHashMap<&DataFlowNodeId, &DataFlowNode> {
+    graph
+        .vertices
+        .iter()
+        .chain(graph.sources.iter())
+        .chain(graph.sinks.iter())
+        .collect()
+}
+
+fn get_reachable_nodes(
+    graph: &DataFlowGraph,
+    start_nodes: &FxHashSet<DataFlowNodeId>,
+) -> FxHashSet<DataFlowNodeId> {
+    let mut reachable = start_nodes.clone();
+    let mut queue = start_nodes.iter().cloned().collect::<Vec<_>>();
+
+    while let Some(node_id) = queue.pop() {
+        if let Some(edges) = graph.forward_edges.get(&node_id) {
+            for to_id in edges.keys() {
+                if reachable.insert(to_id.clone()) {
+                    queue.push(to_id.clone());
+                }
+            }
+        }
+    }
+
+    reachable
+}
+
+fn get_node_functionlike_id(node_id: &DataFlowNodeId) -> Option<&FunctionLikeIdentifier> {
+    match node_id {
+        DataFlowNodeId::ReferenceTo(functionlike_id)
+        | DataFlowNodeId::CallTo(functionlike_id)
+        | DataFlowNodeId::SpecializedCallTo(functionlike_id, ..)
+        | DataFlowNodeId::FunctionLikeArg(functionlike_id, ..)
+        | DataFlowNodeId::SpecializedFunctionLikeArg(functionlike_id, ..)
+        | DataFlowNodeId::FunctionLikeOut(functionlike_id, ..)
+        | DataFlowNodeId::SpecializedFunctionLikeOut(functionlike_id, ..) => Some(functionlike_id),
+        _ => None,
+    }
+}
+
+fn escape_dot_string(str: &str) -> String {
+    str.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,913 @@
+pub struct IssueExplanation {
+    pub kind: &'static str,
+    pub description: &'static str,
+    pub example: &'static str,
+    pub remediation: &'static str,
+}
+
+const NOT_REPORTED: &str = "Hakana does not currently report this issue.";
+
+// One entry per IssueKind, in declaration order. CustomIssue is omitted because its
+// names come from plugins, and kinds that Hakana never emits leave the example and
+// remediation empty.
+pub const ISSUE_EXPLANATIONS: &[IssueExplanation] = &[
+    IssueExplanation {
+        kind: "AbstractInstantiation",
+        description: "An abstract class or an interface is instantiated with new.",
+        example: "abstract class A {}\nnew A();",
+        remediation: "Instantiate a concrete subclass instead.",
+    },
+    IssueExplanation {
+        kind: "BannedFunction",
+        description: "A function listed under banned_builtin_functions in the Hakana config is called.",
+        example: "// with \"banned_builtin_functions\": {\"HH\\\\Lib\\\\Str\\\\format\": \"...\"}\nStr\\format('%s', $a);",
+        remediation: "Use the replacement named in the config message.",
+    },
+    IssueExplanation {
+        kind: "ExtendFinalClass",
+        description: "A class extends a class that is declared final.",
+        example: "final class A {}\nfinal class B extends A {}",
+        remediation: "Remove the final modifier from the parent, or use composition instead of inheritance.",
+    },
+    IssueExplanation {
+        kind: "CannotInferGenericParam",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "CloneInsideLoop",
+        description: "An object is cloned inside a loop, which can be far more expensive than it looks.",
+        example: "foreach ($items as $item) {\n    $copy = clone $template;\n}",
+        remediation: "Move the clone out of the loop, or restructure the code so a copy isn't needed.",
+    },
+    IssueExplanation {
+        kind: "DuplicateCaseValue",
+        description: "A switch case has the same value as an earlier case, so it can never match. Enum and class constants are compared by their values.",
+        example: "function foo(int $i): void {\n    switch ($i) {\n        case 1:\n            echo 'a';\n            break;\n        case 1:\n            echo 'b';\n            break;\n    }\n}",
+        remediation: "Remove the later case, or change it to the value that was intended.",
+    },
+    IssueExplanation {
+        kind: "DuplicateEnumValue",
+        description: "Two cases of an enum share the same value.",
+        example: "enum Color: int {\n    RED = 1;\n    BLUE = 1;\n}",
+        remediation: "Give every enum case a distinct value.",
+    },
+    IssueExplanation {
+        kind: "EmptyBlock",
+        description: "An if or else block contains no statements. Hakana only uses this kind with the fix command to remove such blocks.",
+        example: "if ($a) {\n} else {\n    foo();\n}",
+        remediation: "Run hakana fix --issue EmptyBlock, or remove the block by hand.",
+    },
+    IssueExplanation {
+        kind: "FalsableReturnStatement",
+        description: "A function returns a value that may be false when its return type doesn't allow false.",
+        example: "function foo(string $s): int {\n    return \\strpos($s, 'a');\n}",
+        remediation: "Check for false before returning, or use a function that doesn't return false.",
+    },
+    IssueExplanation {
+        kind: "FalseArgument",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "MissingFinalOrAbstract",
+        description: "A class is neither abstract, final nor <<__Sealed>>.",
+        example: "class A {}",
+        remediation: "Declare the class final if nothing extends it, otherwise abstract or <<__Sealed>>.",
+    },
+    IssueExplanation {
+        kind: "ForLoopInvalidation",
+        description: "A variable that was assigned in a for loop's header is reassigned in its body, which makes the loop hard to follow.",
+        example: "for ($i = 0; $i < 10; $i++) {\n    $i = 5;\n}",
+        remediation: "Use a separate variable inside the loop body.",
+    },
+    IssueExplanation {
+        kind: "ImmutablePropertyWrite",
+        description: "A property of a class marked <<Hakana\\Immutable>> is modified after construction.",
+        example: "<<Hakana\\Immutable>>\nfinal class A {\n    public function __construct(public vec<int> $v) {}\n    public function add(): void {\n        $this->v[] = 1;\n    }\n}",
+        remediation: "Return a new instance with the updated value instead of mutating this one.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleArrayAssignment",
+        description: "A value is written into an array whose type can never hold a value.",
+        example: "function foo(nothing $a): void {\n    $a[0] = 1;\n}",
+        remediation: "Fix the type of the array being assigned to, or remove the unreachable code.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleAssignment",
+        description: "A variable is assigned a value of type nothing, so the code after it can never run.",
+        example: "function foo(): noreturn {\n    throw new Exception();\n}\n$a = foo();",
+        remediation: "Call the function as a statement rather than using its result.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleKeyCheck",
+        description: "A key check can never succeed because the key is not part of the type.",
+        example: "function foo(shape('a' => int) $s): void {\n    if (Shapes::keyExists($s, 'b')) {}\n}",
+        remediation: "Remove the check, or widen the type if the key really can be present.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleNonnullEntryCheck",
+        description: "A check that an entry is non-null can never succeed given the entry's type.",
+        example: "function foo(shape('a' => null) $s): void {\n    if (isset($s['a'])) {}\n}",
+        remediation: "Remove the check, or fix the declared type of the entry.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleNullTypeComparison",
+        description: "A value is compared to null but its type can never be null.",
+        example: "function foo(string $s): void {\n    if ($s === null) {}\n}",
+        remediation: "Remove the comparison, or make the type nullable if null is possible.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleTruthinessCheck",
+        description: "A truthiness check can never succeed because the value is always falsy.",
+        example: "function foo(null $a): void {\n    if ($a) {}\n}",
+        remediation: "Remove the check, or fix the type of the value.",
+    },
+    IssueExplanation {
+        kind: "ImpossibleTypeComparison",
+        description: "A type check can never succeed given what is already known about the value.",
+        example: "function foo(string $s): void {\n    if ($s is int) {}\n}",
+        remediation: "Remove the check, or fix the type of the value.",
+    },
+    IssueExplanation {
+        kind: "IncompatibleTypeParameters",
+        description: "A value is written into a generic container whose type parameter doesn't accept it.",
+        example: "$ref = new HH\\Lib\\Ref(false);\n$ref->value = 5;",
+        remediation: "Give the container a type parameter that accepts every value written to it.",
+    },
+    IssueExplanation {
+        kind: "InternalError",
+        description: "Hakana hit an internal error while analyzing the code.",
+        example: "",
+        remediation: "Report the problem to the Hakana maintainers with a minimal reproduction.",
+    },
+    IssueExplanation {
+        kind: "InvalidArgument",
+        description: "An argument's type is not compatible with the parameter's type.",
+        example: "function foo(int $i): void {}\nfoo('a');",
+        remediation: "Pass a value of the expected type, or convert it first.",
+    },
+    IssueExplanation {
+        kind: "InvalidArrayOffset",
+        description: "An array is accessed with a key of the wrong type.",
+        example: "function foo(vec<int> $v): void {\n    echo $v['a'];\n}",
+        remediation: "Use a key that matches the array's key type.",
+    },
+    IssueExplanation {
+        kind: "InvalidContainsCheck",
+        description: "C\\contains or C\\contains_key is called with a value that can never be in the collection.",
+        example: "function foo(vec<int> $v): bool {\n    return C\\contains($v, 'a');\n}",
+        remediation: "Check for a value of the collection's element type.",
+    },
+    IssueExplanation {
+        kind: "InvalidHackFile",
+        description: "The file could not be parsed as Hack.",
+        example: "function foo( {",
+        remediation: "Fix the syntax errors reported by the parser.",
+    },
+    IssueExplanation {
+        kind: "InvalidInoutArgument",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "InvalidMethodCall",
+        description: "A method is called on a value that can't have methods, such as an int.",
+        example: "function foo(int $i): void {\n    $i->bar();\n}",
+        remediation: "Call the method on an object, or fix the type of the value.",
+    },
+    IssueExplanation {
+        kind: "InvalidPropertyAssignmentValue",
+        description: "A property is assigned a value that doesn't match its declared type.",
+        example: "final class A {\n    public int $i = 0;\n}\n(new A())->i = 'a';",
+        remediation: "Assign a value of the property's type, or change the property's type.",
+    },
+    IssueExplanation {
+        kind: "InvalidReturnStatement",
+        description: "A function returns a value that doesn't match its declared return type.",
+        example: "function foo(): int {\n    return 'a';\n}",
+        remediation: "Return a value of the declared type, or change the return type.",
+    },
+    IssueExplanation {
+        kind: "InvalidReturnType",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "InvalidReturnValue",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "LessSpecificArgument",
+        description: "An argument's type is a parent of the parameter's type, so it may not be valid.",
+        example: "abstract class A {}\nfinal class B extends A {}\nfunction foo(B $b): void {}\nfunction bar(A $a): void {\n    foo($a);\n}",
+        remediation: "Narrow the value with an is or as check before passing it.",
+    },
+    IssueExplanation {
+        kind: "LessSpecificNestedAnyArgumentType",
+        description: "An argument contains a nested type that comes from untyped code and is less specific than the parameter expects.",
+        example: "function foo(vec<int> $v): void {}\nfunction bar(vec<dynamic> $v): void {\n    foo($v);\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "LessSpecificNestedAnyReturnStatement",
+        description: "A return value contains a nested type that comes from untyped code and is less specific than the return type.",
+        example: "function foo(vec<dynamic> $v): vec<int> {\n    return $v;\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "LessSpecificNestedArgumentType",
+        description: "An argument contains a nested type that is less specific than the parameter expects.",
+        example: "function foo(vec<int> $v): void {}\nfunction bar(vec<arraykey> $v): void {\n    foo($v);\n}",
+        remediation: "Narrow the nested values before passing the collection.",
+    },
+    IssueExplanation {
+        kind: "LessSpecificNestedReturnStatement",
+        description: "A return value contains a nested type that is less specific than the return type.",
+        example: "function foo(vec<arraykey> $v): vec<int> {\n    return $v;\n}",
+        remediation: "Narrow the nested values, or widen the return type.",
+    },
+    IssueExplanation {
+        kind: "LessSpecificReturnStatement",
+        description: "A return value's type is a parent of the declared return type.",
+        example: "function foo(arraykey $a): int {\n    return $a;\n}",
+        remediation: "Narrow the value before returning it, or widen the return type.",
+    },
+    IssueExplanation {
+        kind: "MethodCallOnNull",
+        description: "A method is called on a value that is always null.",
+        example: "function foo(null $a): void {\n    $a->bar();\n}",
+        remediation: "Make sure the value is an object before calling the method.",
+    },
+    IssueExplanation {
+        kind: "MissingParameterType",
+        description: "A parameter has no type hint.",
+        example: "function foo($a): void {}",
+        remediation: "Add a type hint to the parameter.",
+    },
+    IssueExplanation {
+        kind: "MissingPropertyType",
+        description: "A property has no type hint.",
+        example: "final class A {\n    public $a;\n}",
+        remediation: "Add a type hint to the property.",
+    },
+    IssueExplanation {
+        kind: "MissingRequiredXhpAttribute",
+        description: "An XHP element is missing an attribute marked @required.",
+        example: "final xhp class my_link {\n    attribute string href @required;\n}\n<my_link />;",
+        remediation: "Pass the required attribute.",
+    },
+    IssueExplanation {
+        kind: "MissingReturnType",
+        description: "A function or method has no return type.",
+        example: "function foo() {\n    return 1;\n}",
+        remediation: "Add a return type.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyArgument",
+        description: "An argument's type comes from untyped code, so it can't be checked against the parameter.",
+        example: "function foo(int $i): void {}\nfunction bar(dynamic $d): void {\n    foo($d);\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyArrayAccess",
+        description: "An array access is made on a value whose type comes from untyped code.",
+        example: "function foo(dynamic $d): void {\n    echo $d[0];\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyArrayAssignment",
+        description: "An array assignment is made on a value whose type comes from untyped code.",
+        example: "function foo(dynamic $d): void {\n    $d[0] = 1;\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyArrayOffset",
+        description: "An array is accessed with a key whose type comes from untyped code.",
+        example: "function foo(vec<int> $v, dynamic $d): void {\n    echo $v[$d];\n}",
+        remediation: "Add type information to the code that produces the key.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyAssignment",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "MixedAnyMethodCall",
+        description: "A method is called on a value whose type comes from untyped code.",
+        example: "function foo(dynamic $d): void {\n    $d->bar();\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyPropertyAssignment",
+        description: "A property is assigned a value whose type comes from untyped code.",
+        example: "final class A {\n    public int $i = 0;\n}\nfunction foo(A $a, dynamic $d): void {\n    $a->i = $d;\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedAnyPropertyTypeCoercion",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "MixedAnyReturnStatement",
+        description: "A function returns a value whose type comes from untyped code.",
+        example: "function foo(dynamic $d): int {\n    return $d;\n}",
+        remediation: "Add type information to the code that produces the value.",
+    },
+    IssueExplanation {
+        kind: "MixedArgument",
+        description: "An argument has type mixed, so it can't be checked against the parameter.",
+        example: "function foo(int $i): void {}\nfunction bar(mixed $m): void {\n    foo($m);\n}",
+        remediation: "Narrow the value with an is or as check before passing it.",
+    },
+    IssueExplanation {
+        kind: "MixedArrayAccess",
+        description: "An array access is made on a value of type mixed.",
+        example: "function foo(mixed $m): void {\n    echo $m[0];\n}",
+        remediation: "Narrow the value to a collection type first.",
+    },
+    IssueExplanation {
+        kind: "MixedArrayAssignment",
+        description: "An array assignment is made on a value of type mixed.",
+        example: "function foo(mixed $m): void {\n    $m[0] = 1;\n}",
+        remediation: "Narrow the value to a collection type first.",
+    },
+    IssueExplanation {
+        kind: "MixedArrayOffset",
+        description: "An array is accessed with a key of type mixed.",
+        example: "function foo(vec<int> $v, mixed $m): void {\n    echo $v[$m];\n}",
+        remediation: "Narrow the key to the array's key type first.",
+    },
+    IssueExplanation {
+        kind: "MixedMethodCall",
+        description: "A method is called on a value of type mixed.",
+        example: "function foo(mixed $m): void {\n    $m->bar();\n}",
+        remediation: "Narrow the value to a class type first.",
+    },
+    IssueExplanation {
+        kind: "MixedOperand",
+        description: "An arithmetic operation has an operand of type mixed.",
+        example: "function foo(mixed $m): void {\n    echo $m + 1;\n}",
+        remediation: "Narrow the operand to num first.",
+    },
+    IssueExplanation {
+        kind: "MixedPropertyAssignment",
+        description: "A property is assigned a value of type mixed.",
+        example: "final class A {\n    public int $i = 0;\n}\nfunction foo(A $a, mixed $m): void {\n    $a->i = $m;\n}",
+        remediation: "Narrow the value to the property's type first.",
+    },
+    IssueExplanation {
+        kind: "MixedPropertyTypeCoercion",
+        description: "A property is assigned a value containing a nested mixed type that is less specific than the property's type.",
+        example: "final class A {\n    public vec<int> $v = vec[];\n}\nfunction foo(A $a, vec<mixed> $m): void {\n    $a->v = $m;\n}",
+        remediation: "Narrow the nested values to the property's type first.",
+    },
+    IssueExplanation {
+        kind: "MixedReturnStatement",
+        description: "A function returns a value of type mixed.",
+        example: "function foo(mixed $m): int {\n    return $m;\n}",
+        remediation: "Narrow the value to the return type first.",
+    },
+    IssueExplanation {
+        kind: "NoValue",
+        description: "A value of type nothing is used, so the code can never run.",
+        example: "function foo(int $i): void {}\nfunction bar(nothing $n): void {\n    foo($n);\n}",
+        remediation: "Remove the unreachable code, or fix the type that produces nothing.",
+    },
+    IssueExplanation {
+        kind: "NonExistentClass",
+        description: "A class that can't be found is instantiated or has a member accessed.",
+        example: "new UndefinedClass();",
+        remediation: "Fix the class name, or make sure the file that defines it is analyzed.",
+    },
+    IssueExplanation {
+        kind: "NonExistentClassConstant",
+        description: "A class constant that doesn't exist is referenced.",
+        example: "final class A {}\necho A::FOO;",
+        remediation: "Fix the constant name, or define the constant.",
+    },
+    IssueExplanation {
+        kind: "NonExistentClasslike",
+        description: "A class, enum or interface that can't be found is referenced, for example in a type hint.",
+        example: "function foo(UndefinedClass $c): void {}",
+        remediation: "Fix the name, or make sure the file that defines it is analyzed.",
+    },
+    IssueExplanation {
+        kind: "NonExistentConstant",
+        description: "A global constant that doesn't exist is referenced.",
+        example: "echo UNDEFINED_CONSTANT;",
+        remediation: "Fix the constant name, or define the constant.",
+    },
+    IssueExplanation {
+        kind: "NonExistentFile",
+        description: "An include or require refers to a file that doesn't exist.",
+        example: "require_once(__DIR__.'/missing.hack');",
+        remediation: "Fix the path.",
+    },
+    IssueExplanation {
+        kind: "NonExistentFunction",
+        description: "A function that doesn't exist is called.",
+        example: "undefined_function();",
+        remediation: "Fix the function name, or make sure the file that defines it is analyzed.",
+    },
+    IssueExplanation {
+        kind: "NonExistentMethod",
+        description: "A method that doesn't exist is called.",
+        example: "final class A {}\n(new A())->foo();",
+        remediation: "Fix the method name, or define the method.",
+    },
+    IssueExplanation {
+        kind: "NonExistentProperty",
+        description: "A property that doesn't exist is accessed.",
+        example: "final class A {}\necho (new A())->foo;",
+        remediation: "Fix the property name, or declare the property.",
+    },
+    IssueExplanation {
+        kind: "NonExistentType",
+        description: "A type that can't be found is referenced in a class constant or type constant access.",
+        example: "echo UndefinedClass::FOO;",
+        remediation: "Fix the type name, or make sure the file that defines it is analyzed.",
+    },
+    IssueExplanation {
+        kind: "NonExistentXhpAttribute",
+        description: "An XHP element is passed an attribute it doesn't declare.",
+        example: "final xhp class my_link {\n    attribute string href;\n}\n<my_link target=\"_blank\" />;",
+        remediation: "Fix the attribute name, or declare the attribute.",
+    },
+    IssueExplanation {
+        kind: "NoJoinInAsyncFunction",
+        description: "HH\\Asio\\join() is called inside an async function, blocking instead of awaiting.",
+        example: "async function foo(): Awaitable<int> {\n    return HH\\Asio\\join(bar());\n}",
+        remediation: "Use await instead of HH\\Asio\\join().",
+    },
+    IssueExplanation {
+        kind: "NonNullableReturnType",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "NothingReturn",
+        description: "A function returns the result of an expression that never returns.",
+        example: "function bar(): noreturn {\n    throw new Exception();\n}\nfunction foo(): int {\n    return bar();\n}",
+        remediation: "Call the expression as a statement instead of returning it.",
+    },
+    IssueExplanation {
+        kind: "NullArrayOffset",
+        description: "An array is accessed with a key that is always null.",
+        example: "function foo(dict<string, int> $d): void {\n    echo $d[null];\n}",
+        remediation: "Use a non-null key.",
+    },
+    IssueExplanation {
+        kind: "NullIterator",
+        description: "A foreach loop iterates over a value that is always null.",
+        example: "function foo(null $a): void {\n    foreach ($a as $v) {}\n}",
+        remediation: "Iterate over a collection instead.",
+    },
+    IssueExplanation {
+        kind: "NullablePropertyAssignment",
+        description: "A property that doesn't accept null is assigned a value that is always null.",
+        example: "final class A {\n    public int $i = 0;\n}\n(new A())->i = null;",
+        remediation: "Assign a non-null value, or make the property nullable.",
+    },
+    IssueExplanation {
+        kind: "NullableReturnStatement",
+        description: "A function returns a value that may be null when its return type isn't nullable.",
+        example: "function foo(?int $i): int {\n    return $i;\n}",
+        remediation: "Handle the null case before returning, or make the return type nullable.",
+    },
+    IssueExplanation {
+        kind: "NullableReturnValue",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "OnlyUsedInTests",
+        description: "A function in production code is only ever called from tests. Files matching test_files are test code unless --include-test-files is passed.",
+        example: "// src/foo.hack\nfunction foo(): void {}\n// tests/foo_test.hack\nfoo();",
+        remediation: "Move the function into test code, or add the <<Hakana\\TestOnly>> attribute if this is deliberate.",
+    },
+    IssueExplanation {
+        kind: "ParadoxicalCondition",
+        description: "A condition contradicts what is already known, so its branch can never run.",
+        example: "function foo(bool $b): void {\n    switch ($b) {\n        case true:\n            break;\n        case false:\n            break;\n        default:\n            break;\n    }\n}",
+        remediation: "Remove the unreachable branch.",
+    },
+    IssueExplanation {
+        kind: "PossibleMethodCallOnNull",
+        description: "A method is called on a value that may be null.",
+        example: "function foo(?Exception $e): void {\n    $e->getMessage();\n}",
+        remediation: "Check for null first, or use the ?-> operator.",
+    },
+    IssueExplanation {
+        kind: "PossiblyFalseArgument",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "PossiblyInvalidArgument",
+        description: "An argument's type is only partly compatible with the parameter's type.",
+        example: "function foo(int $i): void {}\nfunction bar(arraykey $a): void {\n    foo($a);\n}",
+        remediation: "Narrow the value with an is or as check before passing it.",
+    },
+    IssueExplanation {
+        kind: "PossiblyInvalidArrayAccess",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "PossiblyInvalidMethodCall",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "PossiblyNullArrayAccess",
+        description: "An array access is made on a value that may be null.",
+        example: "function foo(?vec<int> $v): void {\n    echo $v[0];\n}",
+        remediation: "Check for null first.",
+    },
+    IssueExplanation {
+        kind: "PossiblyNullArrayOffset",
+        description: "An array is accessed with a key that may be null.",
+        example: "function foo(dict<string, int> $d, ?string $s): void {\n    echo $d[$s];\n}",
+        remediation: "Check the key for null first.",
+    },
+    IssueExplanation {
+        kind: "PossiblyNullIterator",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "PossiblyNullPropertyFetch",
+        description: "A property is accessed on a value that may be null.",
+        example: "final class A {\n    public int $i = 0;\n}\nfunction foo(?A $a): void {\n    echo $a->i;\n}",
+        remediation: "Check for null first, or use the ?-> operator.",
+    },
+    IssueExplanation {
+        kind: "PossiblyUndefinedIntArrayOffset",
+        description: "An int key is read from an array that may not contain it.",
+        example: "function foo(dict<int, string> $d): void {\n    echo $d[0];\n}",
+        remediation: "Check the key exists first, or use idx() with a default.",
+    },
+    IssueExplanation {
+        kind: "PossiblyUndefinedStringArrayOffset",
+        description: "A string key is read from an array that may not contain it.",
+        example: "function foo(dict<string, string> $d): void {\n    echo $d['a'];\n}",
+        remediation: "Check the key exists first, or use idx() with a default.",
+    },
+    IssueExplanation {
+        kind: "PropertyTypeCoercion",
+        description: "A property is assigned a value whose type is a parent of the property's type.",
+        example: "final class A {\n    public int $i = 0;\n}\nfunction foo(A $a, arraykey $k): void {\n    $a->i = $k;\n}",
+        remediation: "Narrow the value before assigning it, or widen the property's type.",
+    },
+    IssueExplanation {
+        kind: "RedundantIssetCheck",
+        description: "An isset check is unnecessary because the value is always set and non-null.",
+        example: "function foo(int $i): void {\n    if (isset($i)) {}\n}",
+        remediation: "Remove the check.",
+    },
+    IssueExplanation {
+        kind: "RedundantKeyCheck",
+        description: "A key check is unnecessary because the key is always present.",
+        example: "function foo(shape('a' => int) $s): void {\n    if (Shapes::keyExists($s, 'a')) {}\n}",
+        remediation: "Remove the check.",
+    },
+    IssueExplanation {
+        kind: "RedundantNonnullEntryCheck",
+        description: "A check that an entry is non-null is unnecessary because it always is.",
+        example: "function foo(shape('a' => int) $s): void {\n    if (isset($s['a'])) {}\n}",
+        remediation: "Remove the check.",
+    },
+    IssueExplanation {
+        kind: "RedundantNonnullTypeComparison",
+        description: "A value is checked for null but is always non-null.",
+        example: "function foo(string $s): void {\n    if ($s !== null) {}\n}",
+        remediation: "Remove the check, or make the type nullable if null is possible.",
+    },
+    IssueExplanation {
+        kind: "RedundantTruthinessCheck",
+        description: "A truthiness check is unnecessary because the value is always truthy.",
+        example: "final class A {}\nfunction foo(A $a): void {\n    if ($a) {}\n}",
+        remediation: "Remove the check.",
+    },
+    IssueExplanation {
+        kind: "RedundantTypeComparison",
+        description: "A type check is unnecessary because the value always has that type.",
+        example: "function foo(string $s): void {\n    if ($s is string) {}\n}",
+        remediation: "Remove the check.",
+    },
+    IssueExplanation {
+        kind: "ShadowedLoopVar",
+        description: "A foreach loop overwrites a variable that is defined above the loop and used after it.",
+        example: "function foo(string $s, vec<string> $v): void {\n    foreach ($v as $s) {}\n    echo $s;\n}",
+        remediation: "Give the loop variable a different name.",
+    },
+    IssueExplanation {
+        kind: "ShadowedVariable",
+        description: "A foreach or catch variable overwrites a variable of an incompatible type whose value is still used after the loop or catch block. Only reported when listed in allowed_issues.",
+        example: "function foo(vec<string> $v): void {\n    $count = 0;\n    foreach ($v as $count) {}\n    echo $count;\n}",
+        remediation: "Give the loop or catch variable a different name.",
+    },
+    IssueExplanation {
+        kind: "StrictObjectEquality",
+        description: "Two objects are compared with === or !==, which compares them by reference.",
+        example: "final class A {}\nfunction foo(A $a, A $b): bool {\n    return $a === $b;\n}",
+        remediation: "Compare an identifying field instead, or use == if value equality is intended.",
+    },
+    IssueExplanation {
+        kind: "SuspiciousLooseEquality",
+        description: "Values of unrelated types, such as a shape and an int, are compared with == or !=. Only reported when listed in allowed_issues.",
+        example: "function foo(shape('id' => int) $s, int $id): bool {\n    return $s == $id;\n}",
+        remediation: "Compare the intended field or value instead.",
+    },
+    IssueExplanation {
+        kind: "TaintedData",
+        description: "Security analysis found a path from user-controlled data to a sensitive sink.",
+        example: "function foo(): void {\n    echo $_GET['name'];\n}",
+        remediation: "Escape or validate the data before it reaches the sink.",
+    },
+    IssueExplanation {
+        kind: "TestOnlyCall",
+        description: "A function marked <<Hakana\\TestOnly>> is called from production code. Files matching test_files are test code unless --include-test-files is passed.",
+        example: "<<Hakana\\TestOnly>>\nfunction foo(): void {}\nfunction bar(): void {\n    foo();\n}",
+        remediation: "Only call the function from tests, or remove the attribute.",
+    },
+    IssueExplanation {
+        kind: "TooFewArguments",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "TooManyArguments",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "UnavailableBuiltin",
+        description: "A builtin function is called that was added in a newer HHVM release than the hack_version set in the Hakana config.",
+        example: "// with \"hack_version\": \"4.140\"\nfunction foo(string $s): num {\n    return HH\\str_to_numeric($s) ?? 0;\n}",
+        remediation: "Avoid the function until the codebase runs on a version that has it, or raise hack_version once it does.",
+    },
+    IssueExplanation {
+        kind: "UnawaitedAwaitable",
+        description: "A call returns an Awaitable that is discarded without being awaited, so the async work may never finish. Only reported when listed in allowed_issues.",
+        example: "async function bar(): Awaitable<void> {}\nasync function foo(): Awaitable<void> {\n    bar();\n}",
+        remediation: "Await the result. For intentional fire-and-forget calls, suppress the issue with HAKANA_FIXME[UnawaitedAwaitable].",
+    },
+    IssueExplanation {
+        kind: "UndefinedIntArrayOffset",
+        description: "An int key is read from an array that never contains it.",
+        example: "function foo(vec<int> $v): void {\n    $v = vec[1];\n    echo $v[1];\n}",
+        remediation: "Use a key that exists, or check for it first.",
+    },
+    IssueExplanation {
+        kind: "UndefinedStringArrayOffset",
+        description: "A string key is read from an array that never contains it.",
+        example: "function foo(shape('a' => int) $s): void {\n    echo $s['b'];\n}",
+        remediation: "Use a key that exists, or add it to the type.",
+    },
+    IssueExplanation {
+        kind: "UndefinedVariable",
+        description: "A variable is read before it has been assigned.",
+        example: "function foo(): void {\n    echo $a;\n}",
+        remediation: "Assign the variable before using it.",
+    },
+    IssueExplanation {
+        kind: "UnevaluatedCode",
+        description: "Code follows a return, throw, break or continue and can never run.",
+        example: "function foo(): void {\n    return;\n    echo 'a';\n}",
+        remediation: "Remove the unreachable code.",
+    },
+    IssueExplanation {
+        kind: "UnhandledEnumCase",
+        description: "A switch over an enum has no default, and some of the enum's members have no case.",
+        example: "enum Color: int {\n    RED = 0;\n    BLUE = 1;\n}\nfunction foo(Color $c): void {\n    switch ($c) {\n        case Color::RED:\n            break;\n    }\n}",
+        remediation: "Add a case for each missing member, or a default case.",
+    },
+    IssueExplanation {
+        kind: "UnnecessaryShapesIdx",
+        description: "Shapes::idx is used for a field that is always present.",
+        example: "function foo(shape('a' => int) $s): void {\n    $a = Shapes::idx($s, 'a');\n}",
+        remediation: "Access the field directly with $s['a'].",
+    },
+    IssueExplanation {
+        kind: "UnrecognizedBinaryOp",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "UnrecognizedExpression",
+        description: "Hakana doesn't know how to analyze this kind of expression.",
+        example: "",
+        remediation: "Report the expression to the Hakana maintainers.",
+    },
+    IssueExplanation {
+        kind: "UnrecognizedStatement",
+        description: "Hakana doesn't know how to analyze this kind of statement.",
+        example: "",
+        remediation: "Report the statement to the Hakana maintainers.",
+    },
+    IssueExplanation {
+        kind: "UnrecognizedUnaryOp",
+        description: NOT_REPORTED,
+        example: "",
+        remediation: "",
+    },
+    IssueExplanation {
+        kind: "UnusedAssignment",
+        description: "A value is assigned to a variable that is never read afterwards.",
+        example: "function foo(): void {\n    $a = bar();\n}",
+        remediation: "Remove the assignment, keeping the right-hand side if it has side effects.",
+    },
+    IssueExplanation {
+        kind: "UnusedAssignmentInClosure",
+        description: "A value is assigned to a variable inside a closure and never read afterwards.",
+        example: "$f = () ==> {\n    $a = bar();\n};",
+        remediation: "Remove the assignment, keeping the right-hand side if it has side effects.",
+    },
+    IssueExplanation {
+        kind: "UnusedAssignmentStatement",
+        description: "A variable is assigned an expression with no side effects and never read, so the whole statement can go.",
+        example: "function foo(): void {\n    $a = 1 + 2;\n}",
+        remediation: "Remove the statement.",
+    },
+    IssueExplanation {
+        kind: "UnusedAwaitable",
+        description: "An awaitable is created but never awaited.",
+        example: "async function bar(): Awaitable<void> {}\nfunction foo(): void {\n    bar();\n}",
+        remediation: "Await the result, or remove the call.",
+    },
+    IssueExplanation {
+        kind: "UnusedBuiltinReturnValue",
+        description: "A builtin function with no side effects is called and its result is discarded.",
+        example: "function foo(vec<int> $v): void {\n    Vec\\map($v, $i ==> $i + 1);\n}",
+        remediation: "Use the result, or remove the call.",
+    },
+    IssueExplanation {
+        kind: "UnusedClass",
+        description: "A class is never referenced.",
+        example: "final class Unused {}",
+        remediation: "Remove the class.",
+    },
+    IssueExplanation {
+        kind: "UnusedClosureParameter",
+        description: "A closure parameter is never used.",
+        example: "$f = (int $i) ==> 5;",
+        remediation: "Prefix the parameter name with an underscore, or remove it.",
+    },
+    IssueExplanation {
+        kind: "UnusedFunction",
+        description: "A function is never called.",
+        example: "function unused(): void {}",
+        remediation: "Remove the function.",
+    },
+    IssueExplanation {
+        kind: "UnusedFunctionCall",
+        description: "The result of a function annotated <<Hakana\\MustUse>> is discarded.",
+        example: "<<Hakana\\MustUse>>\nfunction foo(): int {\n    return 1;\n}\nfoo();",
+        remediation: "Use the result of the call.",
+    },
+    IssueExplanation {
+        kind: "UnusedMethodCall",
+        description: "The result of a method annotated <<Hakana\\MustUse>> is discarded.",
+        example: "final class A {\n    <<Hakana\\MustUse>>\n    public function foo(): int {\n        return 1;\n    }\n}\n(new A())->foo();",
+        remediation: "Use the result of the call.",
+    },
+    IssueExplanation {
+        kind: "UnusedInheritedMethod",
+        description: "A method is never called on any of the classes that inherit it.",
+        example: "abstract class A {\n    public function foo(): void {}\n}\nfinal class B extends A {}\nnew B();",
+        remediation: "Remove the method.",
+    },
+    IssueExplanation {
+        kind: "UnusedInterface",
+        description: "An interface is never referenced.",
+        example: "interface Unused {}",
+        remediation: "Remove the interface.",
+    },
+    IssueExplanation {
+        kind: "UnusedParameter",
+        description: "A function or method parameter is never used.",
+        example: "function foo(int $i): void {}",
+        remediation: "Prefix the parameter name with an underscore, or remove it.",
+    },
+    IssueExplanation {
+        kind: "UnusedPipeVariable",
+        description: "The left-hand side of a pipe is never used on the right-hand side.",
+        example: "$a = foo() |> vec[1];",
+        remediation: "Use $$ on the right-hand side, or remove the pipe.",
+    },
+    IssueExplanation {
+        kind: "UnusedPrivateMethod",
+        description: "A private method is never called.",
+        example: "final class A {\n    private function foo(): void {}\n}",
+        remediation: "Remove the method.",
+    },
+    IssueExplanation {
+        kind: "UnusedPrivateProperty",
+        description: "A private property is never read.",
+        example: "final class A {\n    private int $i = 0;\n}",
+        remediation: "Remove the property.",
+    },
+    IssueExplanation {
+        kind: "UnusedPublicOrProtectedMethod",
+        description: "A public or protected method is never called.",
+        example: "final class A {\n    public function foo(): void {}\n}\nnew A();",
+        remediation: "Remove the method.",
+    },
+    IssueExplanation {
+        kind: "UnusedPublicOrProtectedProperty",
+        description: "A public or protected property is never read.",
+        example: "final class A {\n    public int $i = 0;\n}\nnew A();",
+        remediation: "Remove the property.",
+    },
+    IssueExplanation {
+        kind: "UnusedStatement",
+        description: "A statement has no effect.",
+        example: "function foo(int $i): void {\n    $i + 1;\n}",
+        remediation: "Remove the statement.",
+    },
+    IssueExplanation {
+        kind: "UnusedSuppression",
+        description: "A HAKANA_FIXME or HAKANA_IGNORE comment doesn't suppress any issue. HH_FIXME comments are reported too, but only for Hack error codes that Hakana maps to its own issues.",
+        example: "function foo(): void {\n    /* HAKANA_FIXME[UndefinedVariable] */\n    echo 1;\n}",
+        remediation: "Remove the comment, or run hakana remove-unused-fixmes.",
+    },
+    IssueExplanation {
+        kind: "UnusedTrait",
+        description: "A trait is never used.",
+        example: "trait Unused {}",
+        remediation: "Remove the trait.",
+    },
+    IssueExplanation {
+        kind: "UnusedTypeDefinition",
+        description: "A type alias or newtype is never referenced.",
+        example: "type unused_t = int;",
+        remediation: "Remove the type definition.",
+    },
+    IssueExplanation {
+        kind: "UnusedXhpAttribute",
+        description: "An XHP attribute is declared but never read.",
+        example: "final xhp class my_link {\n    attribute string href;\n}",
+        remediation: "Remove the attribute.",
+    },
+    IssueExplanation {
+        kind: "UpcastAwaitable",
+        description: "A value containing an Awaitable is passed where a more general type such as mixed is expected, so it may never be awaited.",
+        example: "async function bar(): Awaitable<int> {\n    return 1;\n}\nfunction foo(): mixed {\n    return bar();\n}",
+        remediation: "Await the value first, or use a return type that mentions Awaitable.",
+    },
+    IssueExplanation {
+        kind: "UselessControlFlow",
+        description: "A control-flow statement has no effect, such as a break after a throw.",
+        example: "function foo(int $i): void {\n    switch ($i) {\n        case 1:\n            throw new Exception();\n            break;\n    }\n}",
+        remediation: "Remove the statement.",
+    },
+];
+
+pub fn find_explanation(kind: &str) -> Option<&'static IssueExplanation> {
+    ISSUE_EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.kind.eq_ignore_ascii_case(kind))
+}
+
+impl IssueExplanation {
+    pub fn format(&self) -> String {
+        let mut output = format!("{}\n\n{}\n", self.kind, self.description);
+
+        if !self.example.is_empty() {
+            output += &format!(
+                "\nExample:\n\n{}\n",
+                self.example
+                    .lines()
+                    .map(|line| format!("    {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+
+        if !self.remediation.is_empty() {
+            output += &format!("\nRemediation:\n\n{}\n", self.remediation);
+        }
+
+        output
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+use hakana_code_info::analysis_result::AnalysisResult;
+
+// Per-kind issue counts, saved between runs so CI can report which kinds went up or down
+pub struct IssueCounts {
+    counts: BTreeMap<String, usize>,
+}
+
+impl IssueCounts {
+    pub fn read(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            counts: serde_json::from_str(&fs::read_to_string(path)?)?,
+        })
+    }
+
+    pub fn from_analysis_result(analysis_result: &AnalysisResult) -> Self {
+        Self {
+            counts: analysis_result
+                .issue_counts
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(&self.counts)?)?;
+
+        Ok(())
+    }
+
+    // Returns each kind with its count, most common first
+    pub fn get_counts_by_frequency(&self) -> Vec<(&str, usize)> {
+        let mut counts = self
+            .counts
+            .iter()
+            .map(|(kind, count)| (kind.as_str(), *count))
+            .collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        counts
+    }
+
+    // Returns the kinds whose count differs from the baseline, with the old and new counts
+    pub fn get_changes<'a>(&'a self, baseline: &'a IssueCounts) -> Vec<(&'a str, usize, usize)> {
+        let mut kinds = baseline.counts.keys().collect::<Vec<_>>();
+        kinds.extend(self.counts.keys());
+        kinds.sort();
+        kinds.dedup();
+
+        kinds
+            .into_iter()
+            .filter_map(|kind| {
+                let before = baseline.counts.get(kind).copied().unwrap_or(0);
+                let after = self.counts.get(kind).copied().unwrap_or(0);
+
+                if before != after {
+                    Some((kind.as_str(), before, after))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,9 @@
+use hakana_code_info::issue::Issue;
+
+// Formats issues printed by the analyze command. Formatters are passed to init, in the
+// same way as analysis hooks, and chosen with --formatter <name>.
+pub trait IssueFormatter: Send + Sync {
+    fn get_name(&self) -> &str;
+
+    fn format(&self, issue: &Issue, file_path: &str) -> String;
+}
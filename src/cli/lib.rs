@@ -1,31 +1,55 @@
+use baseline::Baseline;
 use clap::{arg, Command};
+use hakana_analyzer::config::effective_config::EffectiveConfig;
 use hakana_analyzer::config::{self};
 use hakana_analyzer::custom_hook::CustomHook;
 use hakana_code_info::analysis_result::{
     AnalysisResult, CheckPointEntry, CheckPointEntryLevel, FullEntry, HhClientEntry, Replacement,
+    TaintPathEntry,
 };
 use hakana_code_info::data_flow::graph::{GraphKind, WholeProgramKind};
-use hakana_code_info::issue::IssueKind;
+use hakana_code_info::functionlike_identifier::get_functionlike_id;
+use hakana_code_info::issue::{Issue, IssueKind, IssueSeverity};
 use hakana_logger::{Logger, Verbosity};
-use hakana_str::Interner;
+use hakana_str::{Interner, StrId};
 use indexmap::IndexMap;
+use issue_counts::IssueCounts;
+use issue_formatter::IssueFormatter;
 use rand::Rng;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
+use sarif::SarifLog;
+use similar::TextDiff;
+use stats::CodebaseStats;
+use std::cmp::Reverse;
 use std::collections::BTreeMap;
 use std::env;
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::Path;
-use std::process::exit;
+use std::process::{self, exit};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use test_runners::junit;
 use test_runners::test_runner::TestRunner;
 
+mod baseline;
+mod call_graph;
+mod cycles;
+mod dataflow_graph;
+mod explain;
+mod issue_counts;
+pub mod issue_formatter;
+mod profile;
+mod sarif;
+mod stats;
 pub mod test_runners;
 
 pub fn init(
     analysis_hooks: Vec<Box<dyn CustomHook>>,
     migration_hooks: Vec<Box<dyn CustomHook>>,
     codegen_hooks: Vec<Box<dyn CustomHook>>,
+    issue_formatters: Vec<Box<dyn IssueFormatter>>,
     header: &str,
     test_runner: &TestRunner,
 ) {
@@ -55,13 +79,13 @@ pub fn init(
                 Command::new("analyze")
                     .alias("analyse")
                     .about("Analyzes code in the current directory")
-                    .arg(arg!(--"root" <PATH>).required(false).help(
-                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    .arg(arg!(--"root" <PATH>).required(false).multiple(true).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory. Pass more than once to analyze several roots together, with the cache and config in the first",
                     ))
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"filter" <PATH>)
@@ -74,10 +98,22 @@ pub fn init(
                             .multiple(true)
                             .help("Ignore certain files during analysis"),
                     )
+                    .arg(arg!(--"files-from" <PATH>).required(false).help(
+                        "Only analyze the files listed (one per line) in this file, or - for stdin",
+                    ))
+                    .arg(arg!(--"since" <REF>).required(false).help(
+                        "Only analyze files that have changed between this git ref and HEAD",
+                    ))
+                    .arg(arg!(--"config-print").required(false).help(
+                        "Print the effective config, after applying the config file and flags, as JSON and exit",
+                    ))
+                    .arg(arg!(--"symbol" <FQN>).required(false).help(
+                        "Only analyze this function or method (e.g. Foo\\bar or Foo\\Bar::baz), and only report its issues",
+                    ))
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"find-unused-expressions")
@@ -89,6 +125,11 @@ pub fn init(
                             .required(false)
                             .help("Find unused definitions — classes, functions, methods etc."),
                     )
+                    .arg(
+                        arg!(--"find-unused-suppressions")
+                            .required(false)
+                            .help("Find HAKANA_FIXME, HAKANA_IGNORE and HH_FIXME comments that suppress nothing"),
+                    )
                     .arg(
                         arg!(--"show-issue" <PATH>)
                             .required(false)
@@ -96,6 +137,12 @@ pub fn init(
                             .help("Only output issues of this/these type(s)"),
                     )
                     .arg(arg!(--"all-issues").required(false).help("Show all issues"))
+                    .arg(arg!(--"include-test-files").required(false).help(
+                        "Treat files matching test_files as production code, so TestOnlyCall and OnlyUsedInTests only consider <<Hakana\\TestOnly>> code to be test code",
+                    ))
+                    .arg(arg!(--"hack-version" <VERSION>).required(false).help(
+                        "The HHVM version (e.g. 4.153) the code runs on, overriding hack_version in the config",
+                    ))
                     .arg(
                         arg!(--"ignore-mixed-issues")
                             .required(false)
@@ -121,28 +168,104 @@ pub fn init(
                             .required(false)
                             .help("If set, timing info will be displayed"),
                     )
+                    .arg(arg!(--"slowest" <COUNT>).required(false).help(
+                        "How many of the slowest files to list with --show-timing (default 10)",
+                    ))
+                    .arg(arg!(--"repeat" <COUNT>).required(false).help(
+                        "Run the analysis this many times in one process, reusing the previous run's codebase, and print how long each run took",
+                    ))
+                    .arg(
+                        arg!(--"discard-first")
+                            .required(false)
+                            .help("Leave the first of the repeated runs out of the timing summary, as a warmup"),
+                    )
+                    .arg(arg!(--"profile" <PATH>).required(false).help(
+                        "File to save phase and per-file timings to, as folded stacks for inferno-flamegraph",
+                    ))
                     .arg(
                         arg!(--"no-cache")
                             .required(false)
                             .help("Whether to ignore the cache"),
                     )
+                    .arg(arg!(--"no-stub-cache").required(false).help(
+                        "Scan the builtin HHI and HSL stubs instead of loading them from .hakana_cache/stubs, which is otherwise used even with --no-cache",
+                    ))
                     .arg(
                         arg!(--"diff")
                             .required(false)
                             .help("Whether perform AST-based diffing to speed up execution"),
                     )
+                    .arg(arg!(--"only-changed-symbols").required(false).help(
+                        "Only report issues inside symbols that the AST diff found to be changed, or affected by a change. Implies --diff",
+                    ))
                     .arg(
                         arg!(--"show-issue-stats")
                             .required(false)
                             .help("Output a summary of issue counts"),
                     )
+                    .arg(arg!(--"count-only").required(false).help(
+                        "Only output the number of issues of each kind, without formatting or printing the issues themselves",
+                    ))
+                    .arg(
+                        arg!(--"counts-out" <PATH>)
+                            .required(false)
+                            .help("Save the number of issues of each kind to this file"),
+                    )
+                    .arg(arg!(--"counts-baseline" <PATH>).required(false).help(
+                        "Compare the number of issues of each kind with a file saved by --counts-out, and print what changed. Only increases cause a non-zero exit",
+                    ))
+                    .arg(arg!(--"fail-on-count-increase" <ENABLED>).required(false).help(
+                        "Whether a kind having more issues than in --counts-baseline causes a non-zero exit. Options: true (default), false",
+                    ))
                     .arg(
                         arg!(--"output" <PATH>)
                             .required(false)
                             .help("File to save output to"),
                     )
                     .arg(arg!(--"json-format" <FORMAT>).required(false).help(
-                        "Format for JSON output. Options: checkpoint (default), full, hh_client",
+                        "Format for JSON output. Options: checkpoint (default), full, hh_client, sarif, ndjson (one checkpoint entry per line)",
+                    ))
+                    .arg(arg!(--"relative-paths" <ENABLED>).required(false).help(
+                        "Whether file paths in JSON output are relative to the root directory. Options: true (default), false",
+                    ))
+                    .arg(
+                        arg!(--"baseline" <PATH>)
+                            .required(false)
+                            .help("Suppress issues that are already recorded in this baseline file"),
+                    )
+                    .arg(
+                        arg!(--"write-baseline" <PATH>)
+                            .required(false)
+                            .help("Record all current issues in a baseline file"),
+                    )
+                    .arg(arg!(--"fail-on" <LEVEL>).required(false).help(
+                        "Minimum severity that causes a non-zero exit. Options: error, warning, info (default)",
+                    ))
+                    .arg(arg!(--"max-issues" <COUNT>).required(false).help(
+                        "Only print and save the first N issues, ordered by file and position",
+                    ))
+                    .arg(arg!(--"max-fixmes" <COUNT>).required(false).help(
+                        "Fail if more than this many HAKANA_FIXME or HAKANA_IGNORE comments suppress an issue",
+                    ))
+                    .arg(arg!(--"fixme-count-out" <PATH>).required(false).help(
+                        "Save the number of HAKANA_FIXME or HAKANA_IGNORE comments that suppress an issue to this file",
+                    ))
+                    .arg(arg!(--"fixme-count-baseline" <PATH>).required(false).help(
+                        "Fail if more HAKANA_FIXME or HAKANA_IGNORE comments suppress an issue than in a file saved by --fixme-count-out",
+                    ))
+                    .arg(arg!(--"group-by" <GROUPING>).required(false).help(
+                        "Print issues grouped under a header for each issue kind. Options: kind",
+                    ))
+                    .arg(arg!(--"sort" <ORDER>).required(false).help(
+                        "Order of printed and written issues. Options: file (default), kind, severity",
+                    ))
+                    .arg(
+                        arg!(--"pretty")
+                            .required(false)
+                            .help("Print each issue with the offending source line underlined"),
+                    )
+                    .arg(arg!(--"formatter" <NAME>).required(false).help(
+                        "Print issues with a formatter registered by name, instead of the default format",
                     )),
             )
             .subcommand(
@@ -154,17 +277,23 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"migration" <PATH>)
-                            .required(true)
+                            .required_unless_present("list")
                             .help("The migration you want to perform"),
                     )
+                    .arg(
+                        arg!(--"list")
+                            .required(false)
+                            .conflicts_with("migration")
+                            .help("List the available migrations and exit"),
+                    )
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"filter" <PATH>)
@@ -186,11 +315,17 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(arg!(--"name" <PATH>).required(false).help(
                         "The codegen you want to perform — if omitted, all codegen is generated",
                     ))
+                    .arg(
+                        arg!(--"list")
+                            .required(false)
+                            .conflicts_with_all(&["name", "check", "overwrite", "diff"])
+                            .help("List the available codegen and exit"),
+                    )
                     .arg(
                         arg!(--"check")
                             .required(false)
@@ -201,10 +336,15 @@ pub fn init(
                             .required(false)
                             .help("If passed, will overwrite any conflicting files"),
                     )
+                    .arg(
+                        arg!(--"diff")
+                            .required(false)
+                            .help("If passed, will print a unified diff for any files that differ from codegen"),
+                    )
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"debug")
@@ -226,17 +366,23 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"migration" <PATH>)
-                            .required(true)
+                            .required_unless_present("list")
                             .help("The migration you want to perform"),
                     )
                     .arg(
                         arg!(--"symbols" <PATH>)
-                            .required(true)
-                            .help("The path to a list of symbols, separated by newlines"),
+                            .required_unless_present("list")
+                            .help("The path to a list of symbols, separated by newlines, or - for stdin"),
+                    )
+                    .arg(
+                        arg!(--"list")
+                            .required(false)
+                            .conflicts_with_all(&["migration", "symbols"])
+                            .help("List the available migrations and exit"),
                     )
                     .arg(
                         arg!(--"filter" <PATH>)
@@ -246,12 +392,22 @@ pub fn init(
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"debug")
                             .required(false)
                             .help("Add output for debugging"),
+                    )
+                    .arg(
+                        arg!(--"dry-run")
+                            .required(false)
+                            .help("List the files that would change without writing them"),
+                    )
+                    .arg(
+                        arg!(--"dry-run-diff")
+                            .required(false)
+                            .help("Like --dry-run, but also print a diff of the changes"),
                     ),
             )
             .subcommand(
@@ -263,7 +419,7 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"issue" <PATH>)
@@ -279,7 +435,7 @@ pub fn init(
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"debug")
@@ -296,12 +452,12 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"filter" <PATH>)
@@ -323,7 +479,7 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"issue" <PATH>)
@@ -338,12 +494,27 @@ pub fn init(
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"debug")
                             .required(false)
                             .help("Add output for debugging"),
+                    )
+                    .arg(
+                        arg!(--"no-cache")
+                            .required(false)
+                            .help("Whether to ignore the cache"),
+                    )
+                    .arg(
+                        arg!(--"dry-run")
+                            .required(false)
+                            .help("List the files that would change without writing them"),
+                    )
+                    .arg(
+                        arg!(--"dry-run-diff")
+                            .required(false)
+                            .help("Like --dry-run, but also print a diff of the changes"),
                     ),
             )
             .subcommand(
@@ -355,17 +526,17 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"max-depth" <PATH>)
                             .required(false)
-                            .help("Length of the longest allowable path — defaults to 20, and overrides config file value"),
+                            .help("Length of the longest allowable path — defaults to 20, and overrides config file value. Per-source max_depth_by_source overrides still apply"),
                     )
                     .arg(
                         arg!(--"debug")
@@ -376,6 +547,11 @@ pub fn init(
                         arg!(--"output" <PATH>)
                             .required(false)
                             .help("File to save output to"),
+                    )
+                    .arg(
+                        arg!(--"json-paths" <PATH>)
+                            .required(false)
+                            .help("File to save each finding, with its full source-to-sink path, as JSON"),
                     ),
             )
             .subcommand(
@@ -387,18 +563,29 @@ pub fn init(
                     .arg(
                         arg!(--"config" <PATH>)
                             .required(false)
-                            .help("Hakana config path — defaults to ./hakana.json"),
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
                     )
                     .arg(
                         arg!(--"threads" <PATH>)
                             .required(false)
-                            .help("How many threads to use"),
+                            .help("How many threads to use, or auto (the default) for one per core"),
                     )
                     .arg(
                         arg!(--"max-depth" <PATH>)
                             .required(false)
-                            .help("Length of the longest allowable path — defaults to 20, and overrides config file value"),
+                            .help("Length of the longest allowable path — defaults to 20, and overrides config file value. Per-source max_depth_by_source overrides still apply"),
+                    )
+                    .arg(arg!(--"from" <SYMBOL>).required(false).help(
+                        "Print the call graph reachable from this function or Class::method instead",
+                    ))
+                    .arg(
+                        arg!(--"export-graph" <PATH>)
+                            .required(false)
+                            .help("File to save the data-flow graph to, in GraphViz DOT format"),
                     )
+                    .arg(arg!(--"export-graph-from" <SYMBOL>).required(false).help(
+                        "Only export the part of the graph reachable from this function, Class::method or node id",
+                    ))
                     .arg(
                         arg!(--"debug")
                             .required(false)
@@ -438,6 +625,11 @@ pub fn init(
                             .required(false)
                             .help("How many times to repeat the test (useful for profiling)"),
                     )
+                    .arg(
+                        arg!(--"junit" <PATH>)
+                            .required(false)
+                            .help("File to write a JUnit XML report of the test results to"),
+                    )
                     .arg(arg!(<TEST> "The test to run"))
                     .arg_required_else_help(true),
             )
@@ -453,20 +645,133 @@ pub fn init(
                             .help("File to save output to"),
                     ),
             )
+            .subcommand(
+                Command::new("stats")
+                    .about("Summarizes codebase metrics — symbol counts, executable lines and mixed usage")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    ))
+                    .arg(
+                        arg!(--"config" <PATH>)
+                            .required(false)
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
+                    )
+                    .arg(
+                        arg!(--"threads" <PATH>)
+                            .required(false)
+                            .help("How many threads to use, or auto (the default) for one per core"),
+                    )
+                    .arg(
+                        arg!(--"no-cache")
+                            .required(false)
+                            .help("Whether to ignore the cache"),
+                    )
+                    .arg(
+                        arg!(--"json")
+                            .required(false)
+                            .help("Output the stats as JSON"),
+                    ),
+            )
+            .subcommand(
+                Command::new("find-dead-code")
+                    .about("Finds unused definitions and code that can never run")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    ))
+                    .arg(
+                        arg!(--"config" <PATH>)
+                            .required(false)
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
+                    )
+                    .arg(
+                        arg!(--"threads" <PATH>)
+                            .required(false)
+                            .help("How many threads to use, or auto (the default) for one per core"),
+                    )
+                    .arg(
+                        arg!(--"no-cache")
+                            .required(false)
+                            .help("Whether to ignore the cache"),
+                    ),
+            )
+            .subcommand(
+                Command::new("find-cycles")
+                    .about("Finds circular dependencies between classes or namespaces")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    ))
+                    .arg(
+                        arg!(--"config" <PATH>)
+                            .required(false)
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
+                    )
+                    .arg(
+                        arg!(--"threads" <PATH>)
+                            .required(false)
+                            .help("How many threads to use, or auto (the default) for one per core"),
+                    )
+                    .arg(
+                        arg!(--"no-cache")
+                            .required(false)
+                            .help("Whether to ignore the cache"),
+                    )
+                    .arg(arg!(--"namespace-depth" <DEPTH>).required(false).help(
+                        "Group symbols by their first DEPTH namespace segments instead of by class",
+                    ))
+                    .arg(
+                        arg!(--"max-cycles" <COUNT>)
+                            .required(false)
+                            .help("How many of the largest cycles to print. Defaults to 10"),
+                    ),
+            )
+            .subcommand(
+                Command::new("validate-config")
+                    .about("Checks the config for unknown issue kinds, invalid globs and missing paths")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    ))
+                    .arg(
+                        arg!(--"config" <PATH>)
+                            .required(false)
+                            .help("Hakana config path — defaults to ./hakana.json, then ./hakana.toml"),
+                    ),
+            )
+            .subcommand(
+                Command::new("cache-info")
+                    .about("Shows the size and contents of the cache, and whether the current build can reuse it")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    )),
+            )
+            .subcommand(
+                Command::new("cache-clear")
+                    .about("Deletes the cache, so the next run starts from scratch")
+                    .arg(arg!(--"root" <PATH>).required(false).help(
+                        "The root directory that Hakana runs in. Defaults to the current directory",
+                    )),
+            )
+            .subcommand(
+                Command::new("explain")
+                    .about("Describes an issue kind, with an example and a suggested fix")
+                    .arg(
+                        arg!([KIND])
+                            .required(false)
+                            .help("The issue kind to explain. Lists all issue kinds if omitted"),
+                    ),
+            )
             .get_matches();
 
+    if let Some(("explain", sub_matches)) = matches.subcommand() {
+        do_explain(sub_matches);
+        return;
+    }
+
     let cwd = (env::current_dir()).unwrap().to_str().unwrap().to_string();
 
     let threads = match matches.subcommand() {
         Some(("test", _)) => 1,
-        Some((_, sub_matches)) => {
-            if let Some(val) = sub_matches.value_of("threads").map(|f| f.to_string()) {
-                val.parse::<u8>().unwrap()
-            } else {
-                8
-            }
-        }
-        _ => 8,
+        Some((_, sub_matches)) => get_thread_count(sub_matches.value_of("threads")),
+        _ => get_thread_count(None),
     };
 
     let logger = match matches.subcommand() {
@@ -501,8 +806,12 @@ pub fn init(
         Some((_, sub_matches)) => Some(
             sub_matches
                 .value_of("config")
-                .unwrap_or(format!("{}/hakana.json", root_dir).as_str())
-                .to_string(),
+                .map(|config_path| config_path.to_string())
+                .unwrap_or_else(|| {
+                    config::get_default_config_path(&root_dir)
+                        .to_string_lossy()
+                        .to_string()
+                }),
         ),
         _ => panic!(),
     };
@@ -511,7 +820,14 @@ pub fn init(
 
     let cache_dir = format!("{}/.hakana_cache", root_dir);
 
-    if !Path::new(&cache_dir).is_dir() && fs::create_dir(&cache_dir).is_err() {
+    // only the subcommands that write to the cache create it, so that e.g. cache-info
+    // doesn't leave an empty one behind
+    let writes_to_cache = matches!(
+        matches.subcommand_name(),
+        Some("analyze" | "fix" | "stats" | "find-dead-code" | "find-cycles")
+    );
+
+    if writes_to_cache && !Path::new(&cache_dir).is_dir() && fs::create_dir(&cache_dir).is_err() {
         panic!("could not create aast cache directory");
     }
 
@@ -524,6 +840,7 @@ pub fn init(
                 all_custom_issues,
                 &root_dir,
                 analysis_hooks,
+                &issue_formatters,
                 config_path,
                 &cwd,
                 cache_dir,
@@ -633,6 +950,7 @@ pub fn init(
                 root_dir,
                 config_path,
                 cwd,
+                cache_dir,
                 threads,
                 logger,
                 header,
@@ -656,7 +974,7 @@ pub fn init(
                 None
             };
 
-            test_runner.run_test(
+            let test_results = test_runner.run_test(
                 sub_matches.value_of("TEST").expect("required").to_string(),
                 Arc::new(logger),
                 !sub_matches.is_present("no-cache"),
@@ -666,10 +984,80 @@ pub fn init(
                 repeat,
                 random_seed,
             );
+
+            if let Some(junit_path) = sub_matches.value_of("junit") {
+                if let Err(error) = fs::write(junit_path, junit::get_junit_xml(&test_results)) {
+                    println!(
+                        "\nERROR: could not write JUnit report to {}: {}\n",
+                        junit_path, error
+                    );
+                    exit(1);
+                }
+            }
         }
         Some(("find-executable", sub_matches)) => {
             do_find_executable(sub_matches, &root_dir, &cwd, threads, logger);
         }
+        Some(("stats", sub_matches)) => {
+            do_stats(
+                sub_matches,
+                all_custom_issues,
+                &root_dir,
+                analysis_hooks,
+                config_path,
+                &cwd,
+                cache_dir,
+                threads,
+                logger,
+                header,
+                &mut had_error,
+            );
+        }
+        Some(("find-dead-code", sub_matches)) => {
+            do_find_dead_code(
+                sub_matches,
+                all_custom_issues,
+                &root_dir,
+                analysis_hooks,
+                config_path,
+                &cwd,
+                cache_dir,
+                threads,
+                logger,
+                header,
+                &mut had_error,
+            );
+        }
+        Some(("find-cycles", sub_matches)) => {
+            do_find_cycles(
+                sub_matches,
+                all_custom_issues,
+                &root_dir,
+                analysis_hooks,
+                config_path,
+                &cwd,
+                cache_dir,
+                threads,
+                logger,
+                header,
+                &mut had_error,
+            );
+        }
+        Some(("validate-config", _)) => {
+            do_validate_config(
+                all_custom_issues,
+                &root_dir,
+                config_path,
+                &cwd,
+                &mut had_error,
+            );
+        }
+        Some(("cache-info", _)) => {
+            do_cache_info(&cache_dir, header);
+        }
+        Some(("cache-clear", _)) => {
+            do_cache_clear(&cache_dir, &mut had_error);
+        }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable!()
     }
 
@@ -685,6 +1073,7 @@ fn do_fix(
     root_dir: String,
     config_path: Option<&Path>,
     cwd: String,
+    cache_dir: String,
     threads: u8,
     logger: Logger,
     header: &str,
@@ -693,6 +1082,8 @@ fn do_fix(
     let issue_kind = IssueKind::from_str_custom(&issue_name, &all_custom_issues).unwrap();
 
     let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+    let dry_run = sub_matches.is_present("dry-run");
+    let dry_run_diff = sub_matches.is_present("dry-run-diff");
 
     let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
     config.hooks = analysis_hooks;
@@ -711,14 +1102,23 @@ fn do_fix(
             .ok();
     }
 
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
     config.allowed_issues = None;
 
+    // Only the scanned codebase is reused from the cache — without --diff every file is
+    // re-analyzed, so replacements always line up with the file contents on disk
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
         filter,
         None,
         Arc::new(config),
-        None,
+        if sub_matches.is_present("no-cache") {
+            None
+        } else {
+            Some(&cache_dir)
+        },
         threads,
         Arc::new(logger),
         header,
@@ -730,22 +1130,53 @@ fn do_fix(
     );
 
     if let Ok((mut analysis_result, successfull_run_data)) = result {
-        update_files(
-            &mut analysis_result,
-            &root_dir,
-            &successfull_run_data.interner,
-        );
+        if dry_run || dry_run_diff {
+            preview_file_updates(
+                &mut analysis_result,
+                &root_dir,
+                &successfull_run_data.interner,
+                dry_run_diff,
+            );
+        } else {
+            update_files(
+                &mut analysis_result,
+                &root_dir,
+                &successfull_run_data.interner,
+            );
+        }
     }
 }
 
-fn do_find_executable(
-    sub_matches: &clap::ArgMatches,
-    root_dir: &str,
-    cwd: &String,
-    threads: u8,
-    logger: Logger,
-) {
-    let output_file = sub_matches.value_of("output").unwrap().to_string();
+fn do_explain(sub_matches: &clap::ArgMatches) {
+    let kind = if let Some(kind) = sub_matches.value_of("KIND") {
+        kind
+    } else {
+        println!("Known issue kinds:\n");
+
+        for explanation in explain::ISSUE_EXPLANATIONS {
+            println!("{}", explanation.kind);
+        }
+
+        println!("\nRun `hakana explain <KIND>` for details");
+        return;
+    };
+
+    if let Some(explanation) = explain::find_explanation(kind) {
+        print!("{}", explanation.format());
+    } else {
+        println!("Unknown issue kind {}", kind);
+        exit(1);
+    }
+}
+
+fn do_find_executable(
+    sub_matches: &clap::ArgMatches,
+    root_dir: &str,
+    cwd: &String,
+    threads: u8,
+    logger: Logger,
+) {
+    let output_file = sub_matches.value_of("output").unwrap().to_string();
     let config = config::Config::new(root_dir.to_string(), FxHashSet::default());
 
     match executable_finder::scan_files(
@@ -781,21 +1212,22 @@ fn do_find_executable(
     }
 }
 
-fn do_remove_unused_fixmes(
+fn do_stats(
     sub_matches: &clap::ArgMatches,
-    root_dir: &String,
     all_custom_issues: FxHashSet<String>,
+    root_dir: &str,
     analysis_hooks: Vec<Box<dyn CustomHook>>,
     config_path: Option<&Path>,
     cwd: &String,
+    cache_dir: String,
     threads: u8,
     logger: Logger,
     header: &str,
+    had_error: &mut bool,
 ) {
-    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
-
-    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
+    let output_json = sub_matches.is_present("json");
 
+    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
     config.hooks = analysis_hooks;
 
     let config_path = config_path.unwrap();
@@ -807,21 +1239,44 @@ fn do_remove_unused_fixmes(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
-    config.allowed_issues = None;
 
-    config.find_unused_expressions = true;
-    config.find_unused_definitions = true;
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
 
-    config.remove_fixmes = true;
+    let config = Arc::new(config);
+    let logger = Arc::new(logger);
+
+    let executable_lines = match executable_finder::scan_files(
+        &vec![root_dir.to_string()],
+        None,
+        &config,
+        threads,
+        logger.clone(),
+    ) {
+        Ok(file_infos) => file_infos
+            .iter()
+            .map(|file_info| file_info.executable_lines.len())
+            .sum(),
+        Err(_) => {
+            println!("\nERROR: Could not find executable lines\n");
+            *had_error = true;
+            return;
+        }
+    };
 
+    // mixed source counts are only available after analysis
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
-        filter,
         None,
-        Arc::new(config),
         None,
+        config,
+        if sub_matches.is_present("no-cache") {
+            None
+        } else {
+            Some(&cache_dir)
+        },
         threads,
-        Arc::new(logger),
+        logger,
         header,
         interner,
         None,
@@ -830,53 +1285,38 @@ fn do_remove_unused_fixmes(
         || {},
     );
 
-    if let Ok((mut analysis_result, successful_run_data)) = result {
-        update_files(
-            &mut analysis_result,
-            root_dir,
-            &successful_run_data.interner,
+    if let Ok((analysis_result, successful_run_data)) = result {
+        let stats = CodebaseStats::new(
+            &successful_run_data.codebase,
+            &analysis_result,
+            executable_lines,
         );
+
+        if output_json {
+            println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+        } else {
+            println!("{}", stats.get_table());
+        }
+    } else {
+        *had_error = true;
     }
 }
 
-fn do_add_fixmes(
+fn do_find_dead_code(
     sub_matches: &clap::ArgMatches,
     all_custom_issues: FxHashSet<String>,
-    root_dir: &String,
+    root_dir: &str,
     analysis_hooks: Vec<Box<dyn CustomHook>>,
     config_path: Option<&Path>,
     cwd: &String,
+    cache_dir: String,
     threads: u8,
     logger: Logger,
     header: &str,
+    had_error: &mut bool,
 ) {
-    let filter_issue_strings = sub_matches
-        .values_of("issue")
-        .map(|values| values.collect::<FxHashSet<_>>());
-
-    let mut issue_kinds_filter = FxHashSet::default();
-
-    if let Some(filter_issue_strings) = filter_issue_strings {
-        for filter_issue_string in filter_issue_strings {
-            if let Ok(issue_kind) =
-                IssueKind::from_str_custom(filter_issue_string, &all_custom_issues)
-            {
-                issue_kinds_filter.insert(issue_kind);
-            } else {
-                println!("Invalid issue type {}", filter_issue_string);
-                exit(1);
-            }
-        }
-    }
-
-    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
-
-    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
-
-    config.issues_to_fix.extend(issue_kinds_filter);
+    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
     config.hooks = analysis_hooks;
-    config.find_unused_expressions = true;
-    config.find_unused_definitions = true;
 
     let config_path = config_path.unwrap();
 
@@ -887,16 +1327,39 @@ fn do_add_fixmes(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
-    config.allowed_issues = None;
 
-    config.add_fixmes = true;
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    // unused definitions are found by the orchestrator once analysis is done, and code
+    // after a return or throw is only flagged when looking for unused expressions
+    config.find_unused_definitions = true;
+    config.find_unused_expressions = true;
+    config.allowed_issues = Some(FxHashSet::from_iter([
+        IssueKind::UnusedClass,
+        IssueKind::UnusedInterface,
+        IssueKind::UnusedTrait,
+        IssueKind::UnusedTypeDefinition,
+        IssueKind::UnusedFunction,
+        IssueKind::UnusedPrivateMethod,
+        IssueKind::UnusedInheritedMethod,
+        IssueKind::UnusedPublicOrProtectedMethod,
+        IssueKind::UnusedPrivateProperty,
+        IssueKind::UnusedPublicOrProtectedProperty,
+        IssueKind::UnusedXhpAttribute,
+        IssueKind::UnevaluatedCode,
+    ]));
 
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
-        filter,
         None,
-        Arc::new(config),
         None,
+        Arc::new(config),
+        if sub_matches.is_present("no-cache") {
+            None
+        } else {
+            Some(&cache_dir)
+        },
         threads,
         Arc::new(logger),
         header,
@@ -907,45 +1370,68 @@ fn do_add_fixmes(
         || {},
     );
 
-    if let Ok((mut analysis_result, successful_run_data)) = result {
-        update_files(
-            &mut analysis_result,
-            root_dir,
-            &successful_run_data.interner,
-        );
+    if let Ok((analysis_result, successful_run_data)) = result {
+        let mut issue_lines_by_kind = BTreeMap::new();
+
+        for (file_path, issues) in
+            analysis_result.get_all_issues(&successful_run_data.interner, root_dir, true)
+        {
+            for issue in issues {
+                issue_lines_by_kind
+                    .entry(issue.kind.to_string())
+                    .or_insert_with(Vec::new)
+                    .push(issue.format(&file_path));
+            }
+        }
+
+        if issue_lines_by_kind.is_empty() {
+            println!("\nNo dead code found!\n");
+        } else {
+            *had_error = true;
+            print_issues_by_kind(issue_lines_by_kind);
+        }
+    } else {
+        *had_error = true;
     }
 }
 
-fn do_migrate(
+fn do_find_cycles(
     sub_matches: &clap::ArgMatches,
-    root_dir: &String,
     all_custom_issues: FxHashSet<String>,
-    migration_hooks: Vec<Box<dyn CustomHook>>,
+    root_dir: &str,
+    analysis_hooks: Vec<Box<dyn CustomHook>>,
     config_path: Option<&Path>,
     cwd: &String,
+    cache_dir: String,
     threads: u8,
     logger: Logger,
     header: &str,
+    had_error: &mut bool,
 ) {
-    let migration_name = sub_matches.value_of("migration").unwrap().to_string();
-    let migration_source = sub_matches.value_of("symbols").unwrap().to_string();
+    let namespace_depth = match sub_matches.value_of("namespace-depth") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(depth) if depth > 0 => Some(depth),
+            _ => {
+                println!("--namespace-depth must be a positive number");
+                exit(1);
+            }
+        },
+        None => None,
+    };
 
-    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
-    config.hooks = migration_hooks
-        .into_iter()
-        .filter(|m| {
-            if let Some(name) = m.get_migration_name() {
-                migration_name == name
-            } else {
-                false
+    let max_cycles = match sub_matches.value_of("max-cycles") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(max_cycles) => max_cycles,
+            Err(_) => {
+                println!("--max-cycles must be a number");
+                exit(1);
             }
-        })
-        .collect();
+        },
+        None => 10,
+    };
 
-    if config.hooks.is_empty() {
-        println!("Migration {} not recognised", migration_name);
-        exit(1);
-    }
+    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
+    config.hooks = analysis_hooks;
 
     let config_path = config_path.unwrap();
 
@@ -956,38 +1442,20 @@ fn do_migrate(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
-    config.allowed_issues = None;
-
-    let file_path = format!("{}/{}", cwd, migration_source);
-
-    let buf = fs::read_to_string(file_path.clone());
-
-    if let Ok(contents) = buf {
-        config.migration_symbols = contents
-            .lines()
-            .map(|v| {
-                let mut parts = v.split(',').collect::<Vec<_>>();
-                let first_part = parts.remove(0);
-                (first_part.to_string(), parts.join(","))
-            })
-            .collect();
-        config.in_migration = true;
-    } else {
-        println!(
-            "\nERROR: File {} does not exist or could not be read\n",
-            file_path
-        );
-        exit(1);
-    }
 
-    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
 
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
-        filter,
         None,
-        Arc::new(config),
         None,
+        Arc::new(config),
+        if sub_matches.is_present("no-cache") {
+            None
+        } else {
+            Some(&cache_dir)
+        },
         threads,
         Arc::new(logger),
         header,
@@ -998,87 +1466,638 @@ fn do_migrate(
         || {},
     );
 
-    if let Ok((mut analysis_result, successful_run_data)) = result {
-        update_files(
-            &mut analysis_result,
-            root_dir,
+    if let Ok((analysis_result, successful_run_data)) = result {
+        let cycles = cycles::find_cycles(
+            &analysis_result.symbol_references,
+            &successful_run_data.codebase,
             &successful_run_data.interner,
+            namespace_depth,
         );
+
+        if cycles.is_empty() {
+            println!("\nNo cycles found!\n");
+            return;
+        }
+
+        *had_error = true;
+
+        if cycles.len() > max_cycles {
+            println!(
+                "\nFound {} cycles, showing the largest {}",
+                cycles.len(),
+                max_cycles
+            );
+        } else {
+            println!("\nFound {} cycles", cycles.len());
+        }
+
+        for (i, cycle) in cycles.iter().take(max_cycles).enumerate() {
+            println!("\nCycle {} ({} members)\n", i + 1, cycle.len());
+
+            for member in cycle {
+                println!("{}", member);
+            }
+        }
+    } else {
+        *had_error = true;
     }
 }
 
-fn do_migration_candidates(
-    sub_matches: &clap::ArgMatches,
-    root_dir: &str,
+fn do_validate_config(
     all_custom_issues: FxHashSet<String>,
-    migration_hooks: Vec<Box<dyn CustomHook>>,
+    root_dir: &str,
     config_path: Option<&Path>,
     cwd: &String,
-    threads: u8,
-    logger: Logger,
-    header: &str,
+    had_error: &mut bool,
 ) {
-    let migration_name = sub_matches.value_of("migration").unwrap().to_string();
+    let config_path = config_path.unwrap();
 
-    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
-    config.hooks = migration_hooks
-        .into_iter()
-        .filter(|m| {
-            if let Some(name) = m.get_migration_name() {
-                migration_name == name
-            } else {
-                false
-            }
-        })
-        .collect();
+    if !config_path.exists() {
+        println!("\nConfig file {} does not exist\n", config_path.display());
+        *had_error = true;
+        return;
+    }
 
-    config.in_migration = true;
+    let mut problems =
+        config::validation::get_config_problems(cwd, config_path, &all_custom_issues);
 
-    if config.hooks.is_empty() {
-        println!("Migration {} not recognised", migration_name);
-        exit(1);
+    // loading a config with the problems above would panic
+    if problems.is_empty() {
+        let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
+
+        if let Err(error) = config.update_from_file(cwd, config_path, &mut Interner::default()) {
+            problems.push(error.to_string());
+        } else if let Err(error) = config.update_from_overlay_files() {
+            problems.push(error.to_string());
+        }
     }
 
-    let config_path = config_path.unwrap();
+    if problems.is_empty() {
+        println!("\n{} is valid\n", config_path.display());
+    } else {
+        *had_error = true;
 
-    let mut interner = Interner::default();
+        println!(
+            "\nFound {} problems in {}\n",
+            problems.len(),
+            config_path.display()
+        );
 
-    if config_path.exists() {
-        config
-            .update_from_file(cwd, config_path, &mut interner)
-            .ok();
+        for problem in problems {
+            println!("{}", problem);
+        }
     }
-    config.allowed_issues = None;
-
-    let config = Arc::new(config);
+}
 
-    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+fn do_cache_info(cache_dir: &String, header: &str) {
+    // written at the end of every cached run, and compared against the current build's header
+    let cached_header = fs::read_to_string(format!("{}/buildinfo", cache_dir)).ok();
 
-    let result = hakana_orchestrator::scan_and_analyze(
-        Vec::new(),
-        filter,
-        None,
-        config.clone(),
-        None,
-        threads,
-        Arc::new(logger),
-        header,
-        interner,
-        None,
-        None,
-        None,
-        || {},
+    println!("Cache directory: {}", cache_dir);
+    println!(
+        "Size:            {}",
+        format_byte_count(get_directory_size(Path::new(cache_dir)))
+    );
+    println!(
+        "Cached files:    {}",
+        hakana_orchestrator::get_cached_file_count(cache_dir)
+            .map_or("none".to_string(), |file_count| file_count.to_string())
     );
 
-    if let Ok(result) = result {
-        println!("\nSymbols to migrate:\n");
-        for config_hook in &config.hooks {
-            let migration_candidates =
-                config_hook.get_candidates(&result.1.codebase, &result.1.interner, &result.0);
+    if let Some(cached_header) = &cached_header {
+        println!("Built with:");
 
-            for migration_candidate in migration_candidates {
-                println!("{}", migration_candidate);
-            }
+        for line in cached_header.trim().lines() {
+            println!("    {}", line);
+        }
+    }
+
+    println!(
+        "Status:          {}",
+        match cached_header {
+            None => "empty",
+            Some(cached_header) if cached_header == header => "up to date",
+            Some(_) => "stale, as it was built by a different version of Hakana",
+        }
+    );
+}
+
+fn do_cache_clear(cache_dir: &String, had_error: &mut bool) {
+    // never follow a symlink, in case it points somewhere that isn't ours to delete
+    let Ok(metadata) = fs::symlink_metadata(cache_dir) else {
+        println!("No cache to remove at {}", cache_dir);
+        return;
+    };
+
+    if !metadata.is_dir() {
+        println!("\nERROR: {} is not a directory\n", cache_dir);
+        *had_error = true;
+        return;
+    }
+
+    let size = get_directory_size(Path::new(cache_dir));
+
+    if let Err(error) = fs::remove_dir_all(cache_dir) {
+        println!("\nERROR: Could not remove {}: {}\n", cache_dir, error);
+        *had_error = true;
+        return;
+    }
+
+    println!("Removed {} ({})", cache_dir, format_byte_count(size));
+}
+
+fn get_directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => get_directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn format_byte_count(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+// Prints how many issues of each kind were found, most common first, without formatting
+// any of them. Returns whether there were any issues.
+fn print_issue_counts(
+    analysis_result: &AnalysisResult,
+    config: &config::Config,
+    fail_on: IssueSeverity,
+    had_error: &mut bool,
+) -> bool {
+    if analysis_result
+        .emitted_issues
+        .values()
+        .chain(analysis_result.emitted_definition_issues.values())
+        .flatten()
+        .any(|issue| config.get_issue_severity(&issue.kind) >= fail_on)
+    {
+        *had_error = true;
+    }
+
+    let issue_counts = IssueCounts::from_analysis_result(analysis_result);
+    let counts_by_kind = issue_counts.get_counts_by_frequency();
+
+    for (kind, count) in &counts_by_kind {
+        println!("{}\t{}", kind, count);
+    }
+
+    if !counts_by_kind.is_empty() {
+        println!(
+            "\n{} issues in total",
+            counts_by_kind.iter().map(|(_, count)| count).sum::<usize>()
+        );
+    }
+
+    !counts_by_kind.is_empty()
+}
+
+fn print_issues_by_kind(issue_lines_by_kind: BTreeMap<String, Vec<String>>) {
+    for (issue_kind, issue_lines) in issue_lines_by_kind {
+        println!("\n{} ({})\n", issue_kind, issue_lines.len());
+
+        for issue_line in issue_lines {
+            println!("{}", issue_line);
+        }
+    }
+}
+
+// Groups issues under the root each file belongs to, in the order the roots were given,
+// with file paths relative to that root
+fn get_issues_by_root<'a>(
+    analysis_result: &'a AnalysisResult,
+    interner: &Interner,
+    config: &'a config::Config,
+) -> Vec<(&'a str, BTreeMap<String, Vec<&'a Issue>>)> {
+    if config.root_dirs.len() < 2 {
+        return vec![(
+            config.root_dir.as_str(),
+            analysis_result.get_all_issues(interner, &config.root_dir, true),
+        )];
+    }
+
+    let mut issues_by_root = config
+        .root_dirs
+        .iter()
+        .map(|root_dir| (root_dir.as_str(), BTreeMap::new()))
+        .collect::<Vec<_>>();
+
+    for (file_path, issues) in analysis_result.get_all_issues(interner, &config.root_dir, false) {
+        let file_root_dir = config.get_root_dir_for_path(&file_path);
+
+        let relative_path = Path::new(&file_path)
+            .strip_prefix(file_root_dir)
+            .map(|relative_path| relative_path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file_path.clone());
+
+        if let Some((_, root_issues)) = issues_by_root
+            .iter_mut()
+            .find(|(root_dir, _)| *root_dir == file_root_dir)
+        {
+            root_issues.insert(relative_path, issues);
+        }
+    }
+
+    issues_by_root
+}
+
+fn do_remove_unused_fixmes(
+    sub_matches: &clap::ArgMatches,
+    root_dir: &String,
+    all_custom_issues: FxHashSet<String>,
+    analysis_hooks: Vec<Box<dyn CustomHook>>,
+    config_path: Option<&Path>,
+    cwd: &String,
+    threads: u8,
+    logger: Logger,
+    header: &str,
+) {
+    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+
+    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
+
+    config.hooks = analysis_hooks;
+
+    let config_path = config_path.unwrap();
+
+    let mut interner = Interner::default();
+
+    if config_path.exists() {
+        config
+            .update_from_file(cwd, config_path, &mut interner)
+            .ok();
+    }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    config.allowed_issues = None;
+
+    config.find_unused_expressions = true;
+    config.find_unused_definitions = true;
+
+    config.remove_fixmes = true;
+
+    let result = hakana_orchestrator::scan_and_analyze(
+        Vec::new(),
+        filter,
+        None,
+        Arc::new(config),
+        None,
+        threads,
+        Arc::new(logger),
+        header,
+        interner,
+        None,
+        None,
+        None,
+        || {},
+    );
+
+    if let Ok((mut analysis_result, successful_run_data)) = result {
+        update_files(
+            &mut analysis_result,
+            root_dir,
+            &successful_run_data.interner,
+        );
+    }
+}
+
+fn do_add_fixmes(
+    sub_matches: &clap::ArgMatches,
+    all_custom_issues: FxHashSet<String>,
+    root_dir: &String,
+    analysis_hooks: Vec<Box<dyn CustomHook>>,
+    config_path: Option<&Path>,
+    cwd: &String,
+    threads: u8,
+    logger: Logger,
+    header: &str,
+) {
+    let filter_issue_strings = sub_matches
+        .values_of("issue")
+        .map(|values| values.collect::<FxHashSet<_>>());
+
+    let mut issue_kinds_filter = FxHashSet::default();
+
+    if let Some(filter_issue_strings) = filter_issue_strings {
+        for filter_issue_string in filter_issue_strings {
+            if let Ok(issue_kind) =
+                IssueKind::from_str_custom(filter_issue_string, &all_custom_issues)
+            {
+                issue_kinds_filter.insert(issue_kind);
+            } else {
+                println!("Invalid issue type {}", filter_issue_string);
+                exit(1);
+            }
+        }
+    }
+
+    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+
+    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
+
+    config.issues_to_fix.extend(issue_kinds_filter);
+    config.hooks = analysis_hooks;
+    config.find_unused_expressions = true;
+    config.find_unused_definitions = true;
+
+    let config_path = config_path.unwrap();
+
+    let mut interner = Interner::default();
+
+    if config_path.exists() {
+        config
+            .update_from_file(cwd, config_path, &mut interner)
+            .ok();
+    }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    config.allowed_issues = None;
+
+    config.add_fixmes = true;
+
+    let result = hakana_orchestrator::scan_and_analyze(
+        Vec::new(),
+        filter,
+        None,
+        Arc::new(config),
+        None,
+        threads,
+        Arc::new(logger),
+        header,
+        interner,
+        None,
+        None,
+        None,
+        || {},
+    );
+
+    if let Ok((mut analysis_result, successful_run_data)) = result {
+        update_files(
+            &mut analysis_result,
+            root_dir,
+            &successful_run_data.interner,
+        );
+    }
+}
+
+fn do_migrate(
+    sub_matches: &clap::ArgMatches,
+    root_dir: &String,
+    all_custom_issues: FxHashSet<String>,
+    migration_hooks: Vec<Box<dyn CustomHook>>,
+    config_path: Option<&Path>,
+    cwd: &String,
+    threads: u8,
+    logger: Logger,
+    header: &str,
+) {
+    if sub_matches.is_present("list") {
+        print_hook_names(
+            &migration_hooks,
+            |hook| hook.get_migration_name(),
+            "migrations",
+        );
+        return;
+    }
+
+    let migration_name = sub_matches.value_of("migration").unwrap().to_string();
+    let migration_source = sub_matches.value_of("symbols").unwrap().to_string();
+    let dry_run = sub_matches.is_present("dry-run");
+    let dry_run_diff = sub_matches.is_present("dry-run-diff");
+
+    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
+    config.hooks = migration_hooks
+        .into_iter()
+        .filter(|m| {
+            if let Some(name) = m.get_migration_name() {
+                migration_name == name
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    if config.hooks.is_empty() {
+        println!("Migration {} not recognised", migration_name);
+        exit(1);
+    }
+
+    let config_path = config_path.unwrap();
+
+    let mut interner = Interner::default();
+
+    if config_path.exists() {
+        config
+            .update_from_file(cwd, config_path, &mut interner)
+            .ok();
+    }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    config.allowed_issues = None;
+
+    let contents = if migration_source == "-" {
+        match io::read_to_string(io::stdin()) {
+            Ok(contents) if !contents.trim().is_empty() => contents,
+            Ok(_) => {
+                println!("\nERROR: No symbols were provided on stdin\n");
+                exit(1);
+            }
+            Err(error) => {
+                println!("\nERROR: Could not read symbols from stdin: {}\n", error);
+                exit(1);
+            }
+        }
+    } else {
+        let file_path = format!("{}/{}", cwd, migration_source);
+
+        if let Ok(contents) = fs::read_to_string(file_path.clone()) {
+            contents
+        } else {
+            println!(
+                "\nERROR: File {} does not exist or could not be read\n",
+                file_path
+            );
+            exit(1);
+        }
+    };
+
+    config.migration_symbols = contents
+        .lines()
+        .map(|v| {
+            let mut parts = v.split(',').collect::<Vec<_>>();
+            let first_part = parts.remove(0);
+            (first_part.to_string(), parts.join(","))
+        })
+        .collect();
+    config.in_migration = true;
+
+    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+
+    let result = hakana_orchestrator::scan_and_analyze(
+        Vec::new(),
+        filter,
+        None,
+        Arc::new(config),
+        None,
+        threads,
+        Arc::new(logger),
+        header,
+        interner,
+        None,
+        None,
+        None,
+        || {},
+    );
+
+    if let Ok((mut analysis_result, successful_run_data)) = result {
+        if dry_run || dry_run_diff {
+            preview_file_updates(
+                &mut analysis_result,
+                root_dir,
+                &successful_run_data.interner,
+                dry_run_diff,
+            );
+        } else {
+            update_files(
+                &mut analysis_result,
+                root_dir,
+                &successful_run_data.interner,
+            );
+        }
+    }
+}
+
+fn do_migration_candidates(
+    sub_matches: &clap::ArgMatches,
+    root_dir: &str,
+    all_custom_issues: FxHashSet<String>,
+    migration_hooks: Vec<Box<dyn CustomHook>>,
+    config_path: Option<&Path>,
+    cwd: &String,
+    threads: u8,
+    logger: Logger,
+    header: &str,
+) {
+    if sub_matches.is_present("list") {
+        print_hook_names(
+            &migration_hooks,
+            |hook| hook.get_migration_name(),
+            "migrations",
+        );
+        return;
+    }
+
+    let migration_name = sub_matches.value_of("migration").unwrap().to_string();
+
+    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
+    config.hooks = migration_hooks
+        .into_iter()
+        .filter(|m| {
+            if let Some(name) = m.get_migration_name() {
+                migration_name == name
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    config.in_migration = true;
+
+    if config.hooks.is_empty() {
+        println!("Migration {} not recognised", migration_name);
+        exit(1);
+    }
+
+    let config_path = config_path.unwrap();
+
+    let mut interner = Interner::default();
+
+    if config_path.exists() {
+        config
+            .update_from_file(cwd, config_path, &mut interner)
+            .ok();
+    }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    config.allowed_issues = None;
+
+    let config = Arc::new(config);
+
+    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+
+    let result = hakana_orchestrator::scan_and_analyze(
+        Vec::new(),
+        filter,
+        None,
+        config.clone(),
+        None,
+        threads,
+        Arc::new(logger),
+        header,
+        interner,
+        None,
+        None,
+        None,
+        || {},
+    );
+
+    if let Ok(result) = result {
+        println!("\nSymbols to migrate:\n");
+        for config_hook in &config.hooks {
+            let migration_candidates =
+                config_hook.get_candidates(&result.1.codebase, &result.1.interner, &result.0);
+
+            for migration_candidate in migration_candidates {
+                println!("{}", migration_candidate);
+            }
+        }
+    }
+}
+
+fn print_hook_names(
+    hooks: &[Box<dyn CustomHook>],
+    get_name: fn(&dyn CustomHook) -> Option<&str>,
+    kind: &str,
+) {
+    let mut hook_names = hooks
+        .iter()
+        .filter_map(|hook| get_name(hook.as_ref()).map(|name| (name, hook.get_description())))
+        .collect::<Vec<_>>();
+
+    if hook_names.is_empty() {
+        println!("No {} available", kind);
+        return;
+    }
+
+    hook_names.sort();
+
+    for (name, description) in hook_names {
+        if let Some(description) = description {
+            println!("{} — {}", name, description);
+        } else {
+            println!("{}", name);
         }
     }
 }
@@ -1095,9 +2114,15 @@ fn do_codegen(
     logger: Logger,
     header: &str,
 ) {
+    if sub_matches.is_present("list") {
+        print_hook_names(&codegen_hooks, |hook| hook.get_codegen_name(), "codegen");
+        return;
+    }
+
     let codegen_name = sub_matches.value_of("name");
     let check_codegen = sub_matches.is_present("check");
     let overwrite_codegen = sub_matches.is_present("overwrite");
+    let show_diff = sub_matches.is_present("diff");
 
     let output_file = sub_matches.value_of("output").map(|f| f.to_string());
 
@@ -1131,6 +2156,10 @@ fn do_codegen(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
     config.allowed_issues = None;
 
     let config = Arc::new(config);
@@ -1153,6 +2182,7 @@ fn do_codegen(
 
     if let Ok(result) = result {
         let mut errors = vec![];
+        let mut diffs = vec![];
         let mut updated_count = 0;
         let mut verified_count = 0;
 
@@ -1169,6 +2199,24 @@ fn do_codegen(
                         let existing_contents = fs::read_to_string(path).unwrap();
                         if existing_contents.trim() != info.trim() {
                             if check_codegen || !overwrite_codegen {
+                                if show_diff {
+                                    let relative_path = name
+                                        .strip_prefix(root_dir)
+                                        .map(|p| p.trim_start_matches('/'))
+                                        .unwrap_or(&name);
+
+                                    diffs.push(
+                                        TextDiff::from_lines(&existing_contents, info)
+                                            .unified_diff()
+                                            .context_radius(3)
+                                            .header(
+                                                &format!("a/{}", relative_path),
+                                                &format!("b/{}", relative_path),
+                                            )
+                                            .to_string(),
+                                    );
+                                }
+
                                 errors.push((name, "differs from codegen".to_string()));
                                 continue;
                             }
@@ -1206,6 +2254,10 @@ fn do_codegen(
             write_codegen_output_files(output_file, cwd, &errors);
         }
 
+        if !diffs.is_empty() {
+            println!("\n{}", diffs.join("\n"));
+        }
+
         if !errors.is_empty() {
             println!(
                 "\nCodegen verification failed.\n\nUse hakana codegen --overwrite to regenerate\n\n{}\n\n",
@@ -1245,6 +2297,10 @@ fn do_find_paths(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
     config.allowed_issues = None;
 
     config.security_config.max_depth =
@@ -1256,7 +2312,17 @@ fn do_find_paths(
 
     config.hooks = analysis_hooks;
 
+    let export_graph_file = sub_matches.value_of("export-graph").map(|f| f.to_string());
+    let export_graph_from = sub_matches.value_of("export-graph-from");
+
+    if export_graph_from.is_some() && export_graph_file.is_none() {
+        println!("\nERROR: --export-graph-from requires --export-graph\n");
+        *had_error = true;
+        return;
+    }
+
     let root_dir = config.root_dir.clone();
+    let max_depth = config.security_config.max_depth;
 
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
@@ -1275,6 +2341,67 @@ fn do_find_paths(
     );
 
     if let Ok((analysis_result, successful_run_data)) = result {
+        if let Some(from) = sub_matches.value_of("from") {
+            if let Some(functionlike_id) = get_functionlike_id(from, &successful_run_data.interner)
+                .filter(|id| {
+                    successful_run_data
+                        .codebase
+                        .functionlike_infos
+                        .contains_key(id)
+                })
+            {
+                for line in call_graph::get_call_graph_lines(
+                    functionlike_id,
+                    &analysis_result.symbol_references,
+                    &successful_run_data.codebase,
+                    &successful_run_data.interner,
+                    &root_dir,
+                    max_depth,
+                ) {
+                    println!("{}", line);
+                }
+            } else {
+                println!("\nERROR: Could not find function or method {}\n", from);
+                *had_error = true;
+            }
+
+            return;
+        }
+
+        if let Some(export_graph_file) = export_graph_file {
+            let start_nodes = export_graph_from.map(|from| {
+                dataflow_graph::get_start_nodes(
+                    from,
+                    &analysis_result.program_dataflow_graph,
+                    &successful_run_data.interner,
+                )
+            });
+
+            if let (Some(from), Some(start_nodes)) = (export_graph_from, &start_nodes) {
+                if start_nodes.is_empty() {
+                    println!("\nERROR: Could not find a data-flow node for {}\n", from);
+                    *had_error = true;
+                    return;
+                }
+            }
+
+            let output_path = if export_graph_file.starts_with('/') {
+                export_graph_file
+            } else {
+                format!("{}/{}", cwd, export_graph_file)
+            };
+
+            let lines = dataflow_graph::get_dot_lines(
+                &analysis_result.program_dataflow_graph,
+                start_nodes.as_ref(),
+                &successful_run_data.interner,
+                &root_dir,
+            );
+
+            fs::write(&output_path, lines.join("\n") + "\n")
+                .unwrap_or_else(|_| panic!("Could not write file {}", &output_path));
+        }
+
         for (file_path, issues) in
             analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true)
         {
@@ -1313,9 +2440,14 @@ fn do_security_check(
             .update_from_file(cwd, config_path, &mut interner)
             .ok();
     }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
     config.allowed_issues = None;
 
     let output_file = sub_matches.value_of("output").map(|f| f.to_string());
+    let json_paths_file = sub_matches.value_of("json-paths").map(|f| f.to_string());
 
     config.security_config.max_depth =
         if let Some(val) = sub_matches.value_of("max-depth").map(|f| f.to_string()) {
@@ -1328,11 +2460,13 @@ fn do_security_check(
 
     let root_dir = config.root_dir.clone();
 
+    let config = Arc::new(config);
+
     let result = hakana_orchestrator::scan_and_analyze(
         Vec::new(),
         None,
         None,
-        Arc::new(config),
+        config.clone(),
         None,
         threads,
         Arc::new(logger),
@@ -1363,6 +2497,21 @@ fn do_security_check(
                 output_file,
                 None,
                 cwd,
+                &root_dir,
+                true,
+                &analysis_result,
+                &successful_run_data.interner,
+                &config,
+                header,
+                IssueOrder::File,
+            );
+        }
+
+        if let Some(json_paths_file) = json_paths_file {
+            write_taint_paths_file(
+                json_paths_file,
+                cwd,
+                &root_dir,
                 &analysis_result,
                 &successful_run_data.interner,
             );
@@ -1370,11 +2519,48 @@ fn do_security_check(
     }
 }
 
+fn write_taint_paths_file(
+    output_file: String,
+    cwd: &String,
+    root_dir: &str,
+    analysis_result: &AnalysisResult,
+    interner: &Interner,
+) {
+    let output_path = if output_file.starts_with('/') {
+        output_file
+    } else {
+        format!("{}/{}", cwd, output_file)
+    };
+
+    fs::write(
+        &output_path,
+        get_taint_paths_json(root_dir, analysis_result, interner),
+    )
+    .unwrap_or_else(|_| panic!("Could not write file {}", &output_path));
+}
+
+pub(crate) fn get_taint_paths_json(
+    root_dir: &str,
+    analysis_result: &AnalysisResult,
+    interner: &Interner,
+) -> String {
+    let mut entries = vec![];
+
+    for (file_path, issues) in analysis_result.get_all_issues(interner, root_dir, true) {
+        for issue in issues {
+            entries.push(TaintPathEntry::from_issue(issue, &file_path));
+        }
+    }
+
+    serde_json::to_string_pretty(&entries).unwrap()
+}
+
 fn do_analysis(
     sub_matches: &clap::ArgMatches,
     all_custom_issues: FxHashSet<String>,
     root_dir: &str,
     analysis_hooks: Vec<Box<dyn CustomHook>>,
+    issue_formatters: &[Box<dyn IssueFormatter>],
     config_path: Option<&Path>,
     cwd: &String,
     cache_dir: String,
@@ -1387,17 +2573,146 @@ fn do_analysis(
 
     let output_file = sub_matches.value_of("output").map(|f| f.to_string());
     let output_format = sub_matches.value_of("json-format").map(|f| f.to_string());
+    let profile_file = sub_matches.value_of("profile").map(|f| f.to_string());
+    let relative_paths = match sub_matches.value_of("relative-paths") {
+        None | Some("true") => true,
+        Some("false") => false,
+        Some(value) => {
+            println!("Invalid value {} for --relative-paths", value);
+            exit(1);
+        }
+    };
+    let baseline_path = sub_matches.value_of("baseline");
+    let write_baseline_path = sub_matches.value_of("write-baseline");
+    let counts_out_path = sub_matches.value_of("counts-out");
+    let counts_baseline_path = sub_matches.value_of("counts-baseline");
+    let fail_on_count_increase = match sub_matches.value_of("fail-on-count-increase") {
+        None | Some("true") => true,
+        Some("false") => false,
+        Some(value) => {
+            println!("Invalid value {} for --fail-on-count-increase", value);
+            exit(1);
+        }
+    };
 
     let ignored = sub_matches
         .values_of("ignore")
         .map(|values| values.map(|f| f.to_string()).collect::<FxHashSet<_>>());
     let find_unused_expressions = sub_matches.is_present("find-unused-expressions");
     let find_unused_definitions = sub_matches.is_present("find-unused-definitions");
+    let find_unused_suppressions = sub_matches.is_present("find-unused-suppressions");
     let show_mixed_function_counts = sub_matches.is_present("show-mixed-function-counts");
     let show_symbol_map = sub_matches.is_present("show-symbol-map");
     let ignore_mixed_issues = sub_matches.is_present("ignore-mixed-issues");
+    let include_test_files = sub_matches.is_present("include-test-files");
     let show_issue_stats = sub_matches.is_present("show-issue-stats");
-    let do_ast_diff = sub_matches.is_present("diff");
+    let count_only = sub_matches.is_present("count-only");
+    let only_changed_symbols = sub_matches.is_present("only-changed-symbols");
+    let do_ast_diff = sub_matches.is_present("diff") || only_changed_symbols;
+    let files_from = sub_matches.value_of("files-from");
+    let since = sub_matches.value_of("since");
+    let symbol = sub_matches.value_of("symbol");
+    let show_timing = logger.can_log_timing();
+    let pretty = sub_matches.is_present("pretty");
+
+    let issue_formatter = sub_matches.value_of("formatter").map(|name| {
+        if let Some(issue_formatter) = issue_formatters
+            .iter()
+            .find(|issue_formatter| issue_formatter.get_name() == name)
+        {
+            issue_formatter
+        } else {
+            println!("Unknown formatter {}", name);
+            exit(1);
+        }
+    });
+
+    let group_by_kind = match sub_matches.value_of("group-by") {
+        None => false,
+        Some("kind") => true,
+        Some(grouping) => {
+            println!("Invalid grouping {}", grouping);
+            exit(1);
+        }
+    };
+
+    let sort = sub_matches.value_of("sort").unwrap_or("file");
+
+    if !matches!(sort, "file" | "kind" | "severity") {
+        println!("Invalid sort order {}", sort);
+        exit(1);
+    }
+
+    let max_issues = sub_matches.value_of("max-issues").map(|count| {
+        if let Ok(count) = count.parse::<usize>() {
+            count
+        } else {
+            println!("Invalid issue count {}", count);
+            exit(1);
+        }
+    });
+
+    let hack_version = sub_matches.value_of("hack-version").map(|version| {
+        if let Some(hack_version) = config::parse_hack_version(version) {
+            hack_version
+        } else {
+            println!("Invalid hack version {}", version);
+            exit(1);
+        }
+    });
+
+    let fixme_count_out_path = sub_matches.value_of("fixme-count-out");
+    let fixme_count_baseline_path = sub_matches.value_of("fixme-count-baseline");
+
+    let max_fixmes = sub_matches.value_of("max-fixmes").map(|count| {
+        if let Ok(count) = count.parse::<usize>() {
+            count
+        } else {
+            println!("Invalid fixme count {}", count);
+            exit(1);
+        }
+    });
+
+    let repeat = if let Some(count) = sub_matches.value_of("repeat") {
+        match count.parse::<usize>() {
+            Ok(count) if count > 0 => count,
+            _ => {
+                println!("Invalid repeat count {}", count);
+                exit(1);
+            }
+        }
+    } else {
+        1
+    };
+
+    let discard_first = sub_matches.is_present("discard-first");
+
+    if discard_first && repeat < 2 {
+        println!("--discard-first needs a --repeat count of at least 2");
+        exit(1);
+    }
+
+    let slowest_file_count = if let Some(count) = sub_matches.value_of("slowest") {
+        if let Ok(count) = count.parse::<usize>() {
+            count
+        } else {
+            println!("Invalid file count {}", count);
+            exit(1);
+        }
+    } else {
+        10
+    };
+
+    let fail_on = if let Some(level) = sub_matches.value_of("fail-on") {
+        if let Ok(severity) = IssueSeverity::from_str(level) {
+            severity
+        } else {
+            println!("Invalid severity {}", level);
+            exit(1);
+        }
+    } else {
+        IssueSeverity::Info
+    };
 
     let mut issue_kinds_filter = FxHashSet::default();
 
@@ -1421,64 +2736,422 @@ fn do_analysis(
     }
 
     let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
+
+    if let Some(root_dirs) = sub_matches.values_of("root") {
+        config
+            .root_dirs
+            .extend(root_dirs.skip(1).map(|root_dir| root_dir.to_string()));
+    }
+
     config.find_unused_expressions = find_unused_expressions;
     config.find_unused_definitions = find_unused_definitions;
+    config.find_unused_suppressions = find_unused_suppressions;
     config.ignore_mixed_issues = ignore_mixed_issues;
     config.ast_diff = do_ast_diff;
 
-    config.hooks = analysis_hooks;
+    if let Some(files_from) = files_from {
+        config.analyze_file_allowlist = Some(read_file_list(files_from, root_dir));
+    }
+
+    if let Some(since) = since {
+        let changed_files = get_changed_files(since, root_dir);
+
+        config.analyze_file_allowlist = Some(
+            if let Some(analyze_file_allowlist) = config.analyze_file_allowlist {
+                analyze_file_allowlist
+                    .intersection(&changed_files)
+                    .cloned()
+                    .collect()
+            } else {
+                changed_files
+            },
+        );
+    }
+
+    config.hooks = analysis_hooks;
+
+    let config_path = config_path.unwrap();
+
+    let mut interner = Interner::default();
+
+    if config_path.exists() {
+        config
+            .update_from_file(cwd, config_path, &mut interner)
+            .ok();
+    }
+
+    load_ignore_file(&mut config);
+    load_overlay_files(&mut config);
+
+    // do this after we've loaded from file, as they can be overridden
+    if !issue_kinds_filter.is_empty() {
+        config.allowed_issues = Some(issue_kinds_filter);
+    } else if show_all_issues {
+        config.allowed_issues = None;
+    }
+
+    config.analyze_symbol = symbol.map(|symbol| symbol.trim_start_matches('\\').to_string());
+
+    if max_fixmes.is_some() {
+        config.max_fixmes = max_fixmes;
+    }
+
+    if include_test_files {
+        config.include_test_files = true;
+    }
+
+    // the stubs only change with the hakana build, so unlike the rest of the cache they can
+    // be reused when the cache isn't
+    if !sub_matches.is_present("no-stub-cache") {
+        config.stub_cache_dir = Some(cache_dir.clone());
+    }
+
+    if hack_version.is_some() {
+        config.hack_version = hack_version;
+    }
+
+    if sub_matches.is_present("config-print") {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&EffectiveConfig::new(&config, &interner)).unwrap()
+        );
+        return;
+    }
+
+    let root_dir = config.root_dir.clone();
+    let config = Arc::new(config);
+    let logger = Arc::new(logger);
+
+    // cached scans record which code is production code, so the cache can only be reused
+    // by runs that agree on whether test files count as production code
+    let cache_header = if config.include_test_files {
+        format!("{}\ninclude_test_files", header)
+    } else {
+        header.to_string()
+    };
+
+    let mut run_times = vec![];
+    let mut previous_run = None;
+
+    // repeated runs reuse the previous codebase, like the language server does
+    let result = loop {
+        let (previous_analysis_result, previous_scan_data) = match previous_run.take() {
+            Some((analysis_result, scan_data)) => (Some(analysis_result), Some(scan_data)),
+            None => (None, None),
+        };
+
+        let run_now = Instant::now();
+
+        let result = hakana_orchestrator::scan_and_analyze(
+            Vec::new(),
+            filter.clone(),
+            ignored.clone(),
+            config.clone(),
+            if sub_matches.is_present("no-cache") || previous_scan_data.is_some() {
+                None
+            } else {
+                Some(&cache_dir)
+            },
+            threads,
+            logger.clone(),
+            &cache_header,
+            interner.clone(),
+            previous_scan_data,
+            previous_analysis_result,
+            None,
+            || {},
+        );
+
+        run_times.push(run_now.elapsed());
+
+        match result {
+            Ok(run) if run_times.len() < repeat => previous_run = Some(run),
+            result => break result,
+        }
+    };
+
+    if repeat > 1 {
+        print_run_times(&run_times, discard_first);
+    }
+
+    if let Ok((mut analysis_result, successful_run_data)) = result {
+        if let Some(analyze_symbol) = &config.analyze_symbol {
+            let analyze_symbol = if let Some(functionlike_id) =
+                get_functionlike_id(analyze_symbol, &successful_run_data.interner).filter(|id| {
+                    successful_run_data
+                        .codebase
+                        .functionlike_infos
+                        .contains_key(id)
+                }) {
+                functionlike_id
+            } else {
+                println!(
+                    "\nERROR: Could not find function or method {}\n",
+                    analyze_symbol
+                );
+                *had_error = true;
+                return;
+            };
+
+            // issues from the rest of the file (e.g. class-level checks) aren't relevant
+            for issues in analysis_result
+                .emitted_issues
+                .values_mut()
+                .chain(analysis_result.emitted_definition_issues.values_mut())
+            {
+                issues.retain(|issue| issue.symbol == analyze_symbol);
+            }
+        }
+
+        if only_changed_symbols {
+            let codebase = &successful_run_data.codebase;
+
+            // symbols the diff pass marked safe kept their cached issues, so nothing in them changed
+            for issues in analysis_result
+                .emitted_issues
+                .values_mut()
+                .chain(analysis_result.emitted_definition_issues.values_mut())
+            {
+                issues.retain(|issue| {
+                    if codebase.safe_symbols.contains(&issue.symbol.0) {
+                        false
+                    } else if issue.symbol.1 == StrId::EMPTY {
+                        true
+                    } else {
+                        !codebase.safe_symbol_members.contains(&issue.symbol)
+                    }
+                });
+            }
+        }
+
+        if let Some(write_baseline_path) = write_baseline_path {
+            let baseline = Baseline::from_analysis_result(
+                &analysis_result,
+                &successful_run_data.codebase,
+                &successful_run_data.interner,
+                &root_dir,
+            );
+
+            if let Err(error) = baseline.write(write_baseline_path) {
+                println!(
+                    "\nERROR: Could not write baseline {}: {}\n",
+                    write_baseline_path, error
+                );
+                exit(1);
+            }
+
+            println!(
+                "Wrote {} issues to baseline {}",
+                baseline.issue_count(),
+                write_baseline_path
+            );
+
+            baseline.filter_issues(
+                &mut analysis_result,
+                &successful_run_data.codebase,
+                &successful_run_data.interner,
+                &root_dir,
+            );
+        } else if let Some(baseline_path) = baseline_path {
+            match Baseline::read(baseline_path) {
+                Ok(baseline) => baseline.filter_issues(
+                    &mut analysis_result,
+                    &successful_run_data.codebase,
+                    &successful_run_data.interner,
+                    &root_dir,
+                ),
+                Err(error) => {
+                    println!(
+                        "\nERROR: Could not read baseline {}: {}\n",
+                        baseline_path, error
+                    );
+                    exit(1);
+                }
+            }
+        }
+
+        let mut total_issue_count = None;
+        let mut had_issue_error = false;
+
+        // every issue is counted, so there's nothing to limit
+        if let Some(max_issues) = max_issues.filter(|_| !count_only) {
+            // issues beyond the limit aren't shown, but they should still fail the run
+            for issues in analysis_result
+                .emitted_issues
+                .values()
+                .chain(analysis_result.emitted_definition_issues.values())
+            {
+                if issues
+                    .iter()
+                    .any(|issue| config.get_issue_severity(&issue.kind) >= fail_on)
+                {
+                    had_issue_error = true;
+                }
+            }
+
+            total_issue_count = Some(limit_issues(
+                &mut analysis_result,
+                &successful_run_data.interner,
+                &root_dir,
+                max_issues,
+            ));
+        }
+
+        let mut has_issues = false;
+        let mut shown_issue_count = 0;
+        let mut issue_lines_by_kind = BTreeMap::new();
+
+        let has_multiple_roots = config.root_dirs.len() > 1;
+
+        let issue_order = match sort {
+            "kind" => IssueOrder::Kind,
+            "severity" => IssueOrder::Severity(&config),
+            _ => IssueOrder::File,
+        };
+
+        if count_only {
+            has_issues =
+                print_issue_counts(&analysis_result, &config, fail_on, &mut had_issue_error);
+        } else {
+            for (issue_root_dir, root_issues) in
+                get_issues_by_root(&analysis_result, &successful_run_data.interner, &config)
+            {
+                if has_multiple_roots && !group_by_kind && !root_issues.is_empty() {
+                    println!("\n{}:", issue_root_dir);
+                }
+
+                let mut file_contents_by_path = FxHashMap::default();
+
+                for (file_path, issue) in get_ordered_issues(&root_issues, issue_order) {
+                    let file_contents = if pretty {
+                        file_contents_by_path
+                            .entry(file_path)
+                            .or_insert_with(|| {
+                                fs::read_to_string(Path::new(issue_root_dir).join(file_path)).ok()
+                            })
+                            .as_deref()
+                    } else {
+                        None
+                    };
+
+                    // there's no per-root header when grouping by kind, so show the full path
+                    let file_path = if has_multiple_roots && group_by_kind {
+                        Path::new(issue_root_dir)
+                            .join(file_path)
+                            .to_string_lossy()
+                            .to_string()
+                    } else {
+                        file_path.clone()
+                    };
+
+                    has_issues = true;
+                    shown_issue_count += 1;
+
+                    // issues below the threshold are still reported, they just don't fail the run
+                    let severity = config.get_issue_severity(&issue.kind);
+
+                    if severity >= fail_on {
+                        had_issue_error = true;
+                    }
+
+                    let formatted_issue = if let Some(issue_formatter) = issue_formatter {
+                        issue_formatter.format(issue, &file_path)
+                    } else if let Some(file_contents) = file_contents {
+                        format_issue_with_snippet(issue, severity, &file_path, file_contents)
+                    } else {
+                        issue.format(&file_path)
+                    };
+
+                    if group_by_kind {
+                        issue_lines_by_kind
+                            .entry(issue.kind.to_string())
+                            .or_insert_with(Vec::new)
+                            .push(formatted_issue);
+                    } else {
+                        println!("{}", formatted_issue);
+                    }
+                }
+            }
+        }
+
+        print_issues_by_kind(issue_lines_by_kind);
 
-    let config_path = config_path.unwrap();
+        if !has_issues {
+            println!("\nNo issues reported!\n");
+        }
 
-    let mut interner = Interner::default();
+        let fixme_count = analysis_result.used_fixme_counts.values().sum::<usize>();
+        let mut too_many_fixmes = false;
 
-    if config_path.exists() {
-        config
-            .update_from_file(cwd, config_path, &mut interner)
-            .ok();
-    }
+        if let Some(max_fixmes) = config.max_fixmes {
+            if fixme_count > max_fixmes {
+                println!(
+                    "\nERROR: Found {} active fixmes, more than the maximum of {}\n",
+                    fixme_count, max_fixmes
+                );
+                too_many_fixmes = true;
+            }
+        }
 
-    // do this after we've loaded from file, as they can be overridden
-    if !issue_kinds_filter.is_empty() {
-        config.allowed_issues = Some(issue_kinds_filter);
-    } else if show_all_issues {
-        config.allowed_issues = None;
-    }
+        if let Some(fixme_count_baseline_path) = fixme_count_baseline_path {
+            let baseline_fixme_count = fs::read_to_string(fixme_count_baseline_path)
+                .ok()
+                .and_then(|contents| contents.trim().parse::<usize>().ok());
+
+            if let Some(baseline_fixme_count) = baseline_fixme_count {
+                if fixme_count > baseline_fixme_count {
+                    println!(
+                        "\nERROR: Found {} active fixmes, more than the {} in {}\n",
+                        fixme_count, baseline_fixme_count, fixme_count_baseline_path
+                    );
+                    too_many_fixmes = true;
+                }
+            } else {
+                println!(
+                    "\nERROR: Could not read fixme count {}\n",
+                    fixme_count_baseline_path
+                );
+                exit(1);
+            }
+        }
 
-    let root_dir = config.root_dir.clone();
+        if too_many_fixmes {
+            let mut used_fixme_counts = analysis_result
+                .used_fixme_counts
+                .iter()
+                .map(|(file_path, count)| {
+                    (
+                        *count,
+                        file_path.get_relative_path(&successful_run_data.interner, &root_dir),
+                    )
+                })
+                .collect::<Vec<_>>();
+            used_fixme_counts.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
 
-    let result = hakana_orchestrator::scan_and_analyze(
-        Vec::new(),
-        filter,
-        ignored,
-        Arc::new(config),
-        if sub_matches.is_present("no-cache") {
-            None
-        } else {
-            Some(&cache_dir)
-        },
-        threads,
-        Arc::new(logger),
-        header,
-        interner,
-        None,
-        None,
-        None,
-        || {},
-    );
+            for (count, file_path) in used_fixme_counts {
+                println!("{}\t{}", count, file_path);
+            }
 
-    if let Ok((analysis_result, successful_run_data)) = result {
-        for (file_path, issues) in
-            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true)
-        {
-            for issue in issues {
-                *had_error = true;
-                println!("{}", issue.format(&file_path));
+            *had_error = true;
+        }
+
+        if let Some(fixme_count_out_path) = fixme_count_out_path {
+            if let Err(error) = fs::write(fixme_count_out_path, fixme_count.to_string()) {
+                println!(
+                    "\nERROR: Could not write fixme count {}: {}\n",
+                    fixme_count_out_path, error
+                );
+                exit(1);
             }
         }
 
-        if !*had_error {
-            println!("\nNo issues reported!\n");
+        if let Some(total_issue_count) = total_issue_count {
+            if total_issue_count > shown_issue_count {
+                println!(
+                    "\nShowing {} of {} issues\n",
+                    shown_issue_count, total_issue_count
+                );
+            }
         }
 
         if let Some(output_file) = output_file {
@@ -1486,11 +3159,88 @@ fn do_analysis(
                 output_file,
                 output_format,
                 cwd,
+                &root_dir,
+                relative_paths,
                 &analysis_result,
                 &successful_run_data.interner,
+                &config,
+                header,
+                issue_order,
             );
         }
 
+        if let Some(profile_file) = profile_file {
+            let profile_path = if profile_file.starts_with('/') {
+                profile_file
+            } else {
+                format!("{}/{}", cwd, profile_file)
+            };
+
+            fs::write(
+                &profile_path,
+                profile::get_folded_stacks(
+                    &analysis_result,
+                    &successful_run_data.interner,
+                    &root_dir,
+                ),
+            )
+            .unwrap_or_else(|_| panic!("Could not write file {}", &profile_path));
+        }
+
+        if counts_out_path.is_some() || counts_baseline_path.is_some() {
+            let issue_counts = IssueCounts::from_analysis_result(&analysis_result);
+
+            if let Some(counts_baseline_path) = counts_baseline_path {
+                match IssueCounts::read(counts_baseline_path) {
+                    Ok(baseline_counts) => {
+                        let changes = issue_counts.get_changes(&baseline_counts);
+
+                        if changes.is_empty() {
+                            println!("\nNo change in issue counts since {}", counts_baseline_path);
+                        } else {
+                            println!("\nIssue count changes since {}:", counts_baseline_path);
+
+                            for (kind, before, after) in changes {
+                                println!(
+                                    "{}\t{} -> {}\t({:+})",
+                                    kind,
+                                    before,
+                                    after,
+                                    after as isize - before as isize
+                                );
+
+                                if after > before && fail_on_count_increase {
+                                    *had_error = true;
+                                }
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        println!(
+                            "\nERROR: Could not read issue counts {}: {}\n",
+                            counts_baseline_path, error
+                        );
+                        exit(1);
+                    }
+                }
+            }
+
+            if let Some(counts_out_path) = counts_out_path {
+                if let Err(error) = issue_counts.write(counts_out_path) {
+                    println!(
+                        "\nERROR: Could not write issue counts {}: {}\n",
+                        counts_out_path, error
+                    );
+                    exit(1);
+                }
+            }
+        }
+
+        // with a counts baseline, only a kind's count going up decides whether the run fails
+        if had_issue_error && counts_baseline_path.is_none() {
+            *had_error = true;
+        }
+
         if show_issue_stats {
             let mut issues_by_kind = analysis_result
                 .issue_counts
@@ -1507,6 +3257,24 @@ fn do_analysis(
             println!("{:#?}", analysis_result.symbol_references);
         }
 
+        if show_timing && slowest_file_count > 0 {
+            let mut file_analysis_times = analysis_result
+                .file_analysis_times
+                .iter()
+                .collect::<Vec<_>>();
+            file_analysis_times.sort_by(|a, b| b.1.cmp(a.1));
+
+            println!("\nSlowest files to analyze:");
+
+            for (file_path, elapsed) in file_analysis_times.into_iter().take(slowest_file_count) {
+                println!(
+                    "{:.2?}\t{}",
+                    elapsed,
+                    file_path.get_relative_path(&successful_run_data.interner, &root_dir)
+                );
+            }
+        }
+
         if show_mixed_function_counts {
             let mut mixed_sources = analysis_result
                 .mixed_source_counts
@@ -1527,12 +3295,292 @@ fn do_analysis(
     }
 }
 
+// Renders an issue with the line it starts on and a caret underline, rustc-style.
+// Spans covering several lines are only underlined up to the end of the first one.
+fn format_issue_with_snippet(
+    issue: &Issue,
+    severity: IssueSeverity,
+    file_path: &String,
+    file_contents: &str,
+) -> String {
+    let start_offset = issue.pos.start_offset as usize;
+
+    // some synthetic issues (e.g. InvalidHackFile) don't point anywhere in the file
+    if issue.pos.start_line == 0 || start_offset > file_contents.len() {
+        return issue.format(file_path);
+    }
+
+    let line_start = file_contents[..start_offset]
+        .rfind('\n')
+        .map(|offset| offset + 1)
+        .unwrap_or(0);
+    let line_end = file_contents[start_offset..]
+        .find('\n')
+        .map(|offset| start_offset + offset)
+        .unwrap_or(file_contents.len());
+
+    let line = file_contents[line_start..line_end].trim_end_matches('\r');
+    let line_end = line_start + line.len();
+
+    let underline_end = if issue.pos.end_line == issue.pos.start_line {
+        (issue.pos.end_offset as usize).min(line_end)
+    } else {
+        line_end
+    };
+
+    // keep tabs so the carets line up with the source line
+    let padding = file_contents[line_start..start_offset]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let underline = "^".repeat(
+        file_contents[start_offset..underline_end.max(start_offset)]
+            .chars()
+            .count()
+            .max(1),
+    );
+
+    let line_number = issue.pos.start_line.to_string();
+    let gutter = " ".repeat(line_number.len());
+
+    format!(
+        "{}[{}]: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+        severity,
+        issue.kind.to_string(),
+        issue.description,
+        gutter,
+        file_path,
+        issue.pos.start_line,
+        issue.pos.start_column,
+        gutter,
+        line_number,
+        line,
+        gutter,
+        padding,
+        underline
+    )
+}
+
+fn print_run_times(run_times: &[Duration], discard_first: bool) {
+    println!();
+
+    for (i, elapsed) in run_times.iter().enumerate() {
+        println!(
+            "Run {}: {:.2?}{}",
+            i + 1,
+            elapsed,
+            if discard_first && i == 0 {
+                " (discarded)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    let mut timed_runs = if discard_first {
+        run_times[1..].to_vec()
+    } else {
+        run_times.to_vec()
+    };
+
+    // a failed run ends the loop early, which can leave nothing to summarize
+    if timed_runs.is_empty() {
+        return;
+    }
+
+    timed_runs.sort();
+
+    let median = if timed_runs.len() % 2 == 0 {
+        (timed_runs[timed_runs.len() / 2 - 1] + timed_runs[timed_runs.len() / 2]) / 2
+    } else {
+        timed_runs[timed_runs.len() / 2]
+    };
+
+    println!(
+        "\nmin {:.2?}, median {:.2?}, max {:.2?} over {} runs",
+        timed_runs[0],
+        median,
+        timed_runs[timed_runs.len() - 1],
+        timed_runs.len()
+    );
+}
+
+// Keeps the first max_issues issues, ordered by file and then position, and returns
+// how many issues there were before the cut
+fn limit_issues(
+    analysis_result: &mut AnalysisResult,
+    interner: &Interner,
+    root_dir: &str,
+    max_issues: usize,
+) -> usize {
+    let mut all_issues = vec![];
+
+    for (is_definition_issue, issues_map) in [
+        (false, &mut analysis_result.emitted_issues),
+        (true, &mut analysis_result.emitted_definition_issues),
+    ] {
+        for (file_path, issues) in issues_map.drain() {
+            let relative_path = file_path.get_relative_path(interner, root_dir);
+
+            for issue in issues {
+                all_issues.push((relative_path.clone(), file_path, is_definition_issue, issue));
+            }
+        }
+    }
+
+    let total_issue_count = all_issues.len();
+
+    all_issues.sort_by(|a, b| {
+        (
+            &a.0,
+            a.3.pos.start_offset,
+            a.3.pos.end_offset,
+            a.3.kind.to_string(),
+            &a.3.description,
+        )
+            .cmp(&(
+                &b.0,
+                b.3.pos.start_offset,
+                b.3.pos.end_offset,
+                b.3.kind.to_string(),
+                &b.3.description,
+            ))
+    });
+
+    for (_, file_path, is_definition_issue, issue) in all_issues.into_iter().take(max_issues) {
+        let issues_map = if is_definition_issue {
+            &mut analysis_result.emitted_definition_issues
+        } else {
+            &mut analysis_result.emitted_issues
+        };
+
+        issues_map.entry(file_path).or_default().push(issue);
+    }
+
+    total_issue_count
+}
+
+fn read_file_list(source: &str, root_dir: &str) -> FxHashSet<String> {
+    let contents = if source == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(source)
+    };
+
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(error) => {
+            println!("\nERROR: Could not read file list {}: {}\n", source, error);
+            exit(1);
+        }
+    };
+
+    contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| line.ends_with(".hack") || line.ends_with(".php") || line.ends_with(".hhi"))
+        .map(|line| {
+            if line.starts_with('/') {
+                line.to_string()
+            } else {
+                format!("{}/{}", root_dir, line.trim_start_matches("./"))
+            }
+        })
+        .collect()
+}
+
+// "auto" (or no value) uses one thread per available core, capped at what fits in a u8
+fn get_thread_count(value: Option<&str>) -> u8 {
+    match value {
+        None | Some("auto") => std::thread::available_parallelism()
+            .map_or(8, |threads| threads.get().min(u8::MAX as usize) as u8),
+        Some(value) => match value.parse::<u8>() {
+            Ok(threads) if threads > 0 => threads,
+            _ => {
+                println!("Invalid thread count {}", value);
+                exit(1);
+            }
+        },
+    }
+}
+
+fn load_ignore_file(config: &mut config::Config) {
+    if let Err(error) = config.update_from_ignore_file() {
+        println!("\nERROR: Could not read .hakanaignore: {}\n", error);
+        exit(1);
+    }
+}
+
+fn load_overlay_files(config: &mut config::Config) {
+    if let Err(error) = config.update_from_overlay_files() {
+        println!("\nERROR: Could not read nested hakana.json: {}\n", error);
+        exit(1);
+    }
+}
+
+fn get_changed_files(since: &str, root_dir: &str) -> FxHashSet<String> {
+    let is_git_checkout = process::Command::new("git")
+        .args(["-C", root_dir, "rev-parse", "--is-inside-work-tree"])
+        .output()
+        .map_or(false, |output| output.status.success());
+
+    if !is_git_checkout {
+        println!(
+            "\nERROR: --since requires {} to be a git checkout\n",
+            root_dir
+        );
+        exit(1);
+    }
+
+    // --relative makes paths relative to the root even when it's a subdirectory
+    // of the repository, and --diff-filter=d leaves out deleted files
+    let output = match process::Command::new("git")
+        .args([
+            "-C",
+            root_dir,
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=d",
+        ])
+        .arg(format!("{}...HEAD", since))
+        .output()
+    {
+        Ok(output) => output,
+        Err(error) => {
+            println!("\nERROR: Could not run git: {}\n", error);
+            exit(1);
+        }
+    };
+
+    if !output.status.success() {
+        println!(
+            "\nERROR: Could not get files changed since {}: {}\n",
+            since,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        exit(1);
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| line.ends_with(".hack") || line.ends_with(".php") || line.ends_with(".hhi"))
+        .map(|line| format!("{}/{}", root_dir, line))
+        .collect()
+}
+
 fn write_analysis_output_files(
     output_file: String,
     output_format: Option<String>,
     cwd: &String,
+    root_dir: &str,
+    relative_paths: bool,
     analysis_result: &AnalysisResult,
     interner: &Interner,
+    config: &config::Config,
+    header: &str,
+    issue_order: IssueOrder,
 ) {
     let output_path = if output_file.starts_with('/') {
         output_file
@@ -1541,42 +3589,142 @@ fn write_analysis_output_files(
     };
     let mut output_path = fs::File::create(Path::new(&output_path)).unwrap();
 
-    let json = match output_format {
-        Some(format) if format == "full" => {
+    // written entry by entry, rather than serialized into one big string first
+    if output_format.as_deref() == Some("ndjson") {
+        write_ndjson_output(
+            &mut io::BufWriter::new(output_path),
+            root_dir,
+            relative_paths,
+            analysis_result,
+            interner,
+            issue_order,
+        )
+        .unwrap();
+        return;
+    }
+
+    let json = get_analysis_output_json(
+        output_format.as_deref(),
+        root_dir,
+        relative_paths,
+        analysis_result,
+        interner,
+        config,
+        header,
+        issue_order,
+    );
+    write!(output_path, "{}", json).unwrap();
+}
+
+// Writes one CheckPointEntry per line, flushing whenever the output moves on to another file
+// so the entries can be consumed while they're still being written
+pub(crate) fn write_ndjson_output<W: Write>(
+    writer: &mut W,
+    root_dir: &str,
+    relative_paths: bool,
+    analysis_result: &AnalysisResult,
+    interner: &Interner,
+    issue_order: IssueOrder,
+) -> io::Result<()> {
+    let all_issues = analysis_result.get_all_issues(interner, root_dir, relative_paths);
+
+    let mut previous_file_path = None;
+
+    for (file_path, issue) in get_ordered_issues(&all_issues, issue_order) {
+        if previous_file_path.map_or(false, |previous_file_path| previous_file_path != file_path) {
+            writer.flush()?;
+        }
+
+        serde_json::to_writer(&mut *writer, &CheckPointEntry::from_issue(issue, file_path))?;
+        writeln!(writer)?;
+
+        previous_file_path = Some(file_path);
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+// File paths are relative to root_dir (with no leading slash) unless relative_paths is false,
+// so the output can be consumed on a machine where the repo lives somewhere else
+pub(crate) fn get_analysis_output_json(
+    output_format: Option<&str>,
+    root_dir: &str,
+    relative_paths: bool,
+    analysis_result: &AnalysisResult,
+    interner: &Interner,
+    config: &config::Config,
+    header: &str,
+    issue_order: IssueOrder,
+) -> String {
+    let all_issues = analysis_result.get_all_issues(interner, root_dir, relative_paths);
+    let ordered_issues = get_ordered_issues(&all_issues, issue_order);
+
+    match output_format {
+        Some("full") => {
             let mut entries = vec![];
 
-            for (file_path, issues) in analysis_result.get_all_issues(interner, cwd, true) {
-                for issue in issues {
-                    entries.push(FullEntry::from_issue(issue, &file_path));
-                }
+            for (file_path, issue) in ordered_issues {
+                entries.push(FullEntry::from_issue(issue, file_path));
             }
 
             serde_json::to_string_pretty(&entries).unwrap()
         }
-        Some(format) if format == "hh_client" => {
+        Some("hh_client") => {
             let mut entries = vec![];
 
-            for (file_path, issues) in analysis_result.get_all_issues(interner, cwd, true) {
-                for issue in issues {
-                    entries.push(HhClientEntry::from_issue(issue, &file_path));
-                }
+            for (file_path, issue) in ordered_issues {
+                entries.push(HhClientEntry::from_issue(issue, file_path));
             }
 
             serde_json::to_string_pretty(&entries).unwrap()
         }
+        Some("sarif") => {
+            let sarif_log = SarifLog::from_issues(header, ordered_issues, config);
+
+            serde_json::to_string_pretty(&sarif_log).unwrap()
+        }
         _ => {
             let mut checkpoint_entries = vec![];
 
-            for (file_path, issues) in analysis_result.get_all_issues(interner, cwd, true) {
-                for issue in issues {
-                    checkpoint_entries.push(CheckPointEntry::from_issue(issue, &file_path));
-                }
+            for (file_path, issue) in ordered_issues {
+                checkpoint_entries.push(CheckPointEntry::from_issue(issue, file_path));
             }
 
             serde_json::to_string_pretty(&checkpoint_entries).unwrap()
         }
-    };
-    write!(output_path, "{}", json).unwrap();
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum IssueOrder<'a> {
+    File,
+    Kind,
+    // most severe first, taking any severity overrides in the config into account
+    Severity(&'a config::Config),
+}
+
+// Flattens issues into (file path, issue) pairs. The sort is stable, so issues that tie
+// keep their file and position order.
+pub(crate) fn get_ordered_issues<'a>(
+    all_issues: &'a BTreeMap<String, Vec<&'a Issue>>,
+    issue_order: IssueOrder,
+) -> Vec<(&'a String, &'a Issue)> {
+    let mut ordered_issues = all_issues
+        .iter()
+        .flat_map(|(file_path, issues)| issues.iter().map(move |issue| (file_path, *issue)))
+        .collect::<Vec<_>>();
+
+    match issue_order {
+        IssueOrder::File => {}
+        IssueOrder::Kind => ordered_issues.sort_by_key(|(_, issue)| issue.kind.to_string()),
+        IssueOrder::Severity(config) => {
+            ordered_issues.sort_by_key(|(_, issue)| Reverse(config.get_issue_severity(&issue.kind)))
+        }
+    }
+
+    ordered_issues
 }
 
 fn write_codegen_output_files(output_file: String, cwd: &String, errors: &Vec<(String, String)>) {
@@ -1604,6 +3752,71 @@ fn write_codegen_output_files(output_file: String, cwd: &String, errors: &Vec<(S
 }
 
 fn update_files(analysis_result: &mut AnalysisResult, root_dir: &String, interner: &Interner) {
+    for_each_file_update(
+        analysis_result,
+        root_dir,
+        interner,
+        |relative_path, _, new_contents| {
+            println!("updating {}", relative_path);
+            let file_path = format!("{}/{}", root_dir, relative_path);
+            let mut file = File::create(&file_path).unwrap();
+
+            file.write_all(new_contents.as_bytes())
+                .unwrap_or_else(|_| panic!("Could not write file {}", &file_path));
+        },
+    );
+}
+
+fn preview_file_updates(
+    analysis_result: &mut AnalysisResult,
+    root_dir: &String,
+    interner: &Interner,
+    show_diff: bool,
+) {
+    let mut changed_file_count = 0;
+
+    for_each_file_update(
+        analysis_result,
+        root_dir,
+        interner,
+        |relative_path, file_contents, new_contents| {
+            if file_contents == new_contents {
+                return;
+            }
+
+            changed_file_count += 1;
+
+            println!("would update {}", relative_path);
+
+            if show_diff {
+                println!(
+                    "{}",
+                    TextDiff::from_lines(file_contents, new_contents)
+                        .unified_diff()
+                        .context_radius(3)
+                        .header(
+                            &format!("a/{}", relative_path),
+                            &format!("b/{}", relative_path),
+                        )
+                );
+            }
+        },
+    );
+
+    println!(
+        "\n{} files would be updated (dry run)\n",
+        changed_file_count
+    );
+}
+
+// Applies the replacements and insertions for each affected file in turn, in relative path
+// order, passing the old and new contents to handle_update before moving on to the next file
+fn for_each_file_update<F: FnMut(&str, &str, &str)>(
+    analysis_result: &mut AnalysisResult,
+    root_dir: &String,
+    interner: &Interner,
+    mut handle_update: F,
+) {
     let mut replacement_and_insertion_keys = analysis_result
         .replacements
         .keys()
@@ -1616,10 +3829,8 @@ fn update_files(analysis_result: &mut AnalysisResult, root_dir: &String, interne
         .map(|v| (v.get_relative_path(interner, root_dir), v))
         .collect::<BTreeMap<_, _>>()
     {
-        println!("updating {}", relative_path);
         let file_path = format!("{}/{}", root_dir, relative_path);
         let file_contents = fs::read_to_string(&file_path).unwrap();
-        let mut file = File::create(&file_path).unwrap();
         let replacements = analysis_result
             .replacements
             .remove(&original_path)
@@ -1630,8 +3841,9 @@ fn update_files(analysis_result: &mut AnalysisResult, root_dir: &String, interne
             .remove(&original_path)
             .unwrap_or_default();
 
-        file.write_all(replace_contents(file_contents, replacements, insertions).as_bytes())
-            .unwrap_or_else(|_| panic!("Could not write file {}", &file_path));
+        let new_contents = replace_contents(file_contents.clone(), replacements, insertions);
+
+        handle_update(&relative_path, &file_contents, &new_contents);
     }
 }
 
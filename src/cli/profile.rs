@@ -0,0 +1,50 @@
+use hakana_code_info::analysis_result::AnalysisResult;
+use hakana_str::Interner;
+
+// Renders phase and per-file timings in the folded stack format read by inferno-flamegraph
+// and flamegraph.pl, with one sample per microsecond. Files are analyzed in parallel, so
+// the per-file frames under "analyze" add up to CPU time rather than wall-clock time, and
+// any wall-clock time not accounted for by them is attributed to "analyze" itself.
+pub fn get_folded_stacks(
+    analysis_result: &AnalysisResult,
+    interner: &Interner,
+    root_dir: &str,
+) -> String {
+    let mut lines = vec![];
+
+    for (phase, elapsed) in &analysis_result.phase_times {
+        if *phase == "analyze" {
+            let mut file_times = analysis_result
+                .file_analysis_times
+                .iter()
+                .map(|(file_path, elapsed)| {
+                    (
+                        file_path
+                            .get_relative_path(interner, root_dir)
+                            .replace(';', "_"),
+                        elapsed.as_micros(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            file_times.sort();
+
+            let file_total = file_times.iter().map(|(_, micros)| micros).sum::<u128>();
+
+            for (file_path, micros) in file_times {
+                if micros > 0 {
+                    lines.push(format!("hakana;analyze;{} {}", file_path, micros));
+                }
+            }
+
+            let self_micros = elapsed.as_micros().saturating_sub(file_total);
+
+            if self_micros > 0 {
+                lines.push(format!("hakana;analyze {}", self_micros));
+            }
+        } else if elapsed.as_micros() > 0 {
+            lines.push(format!("hakana;{} {}", phase, elapsed.as_micros()));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,177 @@
+use hakana_analyzer::config::Config;
+use hakana_code_info::issue::{Issue, IssueSeverity};
+use indexmap::IndexMap;
+use serde::Serialize;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: &'static str,
+    pub information_uri: &'static str,
+    pub version: String,
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub short_description: SarifMessage,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+impl From<IssueSeverity> for SarifLevel {
+    fn from(severity: IssueSeverity) -> Self {
+        match severity {
+            IssueSeverity::Error => SarifLevel::Error,
+            IssueSeverity::Warning => SarifLevel::Warning,
+            IssueSeverity::Info => SarifLevel::Note,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: SarifLevel,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+#[derive(Serialize)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: u32,
+    pub start_column: u16,
+    pub end_line: u32,
+    pub end_column: u16,
+}
+
+impl SarifLog {
+    pub fn from_issues<'a>(
+        header: &str,
+        issues: impl IntoIterator<Item = (&'a String, &'a Issue)>,
+        config: &Config,
+    ) -> Self {
+        let mut rule_indices = IndexMap::new();
+        let mut results = vec![];
+
+        for (file_path, issue) in issues {
+            let rule_id = issue.kind.to_string();
+            let rule_count = rule_indices.len();
+            let rule_index = *rule_indices.entry(rule_id.clone()).or_insert(rule_count);
+
+            // SARIF positions are 1-based, while some synthetic issues
+            // (e.g. InvalidHackFile) are reported at line 0, column 0
+            results.push(SarifResult {
+                rule_id,
+                rule_index,
+                level: config.get_issue_severity(&issue.kind).into(),
+                message: SarifMessage {
+                    text: issue.description.clone(),
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation {
+                            uri: file_path.clone(),
+                        },
+                        region: SarifRegion {
+                            start_line: issue.pos.start_line.max(1),
+                            start_column: issue.pos.start_column.max(1),
+                            end_line: issue.pos.end_line.max(1),
+                            end_column: issue.pos.end_column.max(1),
+                        },
+                    },
+                }],
+            });
+        }
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "Hakana",
+                        information_uri: "https://github.com/slackhq/hakana",
+                        version: get_version_from_header(header),
+                        rules: rule_indices
+                            .into_keys()
+                            .map(|id| SarifRule {
+                                short_description: SarifMessage { text: id.clone() },
+                                id,
+                            })
+                            .collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+// The build header looks like "\nCommit:    abc1234\nTimestamp: ...", but
+// custom binaries may pass something else entirely
+fn get_version_from_header(header: &str) -> String {
+    for line in header.lines() {
+        if let Some(commit) = line.trim().strip_prefix("Commit:") {
+            return commit.trim().to_string();
+        }
+    }
+
+    header.trim().to_string()
+}
@@ -0,0 +1,110 @@
+use hakana_code_info::analysis_result::AnalysisResult;
+use hakana_code_info::codebase_info::symbols::SymbolKind;
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_str::StrId;
+use serde::Serialize;
+
+#[derive(Serialize, Default)]
+pub struct CodebaseStats {
+    pub classes: usize,
+    pub interfaces: usize,
+    pub traits: usize,
+    pub enums: usize,
+    pub functions: usize,
+    pub methods: usize,
+    pub typedefs: usize,
+    pub executable_lines: usize,
+    pub functionlikes_returning_mixed: usize,
+    pub mixed_return_percentage: f64,
+    pub mixed_sources: usize,
+}
+
+impl CodebaseStats {
+    pub fn new(
+        codebase: &CodebaseInfo,
+        analysis_result: &AnalysisResult,
+        executable_lines: usize,
+    ) -> Self {
+        let mut stats = Self {
+            executable_lines,
+            mixed_sources: analysis_result.mixed_source_counts.len(),
+            ..Default::default()
+        };
+
+        for classlike_info in codebase.classlike_infos.values() {
+            if !classlike_info.user_defined {
+                continue;
+            }
+
+            match classlike_info.kind {
+                SymbolKind::Class => stats.classes += 1,
+                SymbolKind::Interface => stats.interfaces += 1,
+                SymbolKind::Trait => stats.traits += 1,
+                SymbolKind::Enum | SymbolKind::EnumClass => stats.enums += 1,
+                SymbolKind::TypeDefinition => {}
+            }
+        }
+
+        stats.typedefs = codebase
+            .type_definitions
+            .values()
+            .filter(|type_definition_info| type_definition_info.user_defined)
+            .count();
+
+        for (functionlike_id, functionlike_info) in &codebase.functionlike_infos {
+            if !functionlike_info.user_defined || functionlike_info.is_closure {
+                continue;
+            }
+
+            if functionlike_id.1 == StrId::EMPTY {
+                stats.functions += 1;
+            } else {
+                stats.methods += 1;
+            }
+
+            // a missing return type is treated as mixed
+            if functionlike_info
+                .return_type
+                .as_ref()
+                .map(|return_type| return_type.is_mixed())
+                .unwrap_or(true)
+            {
+                stats.functionlikes_returning_mixed += 1;
+            }
+        }
+
+        let functionlike_count = stats.functions + stats.methods;
+
+        if functionlike_count > 0 {
+            stats.mixed_return_percentage =
+                stats.functionlikes_returning_mixed as f64 * 100.0 / functionlike_count as f64;
+        }
+
+        stats
+    }
+
+    pub fn get_table(&self) -> String {
+        [
+            ("Classes", self.classes.to_string()),
+            ("Interfaces", self.interfaces.to_string()),
+            ("Traits", self.traits.to_string()),
+            ("Enums", self.enums.to_string()),
+            ("Functions", self.functions.to_string()),
+            ("Methods", self.methods.to_string()),
+            ("Typedefs", self.typedefs.to_string()),
+            ("Executable lines", self.executable_lines.to_string()),
+            (
+                "Returning mixed",
+                format!(
+                    "{} ({:.1}%)",
+                    self.functionlikes_returning_mixed, self.mixed_return_percentage
+                ),
+            ),
+            ("Mixed sources", self.mixed_sources.to_string()),
+        ]
+        .iter()
+        .map(|(label, value)| format!("{:<20}{:>12}", label, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
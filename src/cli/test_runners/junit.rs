@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use super::test_runner::{TestResult, TestStatus};
+
+pub fn get_junit_xml(test_results: &[TestResult]) -> String {
+    let failure_count = test_results
+        .iter()
+        .filter(|r| matches!(r.status, TestStatus::Failed(_)))
+        .count();
+    let skipped_count = test_results
+        .iter()
+        .filter(|r| matches!(r.status, TestStatus::Skipped))
+        .count();
+    let total_time = test_results
+        .iter()
+        .map(|r| r.duration)
+        .sum::<Duration>()
+        .as_secs_f64();
+
+    let suite_attributes = format!(
+        "name=\"hakana\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+        test_results.len(),
+        failure_count,
+        skipped_count,
+        total_time
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!("<testsuites {}>\n", suite_attributes);
+    xml += &format!("  <testsuite {}>\n", suite_attributes);
+
+    for test_result in test_results {
+        // tests/inference/Arg/byRefArgAssignment => (tests/inference/Arg, byRefArgAssignment)
+        let (classname, name) = test_result
+            .name
+            .rsplit_once('/')
+            .unwrap_or(("", &test_result.name));
+
+        xml += &format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(name),
+            escape_xml(classname),
+            test_result.duration.as_secs_f64()
+        );
+
+        match &test_result.status {
+            TestStatus::Passed => {
+                xml += "/>\n";
+            }
+            TestStatus::Skipped => {
+                xml += ">\n      <skipped/>\n    </testcase>\n";
+            }
+            TestStatus::Failed(output) => {
+                xml += &format!(
+                    ">\n      <failure message=\"Unexpected output\">{}</failure>\n    </testcase>\n",
+                    escape_xml(output)
+                );
+            }
+        }
+    }
+
+    xml += "  </testsuite>\n</testsuites>\n";
+
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
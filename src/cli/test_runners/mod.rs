@@ -1,2 +1,3 @@
 pub mod core_test_runner;
+pub mod junit;
 pub mod test_runner;
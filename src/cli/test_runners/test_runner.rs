@@ -1,3 +1,4 @@
+use crate::issue_counts::IssueCounts;
 use hakana_analyzer::config;
 use hakana_analyzer::custom_hook::CustomHook;
 use hakana_logger::Logger;
@@ -5,22 +6,26 @@ use hakana_code_info::analysis_result::AnalysisResult;
 use hakana_code_info::code_location::FilePath;
 use hakana_code_info::data_flow::graph::GraphKind;
 use hakana_code_info::data_flow::graph::WholeProgramKind;
-use hakana_code_info::issue::IssueKind;
-use hakana_str::Interner;
-use hakana_orchestrator::wasm::get_single_file_codebase;
+use hakana_code_info::functionlike_identifier::get_functionlike_id;
+use hakana_code_info::issue::{IssueKind, IssueSeverity};
+use hakana_code_info::t_atomic::TAtomic;
+use hakana_code_info::ttype::{get_expanded_union_syntax_type, wrap_atomic};
+use hakana_str::{Interner, StrId, ThreadedInterner};
+use hakana_orchestrator::wasm::{get_single_file_codebase, scan_single_file};
 use hakana_orchestrator::SuccessfulScanData;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::io;
 use std::io::Write;
 use std::path::Path;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
 pub trait HooksProvider {
@@ -29,6 +34,18 @@ pub trait HooksProvider {
 
 pub struct TestRunner(pub Box<dyn HooksProvider>);
 
+pub enum TestStatus {
+    Passed,
+    Skipped,
+    Failed(String),
+}
+
+pub struct TestResult {
+    pub name: String,
+    pub duration: Duration,
+    pub status: TestStatus,
+}
+
 impl TestRunner {
     pub fn run_test(
         &self,
@@ -40,10 +57,11 @@ impl TestRunner {
         build_checksum: &str,
         repeat: u16,
         random_seed: Option<u64>,
-    ) {
+    ) -> Vec<TestResult> {
         let candidate_test_folders = get_all_test_folders(test_or_test_dir.clone());
 
         let mut test_diagnostics = vec![];
+        let mut test_results = vec![];
 
         let starter_data =
             if candidate_test_folders.len() > 1 && !test_or_test_dir.ends_with("/diff") {
@@ -83,8 +101,11 @@ impl TestRunner {
 
                 let needs_fresh_codebase = test_folder.to_ascii_lowercase().contains("xhp");
 
+                let previous_diagnostics_count = test_diagnostics.len();
+                let start = Instant::now();
+
                 let test_result = self.run_test_in_dir(
-                    test_folder,
+                    test_folder.clone(),
                     logger.clone(),
                     if use_cache { Some(&cache_dir) } else { None },
                     had_error,
@@ -107,6 +128,22 @@ impl TestRunner {
                     &mut time_in_analysis,
                 );
 
+                test_results.push(TestResult {
+                    name: test_folder,
+                    duration: start.elapsed(),
+                    status: match test_result.0.as_str() {
+                        "." => TestStatus::Passed,
+                        "S" => TestStatus::Skipped,
+                        _ => TestStatus::Failed(
+                            test_diagnostics[previous_diagnostics_count..]
+                                .iter()
+                                .map(|(_, diagnostic)| diagnostic.clone())
+                                .collect::<Vec<_>>()
+                                .join("\n\n"),
+                        ),
+                    },
+                });
+
                 last_scan_data = test_result.1;
                 last_analysis_result = test_result.2;
 
@@ -125,6 +162,8 @@ impl TestRunner {
                 .collect::<Vec<_>>()
                 .join("\n\n")
         );
+
+        test_results
     }
 
     fn get_config_for_test(&self, dir: &str) -> config::Config {
@@ -135,6 +174,7 @@ impl TestRunner {
             || dir.contains("UnusedClosureParameter");
         analysis_config.find_unused_definitions =
             dir.to_ascii_lowercase().contains("unused") && !dir.contains("UnusedExpression");
+        analysis_config.find_unused_suppressions = dir.contains("/Suppression/");
         analysis_config.graph_kind = if dir.contains("/security/") {
             GraphKind::WholeProgram(WholeProgramKind::Taint)
         } else if dir.contains("/find-paths/") {
@@ -145,6 +185,74 @@ impl TestRunner {
 
         analysis_config.hooks = self.0.get_hooks_for_test(dir);
 
+        if dir.contains("/MissingTypeHint/") {
+            analysis_config.allowed_issues = Some(FxHashSet::from_iter([
+                IssueKind::MissingParameterType,
+                IssueKind::MissingPropertyType,
+                IssueKind::MissingReturnType,
+            ]));
+        }
+
+        if dir.contains("/UnavailableBuiltin/") {
+            analysis_config.hack_version = Some((4, 140));
+        }
+
+        if dir.contains("/syntax-types/") {
+            analysis_config.collect_hover_types = true;
+        }
+
+        if dir.contains("/UnusedCode/onlyUsedInTestFile") {
+            analysis_config.test_files =
+                vec![glob::Pattern::new(&format!("{}/*_test.hack", dir)).unwrap()];
+        }
+
+        if dir.contains("/Arg/mixedArgumentWithParamDeclarations") {
+            analysis_config.show_mixed_argument_declarations = true;
+        }
+
+        if dir.contains("/AlgebraBudget/raisedBudget") {
+            analysis_config.algebra_complexity_budget = 30000;
+        }
+
+        if dir.contains("/output-formats/sarifLevels") {
+            analysis_config.issue_severities = FxHashMap::from_iter([
+                (IssueKind::UndefinedVariable, IssueSeverity::Warning),
+                (IssueKind::NonExistentFunction, IssueSeverity::Info),
+            ]);
+        }
+
+        if dir.contains("/SuspiciousLooseEquality/") {
+            analysis_config.allowed_issues =
+                Some(FxHashSet::from_iter([IssueKind::SuspiciousLooseEquality]));
+        }
+
+        if dir.contains("/ShadowedVariable/") {
+            analysis_config.allowed_issues = Some(FxHashSet::from_iter([
+                IssueKind::ShadowedVariable,
+                IssueKind::ShadowedLoopVar,
+            ]));
+        }
+
+        if dir.contains("/UnawaitedAwaitable/") {
+            analysis_config.allowed_issues =
+                Some(FxHashSet::from_iter([IssueKind::UnawaitedAwaitable]));
+        }
+
+        if dir.contains("/HhiSignatures/") {
+            analysis_config.analyze_hhi_signatures =
+                vec![glob::Pattern::new(&format!("{}/stubs/*.hhi", dir)).unwrap()];
+        }
+
+        if dir.contains("/DirectoryOverlay/ignoreIssueFilesWins") {
+            analysis_config.ignore_issue_patterns = FxHashMap::from_iter([(
+                IssueKind::UndefinedVariable,
+                vec![glob::Pattern::new(&format!("{}/legacy/**", dir)).unwrap()],
+            )]);
+        }
+
+        analysis_config.update_from_ignore_file().unwrap();
+        analysis_config.update_from_overlay_files().unwrap();
+
         let mut dir_parts = dir.split('/').collect::<Vec<_>>();
 
         while let Some(&"tests" | &"internal" | &"public") = dir_parts.first() {
@@ -219,6 +327,14 @@ impl TestRunner {
             );
         }
 
+        if dir.contains("/populator/") {
+            return self.run_populator_test(dir, test_diagnostics, previous_scan_data);
+        }
+
+        if dir.contains("/stub-cache/") {
+            return self.run_stub_cache_test(dir, logger, test_diagnostics, build_checksum);
+        }
+
         let cwd = env::current_dir().unwrap().to_str().unwrap().to_string();
 
         let analysis_config = self.get_config_for_test(&dir);
@@ -359,23 +475,405 @@ impl TestRunner {
             } else {
                 (".".to_string(), Some(result.1), Some(result.0))
             }
+        } else if dir.contains("/syntax-types/") {
+            let result = result.unwrap();
+
+            *total_time_in_analysis += result.0.time_in_analysis;
+
+            let input_file = format!("{}/input.hack", dir);
+            let input_contents = fs::read_to_string(&input_file).unwrap();
+            let input_file_path = FilePath(result.1.interner.get(&input_file).unwrap());
+
+            let codebase = &result.1.codebase;
+            let interner = &result.1.interner;
+
+            let mut syntax_types = vec![];
+
+            // type aliases and declared return types are rendered with aliases expanded
+            let mut type_definitions = codebase
+                .type_definitions
+                .iter()
+                .filter(|(_, type_definition)| {
+                    type_definition.location.file_path == input_file_path
+                        && type_definition.newtype_file.is_none()
+                })
+                .collect::<Vec<_>>();
+            type_definitions
+                .sort_by_key(|(_, type_definition)| type_definition.location.start_offset);
+
+            for (name, _) in type_definitions {
+                syntax_types.push(format!(
+                    "type {} = {}",
+                    interner.lookup(name),
+                    get_expanded_union_syntax_type(
+                        &wrap_atomic(TAtomic::TTypeAlias {
+                            name: *name,
+                            type_params: None,
+                            as_type: None,
+                        }),
+                        codebase,
+                        interner,
+                        &mut true,
+                    )
+                ));
+            }
+
+            let mut functionlikes = codebase
+                .functionlike_infos
+                .iter()
+                .filter(|(_, functionlike_info)| {
+                    functionlike_info.def_location.file_path == input_file_path
+                })
+                .collect::<Vec<_>>();
+            functionlikes
+                .sort_by_key(|(_, functionlike_info)| functionlike_info.def_location.start_offset);
+
+            for ((name, member_name), functionlike_info) in functionlikes {
+                if let Some(return_type) = &functionlike_info.return_type {
+                    syntax_types.push(format!(
+                        "{}(): {}",
+                        if *member_name == StrId::EMPTY {
+                            interner.lookup(name).to_string()
+                        } else {
+                            format!(
+                                "{}::{}",
+                                interner.lookup(name),
+                                interner.lookup(member_name)
+                            )
+                        },
+                        get_expanded_union_syntax_type(return_type, codebase, interner, &mut true)
+                    ));
+                }
+            }
+
+            // variables are rendered with the types shown when hovering over them
+            let mut hover_types = result
+                .0
+                .hover_types
+                .get(&input_file_path)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            hover_types.sort_by_key(|(offsets, _)| **offsets);
+
+            for ((start_offset, end_offset), hover_type) in hover_types {
+                let expr = &input_contents[*start_offset as usize..*end_offset as usize];
+
+                if expr.starts_with('$')
+                    && expr[1..]
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    syntax_types.push(format!(
+                        "{} on line {}: {}",
+                        expr,
+                        input_contents[..*start_offset as usize]
+                            .matches('\n')
+                            .count()
+                            + 1,
+                        hover_type
+                    ));
+                }
+            }
+
+            let syntax_types = syntax_types.join("\n");
+
+            let expected_output = fs::read_to_string(format!("{}/output.txt", dir))
+                .unwrap()
+                .trim()
+                .to_string();
+
+            if syntax_types == expected_output {
+                (".".to_string(), Some(result.1), Some(result.0))
+            } else {
+                test_diagnostics.push((dir, format!("- {}\n+ {}", expected_output, syntax_types)));
+                ("F".to_string(), Some(result.1), Some(result.0))
+            }
+        } else if dir.contains("/find-paths/") {
+            let result = result.unwrap();
+
+            *total_time_in_analysis += result.0.time_in_analysis;
+
+            // the equivalent of find-paths --from main
+            let call_graph_lines = get_functionlike_id("main", &result.1.interner)
+                .map(|functionlike_id| {
+                    crate::call_graph::get_call_graph_lines(
+                        functionlike_id,
+                        &result.0.symbol_references,
+                        &result.1.codebase,
+                        &result.1.interner,
+                        &dir,
+                        config.security_config.max_depth,
+                    )
+                })
+                .unwrap_or_default()
+                .join("\n");
+
+            let expected_output = fs::read_to_string(format!("{}/output.txt", dir))
+                .unwrap()
+                .trim()
+                .to_string();
+
+            if call_graph_lines == expected_output {
+                (".".to_string(), Some(result.1), Some(result.0))
+            } else {
+                test_diagnostics.push((
+                    dir,
+                    format!("- {}\n+ {}", expected_output, call_graph_lines),
+                ));
+                ("F".to_string(), Some(result.1), Some(result.0))
+            }
+        } else if dir.contains("/json-paths/") {
+            let result = result.unwrap();
+
+            *total_time_in_analysis += result.0.time_in_analysis;
+
+            let json = crate::get_taint_paths_json(&dir, &result.0, &result.1.interner);
+
+            let mut path_lines = vec![];
+
+            for entry in serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap() {
+                let description = entry["description"].as_str().unwrap();
+                let steps = entry["steps"].as_array().unwrap();
+
+                // the steps should be the same path the issue message describes, in order
+                let mut remaining_description = description;
+
+                for step in steps {
+                    let node_label = step["node_label"].as_str().unwrap();
+
+                    if let Some(offset) = remaining_description.find(node_label) {
+                        remaining_description = &remaining_description[offset + node_label.len()..];
+                    } else {
+                        path_lines.push(format!("step {} is not in the message", node_label));
+                    }
+                }
+
+                let step_positions = steps
+                    .iter()
+                    .filter(|step| step["line"].as_u64().unwrap() > 0)
+                    .map(|step| {
+                        format!(
+                            "{}:{}",
+                            step["file"].as_str().unwrap(),
+                            step["line"].as_u64().unwrap()
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                path_lines.push(format!(
+                    "{} at {}:{}:{}, from {} to {}",
+                    entry["kind"].as_str().unwrap(),
+                    entry["file"].as_str().unwrap(),
+                    entry["line"].as_u64().unwrap(),
+                    entry["column"].as_u64().unwrap(),
+                    step_positions
+                        .first()
+                        .map_or("nowhere", |position| position),
+                    step_positions.last().map_or("nowhere", |position| position),
+                ));
+            }
+
+            let path_lines = path_lines.join("\n");
+
+            let expected_output = fs::read_to_string(format!("{}/output.txt", dir))
+                .unwrap()
+                .trim()
+                .to_string();
+
+            if path_lines == expected_output {
+                (".".to_string(), Some(result.1), Some(result.0))
+            } else {
+                test_diagnostics.push((dir, format!("- {}\n+ {}", expected_output, path_lines)));
+                ("F".to_string(), Some(result.1), Some(result.0))
+            }
+        } else if dir.contains("/output-formats/") {
+            let result = result.unwrap();
+
+            *total_time_in_analysis += result.0.time_in_analysis;
+
+            let mut unexpected_paths = vec![];
+
+            // every format should only ever mention the test file, relative to the test dir
+            for output_format in [None, Some("full"), Some("hh_client"), Some("sarif")] {
+                let json = crate::get_analysis_output_json(
+                    output_format,
+                    &dir,
+                    true,
+                    &result.0,
+                    &result.1.interner,
+                    &config,
+                    "",
+                    crate::IssueOrder::File,
+                );
+
+                let mut file_paths = vec![];
+                collect_output_file_paths(&serde_json::from_str(&json).unwrap(), &mut file_paths);
+
+                if file_paths.is_empty() {
+                    unexpected_paths.push(format!(
+                        "{}: no file paths",
+                        output_format.unwrap_or("checkpoint")
+                    ));
+                }
+
+                for file_path in file_paths {
+                    if file_path != "input.hack" {
+                        unexpected_paths.push(format!(
+                            "{}: {}",
+                            output_format.unwrap_or("checkpoint"),
+                            file_path
+                        ));
+                    }
+                }
+            }
+
+            let mut ndjson = vec![];
+            crate::write_ndjson_output(
+                &mut ndjson,
+                &dir,
+                true,
+                &result.0,
+                &result.1.interner,
+                crate::IssueOrder::File,
+            )
+            .unwrap();
+
+            let ndjson = String::from_utf8(ndjson).unwrap();
+
+            if ndjson.is_empty() {
+                unexpected_paths.push("ndjson: no file paths".to_string());
+            }
+
+            for line in ndjson.lines() {
+                let mut file_paths = vec![];
+                collect_output_file_paths(&serde_json::from_str(line).unwrap(), &mut file_paths);
+
+                if file_paths != ["input.hack"] {
+                    unexpected_paths.push(format!("ndjson: {}", line));
+                }
+            }
+
+            // --count-only should report the same per-kind counts as a normal run prints
+            let mut issue_counts = BTreeMap::new();
+
+            for (_, issues) in result.0.get_all_issues(&result.1.interner, &dir, true) {
+                for issue in issues {
+                    *issue_counts.entry(issue.kind.to_string()).or_insert(0) += 1;
+                }
+            }
+
+            let count_only_counts = IssueCounts::from_analysis_result(&result.0)
+                .get_counts_by_frequency()
+                .into_iter()
+                .map(|(kind, count)| (kind.to_string(), count))
+                .collect::<BTreeMap<_, _>>();
+
+            if count_only_counts != issue_counts {
+                test_diagnostics.push((
+                    dir,
+                    format!(
+                        "--count-only counts {:?} differ from issue counts {:?}",
+                        count_only_counts, issue_counts
+                    ),
+                ));
+                return ("F".to_string(), Some(result.1), Some(result.0));
+            }
+
+            // when there's an expected output, it lists the rule and level of each SARIF result
+            let expected_output_path = dir.clone() + "/output.txt";
+
+            if Path::new(&expected_output_path).exists() {
+                let expected_output = fs::read_to_string(expected_output_path)
+                    .unwrap()
+                    .trim()
+                    .to_string();
+
+                let sarif_json = crate::get_analysis_output_json(
+                    Some("sarif"),
+                    &dir,
+                    true,
+                    &result.0,
+                    &result.1.interner,
+                    &config,
+                    "",
+                    crate::IssueOrder::File,
+                );
+                let sarif_log = serde_json::from_str::<serde_json::Value>(&sarif_json).unwrap();
+
+                let sarif_levels = sarif_log["runs"][0]["results"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|sarif_result| {
+                        format!(
+                            "{}: {}",
+                            sarif_result["ruleId"].as_str().unwrap(),
+                            sarif_result["level"].as_str().unwrap()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                if sarif_levels != expected_output {
+                    test_diagnostics
+                        .push((dir, format!("- {}\n+ {}", expected_output, sarif_levels)));
+                    return ("F".to_string(), Some(result.1), Some(result.0));
+                }
+            }
+
+            if unexpected_paths.is_empty() {
+                (".".to_string(), Some(result.1), Some(result.0))
+            } else {
+                test_diagnostics.push((
+                    dir,
+                    format!("Found unexpected paths: {}", unexpected_paths.join("\n")),
+                ));
+                ("F".to_string(), Some(result.1), Some(result.0))
+            }
         } else {
             match result {
                 Ok((analysis_result, run_data)) => {
                     *total_time_in_analysis += analysis_result.time_in_analysis;
 
                     let mut output = vec![];
+                    let mut issue_lines = vec![];
                     for (file_path, issues) in
                         analysis_result.get_all_issues(&run_data.interner, &dir, true)
                     {
                         for issue in issues {
                             output.push(issue.format(&file_path));
+                            issue_lines.push(format!(
+                                "{}:{} {}",
+                                file_path, issue.pos.start_line, issue.kind
+                            ));
                         }
                     }
 
                     let test_output = output;
 
                     let expected_output_path = dir.clone() + "/output.txt";
+
+                    if !Path::new(&expected_output_path).exists() {
+                        let input_contents =
+                            fs::read_to_string(dir.clone() + "/input.hack").unwrap_or_default();
+
+                        if input_contents.contains("HAKANA_EXPECT[") {
+                            let expected_lines = get_expected_issue_lines(&input_contents);
+                            issue_lines.sort();
+
+                            return if expected_lines == issue_lines {
+                                (".".to_string(), Some(run_data), Some(analysis_result))
+                            } else {
+                                test_diagnostics.push((
+                                    dir,
+                                    get_issue_lines_diff(&expected_lines, &issue_lines),
+                                ));
+                                ("F".to_string(), Some(run_data), Some(analysis_result))
+                            };
+                        }
+                    }
+
                     let expected_output = if Path::new(&expected_output_path).exists() {
                         let expected = fs::read_to_string(expected_output_path)
                             .unwrap()
@@ -426,6 +924,89 @@ impl TestRunner {
         }
     }
 
+    // checks that populating the codebase in parallel gives the same result as doing it serially
+    fn run_populator_test(
+        &self,
+        dir: String,
+        test_diagnostics: &mut Vec<(String, String)>,
+        previous_scan_data: Option<SuccessfulScanData>,
+    ) -> (String, Option<SuccessfulScanData>, Option<AnalysisResult>) {
+        let (mut codebase, interner) = if let Some(previous_scan_data) = previous_scan_data {
+            (previous_scan_data.codebase, previous_scan_data.interner)
+        } else {
+            let (codebase, interner, _) = get_single_file_codebase(vec!["tests/stubs/stubs.hack"]);
+            (codebase, interner)
+        };
+
+        let input_file = format!("{}/input.hack", dir);
+        let input_contents = fs::read_to_string(&input_file).unwrap();
+
+        let interner = Arc::new(Mutex::new(interner));
+        let mut threaded_interner = ThreadedInterner::new(interner.clone());
+
+        scan_single_file(
+            &mut codebase,
+            &mut threaded_interner,
+            input_file,
+            input_contents,
+        )
+        .unwrap();
+
+        drop(threaded_interner);
+
+        let interner = Arc::try_unwrap(interner).unwrap().into_inner().unwrap();
+
+        let differences = hakana_orchestrator::get_population_differences(
+            &codebase,
+            &interner,
+            &self.get_config_for_test(&dir),
+        );
+
+        if differences.is_empty() {
+            (".".to_string(), None, None)
+        } else {
+            test_diagnostics.push((
+                dir,
+                format!(
+                    "Serial and parallel population differ in: {}",
+                    differences.join(", ")
+                ),
+            ));
+            ("F".to_string(), None, None)
+        }
+    }
+
+    fn run_stub_cache_test(
+        &self,
+        dir: String,
+        logger: Arc<Logger>,
+        test_diagnostics: &mut Vec<(String, String)>,
+        build_checksum: &str,
+    ) -> (String, Option<SuccessfulScanData>, Option<AnalysisResult>) {
+        let mut analysis_config = self.get_config_for_test(&dir);
+        analysis_config.stub_cache_dir = Some(format!("{}/.hakana_cache", dir));
+
+        let differences = hakana_orchestrator::get_stub_cache_differences(
+            Arc::new(analysis_config),
+            logger,
+            build_checksum,
+            Interner::default(),
+        );
+
+        if differences.is_empty() {
+            (".".to_string(), None, None)
+        } else {
+            test_diagnostics.push((
+                dir,
+                format!(
+                    "Scanning with and without cached stubs differs in: {}",
+                    differences.join(", ")
+                ),
+            ));
+            ("F".to_string(), None, None)
+        }
+    }
+
     fn run_diff_test(
         &self,
         dir: String,
@@ -591,6 +1172,82 @@ fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>) ->
     Ok(())
 }
 
+// Collects the file paths from any of the JSON output formats
+fn collect_output_file_paths(value: &serde_json::Value, file_paths: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_output_file_paths(value, file_paths);
+            }
+        }
+        serde_json::Value::Object(entries) => {
+            for (key, value) in entries {
+                match (key.as_str(), value) {
+                    (
+                        "filename" | "file_path" | "path" | "uri",
+                        serde_json::Value::String(path),
+                    ) => {
+                        file_paths.push(path.clone());
+                    }
+                    _ => collect_output_file_paths(value, file_paths),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Reads `/* HAKANA_EXPECT[Kind1, Kind2] */` annotations, each of which expects the given
+// issues to be reported on the line that follows it.
+fn get_expected_issue_lines(input_contents: &str) -> Vec<String> {
+    let mut expected_lines = vec![];
+
+    for (i, line) in input_contents.lines().enumerate() {
+        let mut rest = line;
+
+        while let Some(start) = rest.find("HAKANA_EXPECT[") {
+            rest = &rest[start + "HAKANA_EXPECT[".len()..];
+
+            let Some(end) = rest.find(']') else {
+                break;
+            };
+
+            for kind in rest[..end].split(',') {
+                let kind = kind.trim();
+
+                if !kind.is_empty() {
+                    // lines are 1-based, and the annotation applies to the next one
+                    expected_lines.push(format!("input.hack:{} {}", i + 2, kind));
+                }
+            }
+
+            rest = &rest[end..];
+        }
+    }
+
+    expected_lines.sort();
+    expected_lines
+}
+
+fn get_issue_lines_diff(expected_lines: &[String], actual_lines: &[String]) -> String {
+    let mut unmatched_actual = actual_lines.to_vec();
+    let mut diff = vec![];
+
+    for expected_line in expected_lines {
+        if let Some(pos) = unmatched_actual.iter().position(|l| l == expected_line) {
+            unmatched_actual.remove(pos);
+        } else {
+            diff.push(format!("- {}", expected_line));
+        }
+    }
+
+    for actual_line in unmatched_actual {
+        diff.push(format!("+ {}", actual_line));
+    }
+
+    diff.join("\n")
+}
+
 fn get_all_test_folders(test_or_test_dir: String) -> Vec<String> {
     let mut test_folders = vec![];
     if Path::new(&(test_or_test_dir.clone() + "/input.hack")).exists()
@@ -11,7 +11,7 @@ use crate::{
         node::DataFlowNodeId,
     },
     function_context::FunctionLikeIdentifier,
-    issue::{Issue, IssueKind},
+    issue::{Issue, IssueKind, TaintTraceStep},
     symbol_references::SymbolReferences,
 };
 
@@ -39,6 +39,12 @@ pub struct AnalysisResult {
     pub functions_to_migrate: FxHashMap<FunctionLikeIdentifier, bool>,
     pub has_invalid_hack_files: bool,
     pub changed_during_analysis_files: FxHashSet<FilePath>,
+    pub hover_types: FxHashMap<FilePath, FxHashMap<(u32, u32), String>>,
+    pub file_analysis_times: FxHashMap<FilePath, Duration>,
+    // number of HAKANA_FIXME and HAKANA_IGNORE comments in each file that suppressed an issue
+    pub used_fixme_counts: FxHashMap<FilePath, usize>,
+    // wall-clock time of each orchestrator phase, in the order they ran
+    pub phase_times: Vec<(&'static str, Duration)>,
 }
 
 impl AnalysisResult {
@@ -60,6 +66,10 @@ impl AnalysisResult {
             codegen: BTreeMap::default(),
             has_invalid_hack_files: false,
             changed_during_analysis_files: FxHashSet::default(),
+            hover_types: FxHashMap::default(),
+            file_analysis_times: FxHashMap::default(),
+            used_fixme_counts: FxHashMap::default(),
+            phase_times: vec![],
         }
     }
 
@@ -85,6 +95,16 @@ impl AnalysisResult {
         self.codegen.extend(other.codegen);
         self.changed_during_analysis_files
             .extend(other.changed_during_analysis_files);
+        for (file_path, hover_types) in other.hover_types {
+            self.hover_types
+                .entry(file_path)
+                .or_default()
+                .extend(hover_types);
+        }
+        self.file_analysis_times.extend(other.file_analysis_times);
+        for (file_path, count) in other.used_fixme_counts {
+            *self.used_fixme_counts.entry(file_path).or_insert(0) += count;
+        }
         self.has_invalid_hack_files = self.has_invalid_hack_files || other.has_invalid_hack_files;
     }
 
@@ -164,6 +184,29 @@ impl FullEntry {
     }
 }
 
+#[derive(Serialize)]
+pub struct TaintPathEntry {
+    pub kind: String,
+    pub description: String,
+    pub file: String,
+    pub line: u32,
+    pub column: u16,
+    pub steps: Vec<TaintTraceStep>,
+}
+
+impl TaintPathEntry {
+    pub fn from_issue(issue: &Issue, path: &str) -> Self {
+        Self {
+            kind: issue.kind.to_string(),
+            description: issue.description.clone(),
+            file: path.to_string(),
+            line: issue.pos.start_line,
+            column: issue.pos.start_column,
+            steps: issue.taint_trace.clone().unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CheckPointEntryLevel {
@@ -171,6 +171,7 @@ impl Assertion {
             | Assertion::IsNotType(atomic) => matches!(
                 atomic,
                 TAtomic::TLiteralInt { .. }
+                    | TAtomic::TLiteralFloat { .. }
                     | TAtomic::TLiteralString { .. }
                     | TAtomic::TEnumLiteralCase { .. }
             ),
@@ -18,10 +18,13 @@ pub struct FilePath(pub StrId);
 impl FilePath {
     pub fn get_relative_path(&self, interner: &Interner, root_dir: &str) -> String {
         let full_path = interner.lookup(&self.0);
-        if full_path.contains(root_dir) {
-            full_path[(root_dir.len() + 1)..].to_string()
-        } else {
-            full_path.to_string()
+
+        // only strip whole directories, so /foo/bar doesn't become a prefix of /foo/barbaz
+        match full_path.strip_prefix(root_dir) {
+            Some(relative_path) if root_dir.ends_with('/') || relative_path.starts_with('/') => {
+                relative_path.trim_start_matches('/').to_string()
+            }
+            _ => full_path.to_string(),
         }
     }
 }
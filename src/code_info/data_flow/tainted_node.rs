@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     code_location::{FilePath, HPos},
+    issue::TaintTraceStep,
     taint::{self, SinkType, SourceType},
 };
 
@@ -61,6 +62,29 @@ impl TaintedNode {
         source_descriptor
     }
 
+    /// The same path as get_trace, as a list of steps ordered from source to sink.
+    /// Nodes without a position (e.g. function return values) are reported at line 0
+    pub fn get_trace_steps(&self, interner: &Interner, root_dir: &str) -> Vec<TaintTraceStep> {
+        let mut steps = if let Some(previous_source) = &self.previous {
+            previous_source.get_trace_steps(interner, root_dir)
+        } else {
+            vec![]
+        };
+
+        steps.push(TaintTraceStep {
+            file: if let Some(pos) = &self.pos {
+                pos.file_path.get_relative_path(interner, root_dir)
+            } else {
+                "".to_string()
+            },
+            line: self.pos.as_ref().map(|pos| pos.start_line).unwrap_or(0),
+            column: self.pos.as_ref().map(|pos| pos.start_column).unwrap_or(0),
+            node_label: self.id.to_label(interner),
+        });
+
+        steps
+    }
+
     pub fn get_taint_sources(&self) -> &Vec<SourceType> {
         if let Some(previous_source) = &self.previous {
             return previous_source.get_taint_sources();
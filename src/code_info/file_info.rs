@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{ast_signature::DefSignatureNode, code_location::HPos};
+use crate::{ast_signature::DefSignatureNode, code_location::HPos, issue::IssueKind};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ParserError {
@@ -14,4 +16,7 @@ pub struct FileInfo {
     pub ast_nodes: Vec<DefSignatureNode>,
     pub closure_refs: Vec<u32>,
     pub parser_errors: Vec<ParserError>,
+    // issue kinds suppressed by @hakana-ignore-line and @hakana-ignore-next-line comments,
+    // keyed by the line they apply to
+    pub line_ignores: BTreeMap<u32, Vec<IssueKind>>,
 }
@@ -59,3 +59,15 @@ impl FunctionLikeIdentifier {
         }
     }
 }
+
+// Resolves a function name or Class::method, with or without a leading backslash, to the
+// key its storage is kept under
+pub fn get_functionlike_id(symbol: &str, interner: &Interner) -> Option<(StrId, StrId)> {
+    let symbol = symbol.trim_start_matches('\\');
+
+    if let Some((class_name, method_name)) = symbol.split_once("::") {
+        Some((interner.get(class_name)?, interner.get(method_name)?))
+    } else {
+        Some((interner.get(symbol)?, StrId::EMPTY))
+    }
+}
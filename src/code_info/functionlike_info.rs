@@ -165,6 +165,9 @@ pub struct FunctionLikeInfo {
 
     pub banned_function_message: Option<StrId>,
 
+    /// the first HHVM version a builtin is available in, when it's newer than some we support
+    pub min_hack_version: Option<(u16, u16)>,
+
     pub is_closure: bool,
 
     pub overriding: bool,
@@ -209,6 +212,7 @@ impl FunctionLikeInfo {
             is_closure: false,
             overriding: false,
             banned_function_message: None,
+            min_hack_version: None,
             ignore_noreturn_calls: false,
         }
     }
@@ -20,6 +20,7 @@ pub enum IssueKind {
     CannotInferGenericParam,
     CloneInsideLoop,
     CustomIssue(Box<String>),
+    DuplicateCaseValue,
     DuplicateEnumValue,
     EmptyBlock,
     FalsableReturnStatement,
@@ -53,7 +54,10 @@ pub enum IssueKind {
     LessSpecificNestedReturnStatement,
     LessSpecificReturnStatement,
     MethodCallOnNull,
+    MissingParameterType,
+    MissingPropertyType,
     MissingRequiredXhpAttribute,
+    MissingReturnType,
     MixedAnyArgument,
     MixedAnyArrayAccess,
     MixedAnyArrayAssignment,
@@ -112,15 +116,20 @@ pub enum IssueKind {
     RedundantTruthinessCheck,
     RedundantTypeComparison,
     ShadowedLoopVar,
+    ShadowedVariable,
     StrictObjectEquality,
+    SuspiciousLooseEquality,
     TaintedData(Box<SinkType>),
     TestOnlyCall,
     TooFewArguments,
     TooManyArguments,
+    UnavailableBuiltin,
+    UnawaitedAwaitable,
     UndefinedIntArrayOffset,
     UndefinedStringArrayOffset,
     UndefinedVariable,
     UnevaluatedCode,
+    UnhandledEnumCase,
     UnnecessaryShapesIdx,
     UnrecognizedBinaryOp,
     UnrecognizedExpression,
@@ -145,6 +154,7 @@ pub enum IssueKind {
     UnusedPublicOrProtectedMethod,
     UnusedPublicOrProtectedProperty,
     UnusedStatement,
+    UnusedSuppression,
     UnusedTrait,
     UnusedTypeDefinition,
     UnusedXhpAttribute,
@@ -152,6 +162,14 @@ pub enum IssueKind {
     UselessControlFlow,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Debug, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum IssueSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
 impl IssueKind {
     pub fn from_str_custom(
         str: &str,
@@ -231,6 +249,74 @@ impl IssueKind {
                 | Self::UnusedPipeVariable
         )
     }
+
+    // these are only reported when explicitly listed in allowed_issues
+    pub fn is_opt_in(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingParameterType
+                | Self::MissingPropertyType
+                | Self::MissingReturnType
+                | Self::ShadowedVariable
+                | Self::SuspiciousLooseEquality
+                | Self::UnawaitedAwaitable
+        )
+    }
+
+    pub fn get_severity(&self) -> IssueSeverity {
+        if self.is_mixed_issue() || self.is_unused_definition() || self.is_unused_expression() {
+            return IssueSeverity::Warning;
+        }
+
+        match self {
+            Self::EmptyBlock
+            | Self::MissingFinalOrAbstract
+            | Self::MissingParameterType
+            | Self::MissingPropertyType
+            | Self::MissingReturnType
+            | Self::ShadowedLoopVar
+            | Self::StrictObjectEquality
+            | Self::UnnecessaryShapesIdx
+            | Self::UnrecognizedBinaryOp
+            | Self::UnrecognizedExpression
+            | Self::UnrecognizedStatement
+            | Self::UnrecognizedUnaryOp
+            | Self::UselessControlFlow => IssueSeverity::Info,
+            Self::CannotInferGenericParam
+            | Self::CloneInsideLoop
+            | Self::LessSpecificArgument
+            | Self::LessSpecificNestedArgumentType
+            | Self::LessSpecificNestedReturnStatement
+            | Self::LessSpecificReturnStatement
+            | Self::PossibleMethodCallOnNull
+            | Self::PossiblyFalseArgument
+            | Self::PossiblyInvalidArgument
+            | Self::PossiblyInvalidArrayAccess
+            | Self::PossiblyInvalidMethodCall
+            | Self::PossiblyNullArrayAccess
+            | Self::PossiblyNullArrayOffset
+            | Self::PossiblyNullIterator
+            | Self::PossiblyNullPropertyFetch
+            | Self::PossiblyUndefinedIntArrayOffset
+            | Self::PossiblyUndefinedStringArrayOffset
+            | Self::PropertyTypeCoercion
+            | Self::RedundantIssetCheck
+            | Self::RedundantKeyCheck
+            | Self::RedundantNonnullEntryCheck
+            | Self::RedundantNonnullTypeComparison
+            | Self::RedundantTruthinessCheck
+            | Self::RedundantTypeComparison
+            | Self::UnawaitedAwaitable
+            | Self::UnusedAwaitable
+            | Self::UnusedBuiltinReturnValue
+            | Self::UnusedFunctionCall
+            | Self::UnusedMethodCall
+            | Self::UnusedStatement
+            | Self::UnusedSuppression
+            | Self::UpcastAwaitable => IssueSeverity::Warning,
+            _ => IssueSeverity::Error,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, Serialize, Deserialize)]
@@ -242,6 +328,16 @@ pub struct Issue {
     pub fixme_added: bool,
     pub symbol: (StrId, StrId),
     pub insertion_start: Option<StmtStart>,
+    /// For TaintedData issues, each step from the taint source to the sink
+    pub taint_trace: Option<Vec<TaintTraceStep>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaintTraceStep {
+    pub file: String,
+    pub line: u32,
+    pub column: u16,
+    pub node_label: String,
 }
 
 impl PartialEq for Issue {
@@ -280,6 +376,7 @@ impl Issue {
             can_fix: false,
             fixme_added: false,
             insertion_start: None,
+            taint_trace: None,
         }
     }
 
@@ -293,6 +390,59 @@ impl Issue {
             self.description
         )
     }
+
+    /// The comment inserted to suppress this issue, followed by whatever whitespace
+    /// is needed to keep the suppressed statement where it was
+    pub fn get_fixme_text(&self, insertion_start: &StmtStart) -> String {
+        format!(
+            "/* HAKANA_FIXME[{}]{} */{}",
+            self.kind.to_string(),
+            if let IssueKind::UnusedParameter
+            | IssueKind::UnusedAssignment
+            | IssueKind::UnusedAssignmentInClosure
+            | IssueKind::UnusedAssignmentStatement
+            | IssueKind::UnusedStatement
+            | IssueKind::UnusedFunction
+            | IssueKind::UnusedPrivateMethod = self.kind
+            {
+                "".to_string()
+            } else {
+                " ".to_string() + &self.description
+            },
+            if insertion_start.add_newline {
+                "\n".to_string() + &"\t".repeat(insertion_start.column as usize)
+            } else {
+                " ".to_string()
+            }
+        )
+    }
+}
+
+// Parses `@hakana-ignore-line Kind` and `@hakana-ignore-next-line Kind, OtherKind` comments,
+// returning whether the comment applies to the following line, and the kinds it suppresses
+pub fn get_line_ignore_from_comment(
+    trimmed_text: &str,
+    all_custom_issues: &FxHashSet<String>,
+) -> Option<(bool, Vec<IssueKind>)> {
+    let (next_line, kinds) =
+        if let Some(kinds) = trimmed_text.strip_prefix("@hakana-ignore-next-line ") {
+            (true, kinds)
+        } else if let Some(kinds) = trimmed_text.strip_prefix("@hakana-ignore-line ") {
+            (false, kinds)
+        } else {
+            return None;
+        };
+
+    let issue_kinds = kinds
+        .split(',')
+        .filter_map(|kind| IssueKind::from_str_custom(kind.trim(), all_custom_issues).ok())
+        .collect::<Vec<_>>();
+
+    if issue_kinds.is_empty() {
+        None
+    } else {
+        Some((next_line, issue_kinds))
+    }
 }
 
 pub fn get_issue_from_comment(
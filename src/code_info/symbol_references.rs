@@ -38,6 +38,12 @@ pub struct SymbolReferences {
     // This is used for dead code detection when we want to see what return values are unused
     pub functionlike_references_to_functionlike_returns:
         FxHashMap<FunctionLikeIdentifier, FxHashSet<FunctionLikeIdentifier>>,
+
+    // A lookup table of the functions and methods that each function or method calls.
+    // Unlike symbol_references_to_symbols it leaves out references that aren't calls,
+    // like function pointers, type hints and constant fetches
+    pub functionlike_references_to_functionlike_calls:
+        FxHashMap<(StrId, StrId), FxHashSet<(StrId, StrId)>>,
 }
 
 impl SymbolReferences {
@@ -47,6 +53,7 @@ impl SymbolReferences {
             symbol_references_to_symbols_in_signature: FxHashMap::default(),
             symbol_references_to_overridden_members: FxHashMap::default(),
             functionlike_references_to_functionlike_returns: FxHashMap::default(),
+            functionlike_references_to_functionlike_calls: FxHashMap::default(),
         }
     }
 
@@ -290,6 +297,25 @@ impl SymbolReferences {
             .insert(functionlike);
     }
 
+    pub fn add_reference_to_functionlike_call(
+        &mut self,
+        function_context: &FunctionContext,
+        functionlike: (StrId, StrId),
+    ) {
+        let referencing_functionlike = match &function_context.calling_functionlike_id {
+            Some(FunctionLikeIdentifier::Function(function_name)) => (*function_name, StrId::EMPTY),
+            Some(FunctionLikeIdentifier::Method(class_name, method_name)) => {
+                (*class_name, *method_name)
+            }
+            _ => return,
+        };
+
+        self.functionlike_references_to_functionlike_calls
+            .entry(referencing_functionlike)
+            .or_default()
+            .insert(functionlike);
+    }
+
     pub fn extend(&mut self, other: Self) {
         for (k, v) in other.symbol_references_to_symbols {
             self.symbol_references_to_symbols
@@ -311,6 +337,20 @@ impl SymbolReferences {
                 .or_default()
                 .extend(v);
         }
+
+        for (k, v) in other.functionlike_references_to_functionlike_returns {
+            self.functionlike_references_to_functionlike_returns
+                .entry(k)
+                .or_default()
+                .extend(v);
+        }
+
+        for (k, v) in other.functionlike_references_to_functionlike_calls {
+            self.functionlike_references_to_functionlike_calls
+                .entry(k)
+                .or_default()
+                .extend(v);
+        }
     }
 
     pub fn get_referenced_symbols_and_members(&self) -> FxHashSet<&(StrId, StrId)> {
@@ -538,5 +578,7 @@ impl SymbolReferences {
             .retain(|symbol, _| !invalid_symbols_and_members.contains(symbol));
         self.symbol_references_to_symbols_in_signature
             .retain(|symbol, _| !invalid_symbols_and_members.contains(symbol));
+        self.functionlike_references_to_functionlike_calls
+            .retain(|symbol, _| !invalid_symbols_and_members.contains(symbol));
     }
 }
@@ -102,6 +102,10 @@ pub enum TAtomic {
     TLiteralInt {
         value: i64,
     },
+    /// A float literal, stored as the bits of its f64 value so that TAtomic can stay Eq + Hash
+    TLiteralFloat {
+        value: u64,
+    },
     TLiteralString {
         value: String,
     },
@@ -432,6 +436,13 @@ impl TAtomic {
                 str += ")";
                 str
             }
+            TAtomic::TLiteralFloat { value } => {
+                let mut str = String::new();
+                str += "float(";
+                str += format!("{:?}", f64::from_bits(*value)).as_str();
+                str += ")";
+                str
+            }
             TAtomic::TLiteralString { value } => {
                 let mut str = String::new();
                 str += "string(";
@@ -733,6 +744,7 @@ impl TAtomic {
             | TAtomic::TEnum { .. }
             | TAtomic::TLiteralClassname { .. }
             | TAtomic::TLiteralInt { .. }
+            | TAtomic::TLiteralFloat { .. }
             | TAtomic::TEnumLiteralCase { .. }
             | TAtomic::TMemberReference { .. }
             | TAtomic::TClassTypeConstant { .. }
@@ -943,6 +955,7 @@ impl TAtomic {
                 | TAtomic::TGenericTypename { .. }
                 | TAtomic::TLiteralClassname { .. }
                 | TAtomic::TLiteralInt { .. }
+                | TAtomic::TLiteralFloat { .. }
                 | TAtomic::TLiteralString { .. }
                 | TAtomic::TArraykey { .. }
                 | TAtomic::TBool { .. }
@@ -982,7 +995,10 @@ impl TAtomic {
             || self.is_int()
             || matches!(
                 self,
-                TAtomic::TFloat | TAtomic::TNum | TAtomic::TArraykey { .. }
+                TAtomic::TFloat
+                    | TAtomic::TLiteralFloat { .. }
+                    | TAtomic::TNum
+                    | TAtomic::TArraykey { .. }
             )
         {
             return true;
@@ -1031,6 +1047,11 @@ impl TAtomic {
         matches!(self, TAtomic::TLiteralInt { .. } | TAtomic::TInt { .. })
     }
 
+    #[inline]
+    pub fn is_float(&self) -> bool {
+        matches!(self, TAtomic::TLiteralFloat { .. } | TAtomic::TFloat)
+    }
+
     #[inline]
     pub fn is_bool(&self) -> bool {
         matches!(
@@ -1044,6 +1065,7 @@ impl TAtomic {
             self,
             TAtomic::TLiteralClassname { .. }
                 | TAtomic::TLiteralInt { .. }
+                | TAtomic::TLiteralFloat { .. }
                 | TAtomic::TLiteralString { .. }
                 | TAtomic::TEnumLiteralCase { .. }
                 | TAtomic::TFalse { .. }
@@ -1135,6 +1157,7 @@ impl TAtomic {
                 }
                 false
             }
+            &TAtomic::TLiteralFloat { value, .. } => f64::from_bits(*value) != 0.0,
             &TAtomic::TLiteralString { value, .. } => {
                 if !value.is_empty() && value != "0" {
                     return true;
@@ -1194,6 +1217,7 @@ impl TAtomic {
                 }
                 false
             }
+            &TAtomic::TLiteralFloat { value, .. } => f64::from_bits(*value) == 0.0,
             &TAtomic::TLiteralString { value, .. } => {
                 if value.is_empty() || value == "0" {
                     return true;
@@ -1457,6 +1481,13 @@ impl TAtomic {
         }
     }
 
+    pub fn get_literal_float_value(&self) -> Option<f64> {
+        match self {
+            TAtomic::TLiteralFloat { value, .. } => Some(f64::from_bits(*value)),
+            _ => None,
+        }
+    }
+
     pub(crate) fn is_json_compatible(&self, banned_type_aliases: &Vec<StrId>) -> bool {
         if self.is_some_scalar() {
             return true;
@@ -105,13 +105,7 @@ impl TUnion {
     }
 
     pub fn has_float(&self) -> bool {
-        for atomic in &self.types {
-            if let TAtomic::TFloat { .. } = atomic {
-                return true;
-            };
-        }
-
-        false
+        self.types.iter().any(|atomic| atomic.is_float())
     }
 
     pub fn is_arraykey(&self) -> bool {
@@ -140,7 +134,7 @@ impl TUnion {
     }
 
     pub fn is_float(&self) -> bool {
-        self.types.len() == 1 && matches!(self.types[0], TAtomic::TFloat)
+        self.types.len() == 1 && self.types[0].is_float()
     }
 
     pub fn is_bool(&self) -> bool {
@@ -638,6 +632,7 @@ impl TUnion {
             matches!(
                 atomic,
                 TAtomic::TLiteralInt { .. }
+                    | TAtomic::TLiteralFloat { .. }
                     | TAtomic::TLiteralString { .. }
                     | TAtomic::TTrue { .. }
                     | TAtomic::TFalse { .. }
@@ -652,6 +647,7 @@ impl TUnion {
                 assignment_atomic_type,
                 TAtomic::TInt
                     | TAtomic::TFloat
+                    | TAtomic::TLiteralFloat { .. }
                     | TAtomic::TNull
                     | TAtomic::TLiteralClassname { .. }
                     | TAtomic::TLiteralInt { .. }
@@ -702,6 +698,14 @@ impl TUnion {
                         generalized_literals.push(TAtomic::TInt);
                     }
                 }
+                TAtomic::TLiteralFloat { .. } => {
+                    if !generalized_literals
+                        .iter()
+                        .any(|t| matches!(t, TAtomic::TFloat))
+                    {
+                        generalized_literals.push(TAtomic::TFloat);
+                    }
+                }
                 _ => {
                     types.push(t);
                 }
@@ -92,9 +92,10 @@ pub fn is_contained_by(
     {
         if input_type_part.is_string()
             || input_type_part.is_int()
+            || input_type_part.is_float()
             || matches!(
                 input_type_part,
-                TAtomic::TFloat | TAtomic::TNum | TAtomic::TArraykey { .. } | TAtomic::TNull
+                TAtomic::TNum | TAtomic::TArraykey { .. } | TAtomic::TNull
             )
         {
             return true;
@@ -302,6 +303,18 @@ pub fn is_contained_by(
             type_param: input_type_param,
         } = input_type_part
         {
+            // keyset values are always arraykeys, so any int or string param (including
+            // literal-narrowed ones, and the nothing param of an empty keyset) fits
+            if container_type_param.is_arraykey()
+                && input_type_param.types.iter().all(|t| {
+                    t.is_int()
+                        || t.is_string()
+                        || matches!(t, TAtomic::TArraykey { .. } | TAtomic::TNothing)
+                })
+            {
+                return true;
+            }
+
             return union_type_comparator::is_contained_by(
                 codebase,
                 input_type_param,
@@ -667,6 +680,7 @@ pub fn is_contained_by(
             | TAtomic::TInt
             | TAtomic::TLiteralInt { .. }
             | TAtomic::TFloat
+            | TAtomic::TLiteralFloat { .. }
             | TAtomic::TNum = input_type_part
             {
                 return true;
@@ -88,7 +88,11 @@ pub fn is_contained_by(
     if matches!(container_type_part, TAtomic::TNum)
         && matches!(
             input_type_part,
-            TAtomic::TNum | TAtomic::TFloat | TAtomic::TInt | TAtomic::TLiteralInt { .. }
+            TAtomic::TNum
+                | TAtomic::TFloat
+                | TAtomic::TLiteralFloat { .. }
+                | TAtomic::TInt
+                | TAtomic::TLiteralInt { .. }
         )
     {
         return true;
@@ -133,6 +137,19 @@ pub fn is_contained_by(
         }
     }
 
+    if let TAtomic::TLiteralFloat {
+        value: container_value,
+        ..
+    } = container_type_part
+    {
+        if let TAtomic::TLiteralFloat {
+            value: input_value, ..
+        } = input_type_part
+        {
+            return input_value == container_value;
+        }
+    }
+
     if let TAtomic::TEnum {
         name: container_name,
         ..
@@ -287,6 +304,20 @@ pub fn is_contained_by(
         return false;
     }
 
+    if matches!(container_type_part, TAtomic::TFloat)
+        && matches!(input_type_part, TAtomic::TLiteralFloat { .. })
+    {
+        return true;
+    }
+
+    if matches!(input_type_part, TAtomic::TFloat)
+        && matches!(container_type_part, TAtomic::TLiteralFloat { .. })
+    {
+        atomic_comparison_result.type_coerced = Some(true);
+        atomic_comparison_result.type_coerced_to_literal = Some(true);
+        return false;
+    }
+
     if (matches!(input_type_part, TAtomic::TFalse | TAtomic::TTrue))
         && matches!(container_type_part, TAtomic::TBool)
     {
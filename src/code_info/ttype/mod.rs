@@ -2,10 +2,12 @@ use hakana_str::{Interner, StrId};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
+    classlike_info::ClassConstantType,
     codebase_info::CodebaseInfo,
     data_flow::node::DataFlowNode,
     t_atomic::{DictKey, TAtomic, TDict},
     t_union::TUnion,
+    ttype::comparison::type_comparison_result::TypeComparisonResult,
     type_resolution::TypeResolutionContext,
 };
 use itertools::Itertools;
@@ -47,6 +49,13 @@ pub fn get_float() -> TUnion {
     wrap_atomic(TAtomic::TFloat)
 }
 
+#[inline]
+pub fn get_literal_float(value: f64) -> TUnion {
+    wrap_atomic(TAtomic::TLiteralFloat {
+        value: value.to_bits(),
+    })
+}
+
 #[inline]
 pub fn get_mixed() -> TUnion {
     wrap_atomic(TAtomic::TMixed)
@@ -323,11 +332,111 @@ pub fn add_union_type(
     base_type
 }
 
+/// Computes the meet of two types, which is nothing when they share no values.
 pub fn intersect_union_types(
-    _type_1: &TUnion,
-    _type_2: &TUnion,
-    _codebase: &CodebaseInfo,
+    type_1: &TUnion,
+    type_2: &TUnion,
+    codebase: &CodebaseInfo,
 ) -> Option<TUnion> {
+    if type_1 == type_2 {
+        return Some(type_1.clone());
+    }
+
+    if type_1.is_mixed() {
+        return Some(type_2.clone());
+    }
+
+    if type_2.is_mixed() {
+        return Some(type_1.clone());
+    }
+
+    let mut intersected_atomic_types = vec![];
+
+    for type_1_atomic in &type_1.types {
+        for type_2_atomic in &type_2.types {
+            if let Some(intersected_atomic) =
+                intersect_atomic_types(type_1_atomic, type_2_atomic, codebase)
+            {
+                intersected_atomic_types.push(intersected_atomic);
+            }
+        }
+    }
+
+    if intersected_atomic_types.is_empty() {
+        return Some(get_nothing());
+    }
+
+    let mut intersected_type = TUnion::new(combine(intersected_atomic_types, codebase, false));
+
+    if type_1.had_template && type_2.had_template {
+        intersected_type.had_template = true;
+    }
+
+    if type_1.reference_free || type_2.reference_free {
+        intersected_type.reference_free = true;
+    }
+
+    if type_1.possibly_undefined_from_try && type_2.possibly_undefined_from_try {
+        intersected_type.possibly_undefined_from_try = true;
+    }
+
+    if type_1.ignore_falsable_issues || type_2.ignore_falsable_issues {
+        intersected_type.ignore_falsable_issues = true;
+    }
+
+    Some(intersected_type)
+}
+
+fn intersect_atomic_types(
+    type_1_atomic: &TAtomic,
+    type_2_atomic: &TAtomic,
+    codebase: &CodebaseInfo,
+) -> Option<TAtomic> {
+    if type_1_atomic.is_mixed() {
+        return Some(type_2_atomic.clone());
+    }
+
+    if type_2_atomic.is_mixed() {
+        return Some(type_1_atomic.clone());
+    }
+
+    if comparison::atomic_type_comparator::is_contained_by(
+        codebase,
+        type_2_atomic,
+        type_1_atomic,
+        false,
+        &mut TypeComparisonResult::new(),
+    ) {
+        return Some(type_2_atomic.clone());
+    }
+
+    if comparison::atomic_type_comparator::is_contained_by(
+        codebase,
+        type_1_atomic,
+        type_2_atomic,
+        false,
+        &mut TypeComparisonResult::new(),
+    ) {
+        return Some(type_1_atomic.clone());
+    }
+
+    // two unrelated classlikes can only share values when one of them is an interface
+    if let (
+        TAtomic::TNamedObject {
+            name: type_1_name, ..
+        },
+        TAtomic::TNamedObject {
+            name: type_2_name, ..
+        },
+    ) = (type_1_atomic, type_2_atomic)
+    {
+        if codebase.interface_exists(type_1_name) || codebase.interface_exists(type_2_name) {
+            let mut intersected_atomic = type_1_atomic.clone();
+            intersected_atomic.add_intersection_type(type_2_atomic.clone());
+            return Some(intersected_atomic);
+        }
+    }
+
     None
 }
 
@@ -471,6 +580,41 @@ pub fn get_union_syntax_type(
     codebase: &CodebaseInfo,
     interner: &Interner,
     is_valid: &mut bool,
+) -> String {
+    get_union_syntax_type_with_aliases(union, codebase, interner, &mut None, is_valid)
+}
+
+// how many times an alias can be expanded inside its own expansion
+const MAX_NESTED_ALIAS_EXPANSIONS: usize = 3;
+
+/// Like get_union_syntax_type, but renders type aliases as the types they stand for, with
+/// any type params substituted, and does the same for concrete class type constants.
+/// Newtypes are still rendered by name, since their underlying type isn't visible outside
+/// the file they're defined in, as are aliases that refer back to themselves.
+pub fn get_expanded_union_syntax_type(
+    union: &TUnion,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    is_valid: &mut bool,
+) -> String {
+    get_union_syntax_type_with_aliases(union, codebase, interner, &mut Some(vec![]), is_valid)
+}
+
+pub fn get_atomic_syntax_type(
+    atomic: &TAtomic,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    is_valid: &mut bool,
+) -> String {
+    get_atomic_syntax_type_with_aliases(atomic, codebase, interner, &mut None, is_valid)
+}
+
+fn get_union_syntax_type_with_aliases(
+    union: &TUnion,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    expanding_aliases: &mut Option<Vec<(StrId, Option<Vec<TUnion>>)>>,
+    is_valid: &mut bool,
 ) -> String {
     let mut t_atomic_strings = FxHashSet::default();
 
@@ -478,13 +622,30 @@ pub fn get_union_syntax_type(
 
     let is_nullable = union.is_nullable() && !union.is_mixed();
 
+    // an intersection has to be parenthesised before it can be made nullable
+    let mut has_intersection = false;
+
     for atomic in &union.types {
         if let TAtomic::TNull { .. } = atomic {
             continue;
         }
 
+        if let TAtomic::TNamedObject {
+            extra_types: Some(_),
+            ..
+        } = atomic
+        {
+            has_intersection = true;
+        }
+
         t_atomic_strings.insert({
-            let s = get_atomic_syntax_type(atomic, codebase, interner, is_valid);
+            let s = get_atomic_syntax_type_with_aliases(
+                atomic,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             if let TAtomic::TNamedObject {
                 name,
                 type_params: None,
@@ -534,20 +695,35 @@ pub fn get_union_syntax_type(
         return "_".to_string();
     }
 
-    if is_nullable { "?" } else { "" }.to_string() + t_atomic_strings.iter().join("").as_str()
+    let atomic_string = t_atomic_strings.iter().join("");
+
+    if !is_nullable {
+        atomic_string
+    } else if has_intersection {
+        format!("?({})", atomic_string)
+    } else {
+        "?".to_string() + atomic_string.as_str()
+    }
 }
 
-pub fn get_atomic_syntax_type(
+fn get_atomic_syntax_type_with_aliases(
     atomic: &TAtomic,
     codebase: &CodebaseInfo,
     interner: &Interner,
+    expanding_aliases: &mut Option<Vec<(StrId, Option<Vec<TUnion>>)>>,
     is_valid: &mut bool,
 ) -> String {
     match atomic {
         TAtomic::TArraykey { .. } => "arraykey".to_string(),
         TAtomic::TBool { .. } => "bool".to_string(),
         TAtomic::TClassname { as_type, .. } => {
-            let as_string = get_atomic_syntax_type(as_type, codebase, interner, is_valid);
+            let as_string = get_atomic_syntax_type_with_aliases(
+                as_type,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             let mut str = String::new();
             str += "classname<";
             str += as_string.as_str();
@@ -555,7 +731,13 @@ pub fn get_atomic_syntax_type(
             str
         }
         TAtomic::TTypename { as_type, .. } => {
-            let as_string = get_atomic_syntax_type(as_type, codebase, interner, is_valid);
+            let as_string = get_atomic_syntax_type_with_aliases(
+                as_type,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             let mut str = String::new();
             str += "typename<";
             str += as_string.as_str();
@@ -563,7 +745,13 @@ pub fn get_atomic_syntax_type(
             str
         }
         TAtomic::TAwaitable { value, .. } => {
-            let value_string = get_union_syntax_type(value, codebase, interner, is_valid);
+            let value_string = get_union_syntax_type_with_aliases(
+                value,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             let mut str = String::new();
             str += "Awaitable<";
             str += value_string.as_str();
@@ -600,8 +788,13 @@ pub fn get_atomic_syntax_type(
 
                     for (property, (pu, property_type)) in known_items {
                         known_item_strings.push({
-                            let property_type_string =
-                                get_union_syntax_type(property_type, codebase, interner, is_valid);
+                            let property_type_string = get_union_syntax_type_with_aliases(
+                                property_type,
+                                codebase,
+                                interner,
+                                expanding_aliases,
+                                is_valid,
+                            );
                             format!(
                                 "{}'{}' => {}",
                                 if *pu { "?".to_string() } else { "".to_string() },
@@ -622,8 +815,20 @@ pub fn get_atomic_syntax_type(
             }
 
             if let Some(params) = params {
-                let key_param = get_union_syntax_type(&params.0, codebase, interner, is_valid);
-                let value_param = get_union_syntax_type(&params.1, codebase, interner, is_valid);
+                let key_param = get_union_syntax_type_with_aliases(
+                    &params.0,
+                    codebase,
+                    interner,
+                    expanding_aliases,
+                    is_valid,
+                );
+                let value_param = get_union_syntax_type_with_aliases(
+                    &params.1,
+                    codebase,
+                    interner,
+                    expanding_aliases,
+                    is_valid,
+                );
                 format!("dict<{}, {}>", key_param, value_param)
             } else {
                 "dict<nothing, nothing>".to_string()
@@ -631,11 +836,51 @@ pub fn get_atomic_syntax_type(
         }
         TAtomic::TEnum { name, .. } => interner.lookup(name).to_string(),
         TAtomic::TFalse { .. } => "bool".to_string(),
-        TAtomic::TFloat { .. } => "float".to_string(),
-        TAtomic::TClosure(_) => {
-            *is_valid = false;
-            // todo
-            "_".to_string()
+        TAtomic::TFloat { .. } | TAtomic::TLiteralFloat { .. } => "float".to_string(),
+        TAtomic::TClosure(closure) => {
+            let param_strings = closure
+                .params
+                .iter()
+                .map(|param| {
+                    let param_type = if let Some(param_type) = &param.signature_type {
+                        get_union_syntax_type_with_aliases(
+                            param_type,
+                            codebase,
+                            interner,
+                            expanding_aliases,
+                            is_valid,
+                        )
+                    } else {
+                        "mixed".to_string()
+                    };
+
+                    format!(
+                        "{}{}{}{}",
+                        if param.is_optional && !param.is_variadic {
+                            "optional "
+                        } else {
+                            ""
+                        },
+                        if param.is_inout { "inout " } else { "" },
+                        param_type,
+                        if param.is_variadic { "..." } else { "" }
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let return_type = if let Some(return_type) = &closure.return_type {
+                get_union_syntax_type_with_aliases(
+                    return_type,
+                    codebase,
+                    interner,
+                    expanding_aliases,
+                    is_valid,
+                )
+            } else {
+                "mixed".to_string()
+            };
+
+            format!("(function({}): {})", param_strings.join(", "), return_type)
         }
         TAtomic::TClosureAlias { .. } => {
             *is_valid = false;
@@ -648,7 +893,13 @@ pub fn get_atomic_syntax_type(
             "_".to_string()
         }
         TAtomic::TKeyset { type_param, .. } => {
-            let type_param = get_union_syntax_type(type_param, codebase, interner, is_valid);
+            let type_param = get_union_syntax_type_with_aliases(
+                type_param,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             format!("keyset<{}>", type_param)
         }
         TAtomic::TLiteralClassname { .. } => {
@@ -663,21 +914,98 @@ pub fn get_atomic_syntax_type(
         TAtomic::TLiteralString { .. } | TAtomic::TStringWithFlags(..) => "string".to_string(),
         TAtomic::TMixed | TAtomic::TMixedFromLoopIsset => "mixed".to_string(),
         TAtomic::TNamedObject {
-            name, type_params, ..
-        } => match type_params {
-            None => interner.lookup(name).to_string(),
-            Some(type_params) => {
-                let mut param_strings = vec![];
-                for param in type_params {
-                    param_strings.push(get_union_syntax_type(param, codebase, interner, is_valid));
+            name,
+            type_params,
+            extra_types,
+            ..
+        } => {
+            let mut str = match type_params {
+                None => interner.lookup(name).to_string(),
+                Some(type_params) => {
+                    let mut param_strings = vec![];
+                    for param in type_params {
+                        param_strings.push(get_union_syntax_type_with_aliases(
+                            param,
+                            codebase,
+                            interner,
+                            expanding_aliases,
+                            is_valid,
+                        ));
+                    }
+
+                    format!("{}<{}>", interner.lookup(name), param_strings.join(", "))
                 }
+            };
 
-                format!("{}<{}>", interner.lookup(name), param_strings.join(", "))
+            if let Some(extra_types) = extra_types {
+                for extra_type in extra_types {
+                    str += " & ";
+                    str += get_atomic_syntax_type_with_aliases(
+                        extra_type,
+                        codebase,
+                        interner,
+                        expanding_aliases,
+                        is_valid,
+                    )
+                    .as_str();
+                }
             }
-        },
+
+            str
+        }
         TAtomic::TTypeAlias {
             name, type_params, ..
         } => {
+            let alias_type = match (
+                expanding_aliases.as_ref(),
+                codebase.type_definitions.get(name),
+            ) {
+                (Some(aliases), Some(type_definition))
+                    if type_definition.newtype_file.is_none()
+                        && !aliases.iter().any(|(alias_name, alias_type_params)| {
+                            alias_name == name && alias_type_params == type_params
+                        })
+                        && aliases
+                            .iter()
+                            .filter(|(alias_name, _)| alias_name == name)
+                            .count()
+                            < MAX_NESTED_ALIAS_EXPANSIONS =>
+                {
+                    Some(type_expander::get_untemplated_alias_type(
+                        type_definition,
+                        type_params,
+                        codebase,
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some(alias_type) = alias_type {
+                // the alias stays on the stack while its own type is rendered, so an alias
+                // that refers back to itself with the same type params is rendered by name
+                // instead, while e.g. Box<Box<int>> is still expanded all the way down. One
+                // that refers back to itself with different params, like
+                // type G<T> = vec<G<vec<T>>>, would never repeat, so it's cut off after a few
+                // expansions
+                if let Some(aliases) = expanding_aliases.as_mut() {
+                    aliases.push((*name, type_params.clone()));
+                }
+
+                let alias_string = get_union_syntax_type_with_aliases(
+                    &alias_type,
+                    codebase,
+                    interner,
+                    expanding_aliases,
+                    is_valid,
+                );
+
+                if let Some(aliases) = expanding_aliases.as_mut() {
+                    aliases.pop();
+                }
+
+                return alias_string;
+            }
+
             if type_params.is_none() {
                 interner.lookup(name).to_string()
             } else {
@@ -727,8 +1055,13 @@ pub fn get_atomic_syntax_type(
                     let mut all_good = true;
                     for (i, (offset, (pu, t))) in known_items.iter().enumerate() {
                         if i == *offset && !pu {
-                            known_item_strings
-                                .push(get_union_syntax_type(t, codebase, interner, is_valid))
+                            known_item_strings.push(get_union_syntax_type_with_aliases(
+                                t,
+                                codebase,
+                                interner,
+                                expanding_aliases,
+                                is_valid,
+                            ))
                         } else {
                             all_good = false;
                             break;
@@ -743,7 +1076,13 @@ pub fn get_atomic_syntax_type(
 
             let type_param = get_value_param(atomic, codebase).unwrap();
 
-            let type_param = get_union_syntax_type(&type_param, codebase, interner, is_valid);
+            let type_param = get_union_syntax_type_with_aliases(
+                &type_param,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             format!("vec<{}>", type_param)
         }
         TAtomic::TVoid => "void".to_string(),
@@ -768,7 +1107,31 @@ pub fn get_atomic_syntax_type(
             member_name,
             ..
         } => {
-            let lhs = get_atomic_syntax_type(class_type, codebase, interner, is_valid);
+            if expanding_aliases.is_some() {
+                if let TAtomic::TNamedObject { name, .. } = class_type.as_ref() {
+                    if let Some(ClassConstantType::Concrete(constant_type)) = codebase
+                        .classlike_infos
+                        .get(name)
+                        .and_then(|classlike_info| classlike_info.type_constants.get(member_name))
+                    {
+                        return get_union_syntax_type_with_aliases(
+                            constant_type,
+                            codebase,
+                            interner,
+                            expanding_aliases,
+                            is_valid,
+                        );
+                    }
+                }
+            }
+
+            let lhs = get_atomic_syntax_type_with_aliases(
+                class_type,
+                codebase,
+                interner,
+                expanding_aliases,
+                is_valid,
+            );
             format!("{}::{}", lhs, interner.lookup(member_name))
         }
         TAtomic::TEnumClassLabel { .. } => {
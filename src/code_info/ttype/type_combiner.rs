@@ -1047,7 +1047,11 @@ fn scrape_type_properties(
         }
     }
 
-    if let TAtomic::TFloat | TAtomic::TInt | TAtomic::TLiteralInt { .. } = atomic {
+    if let TAtomic::TFloat
+    | TAtomic::TLiteralFloat { .. }
+    | TAtomic::TInt
+    | TAtomic::TLiteralInt { .. } = atomic
+    {
         if combination.value_types.contains_key("num")
             || combination.value_types.contains_key("scalar")
         {
@@ -1181,6 +1185,19 @@ fn scrape_type_properties(
         return;
     }
 
+    // literal floats share the float slot, so two different ones widen to float
+    if let TAtomic::TLiteralFloat { .. } = atomic {
+        if let Some(existing_float_type) = combination.value_types.get_mut("float") {
+            if *existing_float_type != atomic {
+                *existing_float_type = TAtomic::TFloat;
+            }
+        } else {
+            combination.value_types.insert("float".to_string(), atomic);
+        }
+
+        return;
+    }
+
     combination.value_types.insert(atomic.get_key(), atomic);
 }
 
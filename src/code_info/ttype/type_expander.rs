@@ -13,6 +13,7 @@ use crate::{
     functionlike_parameter::FnParameter,
     t_atomic::{DictKey, TAtomic, TClosure, TDict},
     t_union::TUnion,
+    type_definition_info::TypeDefinitionInfo,
 };
 use crate::{functionlike_identifier::FunctionLikeIdentifier, method_identifier::MethodIdentifier};
 use hakana_str::{Interner, StrId};
@@ -22,6 +23,36 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::ttype::{extend_dataflow_uniquely, get_nothing, template, type_combiner, wrap_atomic};
 
+// The type an alias stands for, with the alias's type params substituted in
+pub(crate) fn get_untemplated_alias_type(
+    type_definition: &TypeDefinitionInfo,
+    type_params: &Option<Vec<TUnion>>,
+    codebase: &CodebaseInfo,
+) -> TUnion {
+    if let Some(type_params) = type_params {
+        let mut new_template_types = IndexMap::new();
+
+        for (i, (k, v)) in type_definition.template_types.iter().enumerate() {
+            if i < type_params.len() {
+                let mut h = FxHashMap::default();
+                for (kk, _) in v {
+                    h.insert(*kk, type_params[i].clone());
+                }
+
+                new_template_types.insert(*k, h);
+            }
+        }
+
+        template::inferred_type_replacer::replace(
+            &type_definition.actual_type,
+            &template::TemplateResult::new(IndexMap::new(), new_template_types),
+            codebase,
+        )
+    } else {
+        type_definition.actual_type.clone()
+    }
+}
+
 #[derive(Debug)]
 pub enum StaticClassType<'a, 'b> {
     None,
@@ -398,28 +429,8 @@ fn expand_atomic(
         if can_expand_type {
             *skip_key = true;
 
-            let mut untemplated_type = if let Some(type_params) = type_params {
-                let mut new_template_types = IndexMap::new();
-
-                for (i, (k, v)) in type_definition.template_types.iter().enumerate() {
-                    if i < type_params.len() {
-                        let mut h = FxHashMap::default();
-                        for (kk, _) in v {
-                            h.insert(*kk, type_params[i].clone());
-                        }
-
-                        new_template_types.insert(*k, h);
-                    }
-                }
-
-                template::inferred_type_replacer::replace(
-                    &type_definition.actual_type,
-                    &template::TemplateResult::new(IndexMap::new(), new_template_types),
-                    codebase,
-                )
-            } else {
-                type_definition.actual_type.clone()
-            };
+            let mut untemplated_type =
+                get_untemplated_alias_type(type_definition, type_params, codebase);
 
             expand_union(
                 codebase,
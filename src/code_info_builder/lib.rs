@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::sync::Arc;
 
@@ -6,6 +7,7 @@ use hakana_aast_helper::Uses;
 use hakana_code_info::attribute_info::AttributeInfo;
 use hakana_code_info::file_info::{FileInfo, ParserError};
 use hakana_code_info::functionlike_info::FunctionLikeInfo;
+use hakana_code_info::issue::{get_line_ignore_from_comment, IssueKind};
 use hakana_code_info::t_atomic::TDict;
 use hakana_code_info::t_union::TUnion;
 use hakana_code_info::ttype::{get_bool, get_int, get_mixed_any, get_string};
@@ -20,6 +22,7 @@ use hakana_str::{StrId, ThreadedInterner};
 use no_pos_hash::{position_insensitive_hash, Hasher};
 use oxidized::ast::{FunParam, Tparam, TypeHint};
 use oxidized::ast_defs::Id;
+use oxidized::prim_defs::Comment;
 use oxidized::tast;
 use oxidized::{
     aast,
@@ -730,6 +733,7 @@ impl<'a> Scanner<'a> {
         if !self.user_defined {
             if let Some(name) = name {
                 fix_function_return_type(name, &mut functionlike_storage);
+                functionlike_storage.min_hack_version = get_min_hack_version(name);
             }
         }
 
@@ -814,6 +818,16 @@ fn fix_function_return_type(function_name: StrId, functionlike_storage: &mut Fun
     }
 }
 
+// builtins that were added in a recent HHVM release, keyed to the release they first appeared in
+fn get_min_hack_version(function_name: StrId) -> Option<(u16, u16)> {
+    match function_name {
+        StrId::NON_CRYPTO_MD5_LOWER | StrId::NON_CRYPTO_MD5_UPPER => Some((4, 143)),
+        StrId::STR_TO_NUMERIC | StrId::STR_NUMBER_COERCIBLE => Some((4, 153)),
+        StrId::TYPE_STRUCTURE_FOR_ALIAS => Some((4, 163)),
+        _ => None,
+    }
+}
+
 fn get_uses_hash(uses: &Vec<(StrId, StrId)>) -> u64 {
     let mut hasher = rustc_hash::FxHasher::default();
     uses.hash(&mut hasher);
@@ -918,7 +932,32 @@ pub fn collect_info_for_aast(
                 closure_refs: checker.closure_refs,
                 ast_nodes: checker.ast_nodes,
                 parser_errors,
+                line_ignores: get_line_ignores(checker.file_source.comments, all_custom_issues),
             },
         );
     }
 }
+
+fn get_line_ignores(
+    comments: &Vec<(tast::Pos, Comment)>,
+    all_custom_issues: &FxHashSet<String>,
+) -> BTreeMap<u32, Vec<IssueKind>> {
+    let mut line_ignores = BTreeMap::new();
+
+    for (pos, comment) in comments {
+        if let Comment::CmtLine(text) = comment {
+            if let Some((next_line, issue_kinds)) =
+                get_line_ignore_from_comment(text.trim(), all_custom_issues)
+            {
+                let line = pos.line() as u32 + if next_line { 1 } else { 0 };
+
+                line_ignores
+                    .entry(line)
+                    .or_insert_with(Vec::new)
+                    .extend(issue_kinds);
+            }
+        }
+    }
+
+    line_ignores
+}
@@ -129,7 +129,13 @@ pub fn infer(expr: &aast::Expr<(), ()>, resolved_names: &FxHashMap<u32, StrId>)
         aast::Expr_::Int(value) => Some(TAtomic::TLiteralInt {
             value: int_from_string(value).unwrap(),
         }),
-        aast::Expr_::Float(_) => Some(TAtomic::TFloat),
+        aast::Expr_::Float(value) => Some(if let Ok(value) = value.parse::<f64>() {
+            TAtomic::TLiteralFloat {
+                value: value.to_bits(),
+            }
+        } else {
+            TAtomic::TFloat
+        }),
         aast::Expr_::String(value) => Some(if value.len() < 200 {
             TAtomic::TLiteralString {
                 value: value.to_string(),
@@ -171,6 +177,10 @@ pub fn infer(expr: &aast::Expr<(), ()>, resolved_names: &FxHashMap<u32, StrId>)
                 if let Some(number_type) = number_type {
                     if let TAtomic::TLiteralInt { value, .. } = number_type {
                         Some(TAtomic::TLiteralInt { value: -value })
+                    } else if let TAtomic::TLiteralFloat { value, .. } = number_type {
+                        Some(TAtomic::TLiteralFloat {
+                            value: (-f64::from_bits(value)).to_bits(),
+                        })
                     } else {
                         Some(number_type)
                     }
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::error::Error;
+use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -6,46 +8,90 @@ use std::time::Duration;
 use hakana_analyzer::config::{self, Config};
 use hakana_analyzer::custom_hook::CustomHook;
 use hakana_code_info::analysis_result::AnalysisResult;
-use hakana_str::Interner;
+use hakana_code_info::ast_signature::DefSignatureNode;
+use hakana_code_info::classlike_info::ClassLikeInfo;
+use hakana_code_info::code_location::{FilePath, HPos};
+use hakana_code_info::codebase_info::symbols::SymbolKind as CodebaseSymbolKind;
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_code_info::functionlike_info::FunctionLikeInfo;
+use hakana_code_info::issue::{IssueKind, IssueSeverity};
+use hakana_code_info::member_visibility::MemberVisibility;
+use hakana_str::{Interner, StrId};
 use hakana_orchestrator::file::FileStatus;
 use hakana_orchestrator::{scan_and_analyze_async, SuccessfulScanData};
 use rustc_hash::{FxHashMap, FxHashSet};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error as JsonRpcError, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
 #[derive(Debug)]
 pub struct Backend {
     client: Client,
-    analysis_config: Arc<Config>,
+    // only changed at the start of an analysis run, since each run holds its own reference
+    analysis_config: RwLock<Arc<Config>>,
+    // the values from hakana.json, used when the editor's settings don't override them
+    file_allowed_issues: Option<FxHashSet<IssueKind>>,
+    file_find_unused_definitions: bool,
+    // the settings currently in effect, applied to the config when the next run starts
+    allowed_issues: RwLock<Option<FxHashSet<IssueKind>>>,
+    find_unused_definitions: RwLock<bool>,
     starter_interner: Arc<Interner>,
     previous_scan_data: RwLock<Option<SuccessfulScanData>>,
     previous_analysis_result: RwLock<Option<AnalysisResult>>,
     all_diagnostics: RwLock<Option<FxHashMap<Url, Vec<Diagnostic>>>>,
     file_changes: RwLock<Option<FxHashMap<String, FileStatus>>>,
     files_with_errors: RwLock<FxHashSet<Url>>,
+    workspace_symbol_limit: RwLock<usize>,
+    // unsaved contents of open files, which are analyzed in place of what's on disk
+    open_documents: RwLock<FxHashMap<Url, String>>,
+    // bumped on every edit, so that an edit can tell whether another one came in after it
+    document_change_count: RwLock<usize>,
 }
 
+const DEFAULT_WORKSPACE_SYMBOL_LIMIT: usize = 100;
+
+// how long typing has to pause for before unsaved edits are analyzed
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+// drops all incremental state and analyzes the whole codebase again
+const REANALYZE_COMMAND: &str = "hakana.reanalyze";
+
 impl Backend {
     pub fn new(client: Client, analysis_config: Config, starter_interner: Interner) -> Self {
         Self {
             client,
-            analysis_config: Arc::new(analysis_config),
+            file_allowed_issues: analysis_config.allowed_issues.clone(),
+            file_find_unused_definitions: analysis_config.find_unused_definitions,
+            allowed_issues: RwLock::new(analysis_config.allowed_issues.clone()),
+            find_unused_definitions: RwLock::new(analysis_config.find_unused_definitions),
+            analysis_config: RwLock::new(Arc::new(analysis_config)),
             starter_interner: Arc::new(starter_interner),
             previous_scan_data: RwLock::new(None),
             previous_analysis_result: RwLock::new(None),
             all_diagnostics: RwLock::new(None),
             file_changes: RwLock::new(None),
             files_with_errors: RwLock::new(FxHashSet::default()),
+            workspace_symbol_limit: RwLock::new(DEFAULT_WORKSPACE_SYMBOL_LIMIT),
+            open_documents: RwLock::new(FxHashMap::default()),
+            document_change_count: RwLock::new(0),
         }
     }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(limit) = params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("workspaceSymbolLimit"))
+            .and_then(|limit| limit.as_u64())
+        {
+            *self.workspace_symbol_limit.write().await = limit as usize;
+        }
+
         self.do_analysis().await;
 
         Ok(InitializeResult {
@@ -53,12 +99,30 @@ impl LanguageServer for Backend {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::NONE),
+                        change: Some(TextDocumentSyncKind::FULL),
                         will_save: Some(false),
                         will_save_wait_until: Some(false),
                         save: Some(TextDocumentSyncSaveOptions::Supported(true)),
                     },
                 )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                references_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![">".to_string(), ":".to_string()]),
+                    ..CompletionOptions::default()
+                }),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![REANALYZE_COMMAND.to_string()],
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
                 ..ServerCapabilities::default()
             },
             ..InitializeResult::default()
@@ -178,29 +242,906 @@ impl LanguageServer for Backend {
         }
     }
 
-    async fn hover(&self, _: HoverParams) -> Result<Option<Hover>> {
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.open_documents
+            .write()
+            .await
+            .insert(params.text_document.uri.clone(), params.text_document.text);
+
+        // hover types are only kept for open documents, so a newly opened file needs them
+        // collected again
+        if !self.has_hover_types(&params.text_document.uri).await {
+            self.analyze_open_document(&params.text_document.uri).await;
+        }
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // with full sync, the last change holds the whole document
+        if let Some(change) = params.content_changes.into_iter().last() {
+            self.open_documents
+                .write()
+                .await
+                .insert(params.text_document.uri.clone(), change.text);
+
+            if !self.queue_open_document(&params.text_document.uri).await {
+                return;
+            }
+
+            let change_count = {
+                let mut change_count_guard = self.document_change_count.write().await;
+                *change_count_guard += 1;
+                *change_count_guard
+            };
+
+            // only analyze once typing pauses. Queued files are analyzed together, so any
+            // later edit will pick this file up along with its own
+            sleep(DOCUMENT_CHANGE_DEBOUNCE).await;
+
+            if *self.document_change_count.read().await != change_count {
+                return;
+            }
+
+            self.do_analysis().await;
+            self.emit_issues().await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.open_documents
+            .write()
+            .await
+            .remove(&params.text_document.uri);
+
+        // any unsaved edits were discarded, so go back to what's on disk
+        self.analyze_open_document(&params.text_document.uri).await;
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+        let analysis_result_guard = self.previous_analysis_result.read().await;
+
+        let (scan_data, analysis_result) = if let (Some(scan_data), Some(analysis_result)) =
+            (scan_data_guard.as_ref(), analysis_result_guard.as_ref())
+        {
+            (scan_data, analysis_result)
+        } else {
+            return Ok(None);
+        };
+
+        let position_params = params.text_document_position_params;
+
+        let file_path = if let Some(file_path) = position_params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+        {
+            FilePath(file_path)
+        } else {
+            return Ok(None);
+        };
+
+        let file_hover_types =
+            if let Some(file_hover_types) = analysis_result.hover_types.get(&file_path) {
+                file_hover_types
+            } else {
+                return Ok(None);
+            };
+
+        let contents = if let Some(contents) = get_analyzed_contents(scan_data, &file_path) {
+            contents
+        } else {
+            return Ok(None);
+        };
+
+        let offset = if let Some(offset) = get_offset(&contents, position_params.position) {
+            offset as u32
+        } else {
+            return Ok(None);
+        };
+
+        // pick the narrowest expression that contains the cursor
+        let hover_type = file_hover_types
+            .iter()
+            .filter(|((start_offset, end_offset), _)| {
+                *start_offset <= offset && offset < *end_offset
+            })
+            .min_by_key(|((start_offset, end_offset), _)| end_offset - start_offset);
+
+        Ok(
+            hover_type.map(|((start_offset, end_offset), hover_type)| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!("```hack\n{}\n```", hover_type),
+                }),
+                range: Some(Range {
+                    start: get_position(&contents, *start_offset as usize),
+                    end: get_position(&contents, *end_offset as usize),
+                }),
+            }),
+        )
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        let symbol = if let Some((symbol, _)) =
+            get_symbol_at_position(scan_data, &params.text_document_position)
+        {
+            symbol
+        } else {
+            return Ok(None);
+        };
+
+        // resolved_names also contains local variables and unqualified member names,
+        // so only top-level symbols can be looked up reliably
+        let declaration_pos =
+            if let Some(declaration_pos) = get_declaration_pos(&scan_data.codebase, &symbol) {
+                declaration_pos
+            } else {
+                return Ok(None);
+            };
+
+        let mut locations = vec![];
+
+        for (reference_file_path, mut offsets) in get_reference_offsets(scan_data, symbol) {
+            if !params.context.include_declaration
+                && *reference_file_path == declaration_pos.file_path
+            {
+                // the symbol's name is the first reference inside its own definition
+                if let Some(index) = offsets.iter().position(|offset| {
+                    *offset >= declaration_pos.start_offset && *offset <= declaration_pos.end_offset
+                }) {
+                    offsets.remove(index);
+                }
+            }
+
+            let reference_path = scan_data.interner.lookup(&reference_file_path.0);
+
+            let (uri, reference_contents) = match (
+                Url::from_file_path(reference_path),
+                get_analyzed_contents(scan_data, reference_file_path),
+            ) {
+                (Ok(uri), Some(reference_contents)) => (uri, reference_contents),
+                _ => continue,
+            };
+
+            for offset in offsets {
+                let offset = offset as usize;
+
+                locations.push(Location {
+                    uri: uri.clone(),
+                    range: Range {
+                        start: get_position(&reference_contents, offset),
+                        end: get_position(
+                            &reference_contents,
+                            get_identifier_end(&reference_contents, offset),
+                        ),
+                    },
+                });
+            }
+        }
+
+        Ok(Some(locations))
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        let (symbol, range) =
+            if let Some(symbol_at_position) = get_symbol_at_position(scan_data, &params) {
+                symbol_at_position
+            } else {
+                return Err(JsonRpcError::invalid_params(
+                    "Only symbols that Hakana can resolve statically can be renamed",
+                ));
+            };
+
+        if get_declaration_pos(&scan_data.codebase, &symbol).is_none() {
+            return Err(JsonRpcError::invalid_params(
+                "Only top-level functions, classes, constants and type aliases can be renamed",
+            ));
+        }
+
+        Ok(Some(PrepareRenameResponse::Range(range)))
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        if !is_valid_identifier(&params.new_name) {
+            return Err(JsonRpcError::invalid_params(format!(
+                "{} is not a valid name",
+                params.new_name
+            )));
+        }
+
+        let symbol = if let Some((symbol, _)) =
+            get_symbol_at_position(scan_data, &params.text_document_position)
+        {
+            symbol
+        } else {
+            return Err(JsonRpcError::invalid_params(
+                "Only symbols that Hakana can resolve statically can be renamed",
+            ));
+        };
+
+        if get_declaration_pos(&scan_data.codebase, &symbol).is_none() {
+            return Err(JsonRpcError::invalid_params(
+                "Only top-level functions, classes, constants and type aliases can be renamed",
+            ));
+        }
+
+        let symbol_name = scan_data.interner.lookup(&symbol);
+        let short_name = symbol_name
+            .rsplit_once('\\')
+            .map_or(symbol_name, |(_, short_name)| short_name);
+
+        let mut changes = HashMap::new();
+
+        for (reference_file_path, offsets) in get_reference_offsets(scan_data, symbol) {
+            let reference_path = scan_data.interner.lookup(&reference_file_path.0);
+
+            let (uri, reference_contents) = match (
+                Url::from_file_path(reference_path),
+                get_analyzed_contents(scan_data, reference_file_path),
+            ) {
+                (Ok(uri), Some(reference_contents)) => (uri, reference_contents),
+                _ => continue,
+            };
+
+            let mut edits = vec![];
+
+            for offset in offsets {
+                let offset = offset as usize;
+                let end = get_identifier_end(&reference_contents, offset);
+                let segment_start = reference_contents[offset..end]
+                    .rfind('\\')
+                    .map_or(offset, |pos| offset + pos + 1);
+
+                // references through a `use ... as` alias don't spell out the name
+                if !reference_contents[segment_start..end].eq_ignore_ascii_case(short_name) {
+                    continue;
+                }
+
+                edits.push(TextEdit {
+                    range: Range {
+                        start: get_position(&reference_contents, segment_start),
+                        end: get_position(&reference_contents, end),
+                    },
+                    new_text: params.new_name.clone(),
+                });
+            }
+
+            if !edits.is_empty() {
+                changes.insert(uri, edits);
+            }
+        }
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..WorkspaceEdit::default()
+        }))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+        let analysis_result_guard = self.previous_analysis_result.read().await;
+
+        let (scan_data, analysis_result) = if let (Some(scan_data), Some(analysis_result)) =
+            (scan_data_guard.as_ref(), analysis_result_guard.as_ref())
+        {
+            (scan_data, analysis_result)
+        } else {
+            return Ok(None);
+        };
+
+        let file_path = if let Some(file_path) = params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+        {
+            FilePath(file_path)
+        } else {
+            return Ok(None);
+        };
+
+        // diagnostics include definition issues, like unused functions, as well
+        let file_issues = analysis_result
+            .emitted_issues
+            .get(&file_path)
+            .into_iter()
+            .chain(analysis_result.emitted_definition_issues.get(&file_path))
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if file_issues.is_empty() {
+            return Ok(None);
+        }
+
+        let contents = if let Some(contents) = get_analyzed_contents(scan_data, &file_path) {
+            contents
+        } else {
+            return Ok(None);
+        };
+
+        let mut actions = vec![];
+
+        for diagnostic in params.context.diagnostics {
+            if diagnostic.source.as_deref() != Some("Hakana") {
+                continue;
+            }
+
+            // diagnostics are built from issue positions in do_analysis
+            let issue = file_issues.iter().find(|issue| {
+                diagnostic.code == Some(NumberOrString::String(issue.kind.to_string()))
+                    && diagnostic.range.start.line == issue.pos.start_line - 1
+                    && diagnostic.range.start.character == issue.pos.start_column as u32 - 1
+            });
+
+            let (issue, insertion_start) = if let Some(issue) = issue {
+                if let Some(insertion_start) = &issue.insertion_start {
+                    (issue, insertion_start)
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+
+            let insertion_position = get_position(&contents, insertion_start.offset as usize);
+
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!("Suppress {} with HAKANA_FIXME", issue.kind.to_string()),
+                kind: Some(CodeActionKind::QUICKFIX),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        params.text_document.uri.clone(),
+                        vec![TextEdit {
+                            range: Range {
+                                start: insertion_position,
+                                end: insertion_position,
+                            },
+                            new_text: issue.get_fixme_text(insertion_start),
+                        }],
+                    )])),
+                    ..WorkspaceEdit::default()
+                }),
+                diagnostics: Some(vec![diagnostic]),
+                ..CodeAction::default()
+            }));
+        }
+
+        Ok(Some(actions))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        let file_info = if let Some(file_info) = params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+            .and_then(|file_path| scan_data.codebase.files.get(&FilePath(file_path)))
+        {
+            file_info
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(DocumentSymbolResponse::Nested(
+            file_info
+                .ast_nodes
+                .iter()
+                .map(|node| {
+                    get_document_symbol(
+                        node,
+                        get_top_level_symbol_kind(&scan_data.codebase, node),
+                        &scan_data.interner,
+                    )
+                })
+                .collect(),
+        )))
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        let file_info = if let Some(file_info) = params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+            .and_then(|file_path| scan_data.codebase.files.get(&FilePath(file_path)))
+        {
+            file_info
+        } else {
+            return Ok(None);
+        };
+
+        let mut folding_ranges = vec![];
+
+        for node in &file_info.ast_nodes {
+            if node.is_constant
+                || matches!(
+                    scan_data.codebase.symbols.all.get(&node.name),
+                    Some(CodebaseSymbolKind::TypeDefinition)
+                )
+            {
+                continue;
+            }
+
+            add_folding_range(node, &mut folding_ranges);
+
+            for child in &node.children {
+                if child.is_function {
+                    add_folding_range(child, &mut folding_ranges);
+                }
+            }
+        }
+
+        Ok(Some(folding_ranges))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+        let analysis_result_guard = self.previous_analysis_result.read().await;
+
+        let (scan_data, analysis_result) = if let (Some(scan_data), Some(analysis_result)) =
+            (scan_data_guard.as_ref(), analysis_result_guard.as_ref())
+        {
+            (scan_data, analysis_result)
+        } else {
+            return Ok(None);
+        };
+
+        let position_params = params.text_document_position;
+
+        let file_path = if let Some(file_path) = position_params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+        {
+            FilePath(file_path)
+        } else {
+            return Ok(None);
+        };
+
+        let analyzed_contents = if let Some(contents) = get_analyzed_contents(scan_data, &file_path)
+        {
+            contents
+        } else {
+            return Ok(None);
+        };
+
+        let contents = self
+            .open_documents
+            .read()
+            .await
+            .get(&position_params.text_document.uri)
+            .cloned()
+            .unwrap_or_else(|| analyzed_contents.clone());
+
+        let offset = if let Some(offset) = get_offset(&contents, position_params.position) {
+            offset
+        } else {
+            return Ok(None);
+        };
+
+        // skip past any part of the member name that's already been typed
+        let member_start = get_identifier_start(&contents, offset) as usize;
+
+        let before = if let Some(before) = contents.get(..member_start) {
+            before
+        } else {
+            return Ok(None);
+        };
+
+        let (receiver_end, is_static) = if let Some(before) = before.strip_suffix("::") {
+            (before.len(), true)
+        } else if let Some(before) = before.strip_suffix("->") {
+            (before.strip_suffix('?').unwrap_or(before).len(), false)
+        } else {
+            return Ok(None);
+        };
+
+        // types and names are only known for the last analyzed version of the file, so
+        // bail if anything before the receiver has changed since then
+        if analyzed_contents.get(..receiver_end) != contents.get(..receiver_end) {
+            return Ok(None);
+        }
+
+        let classlike_name = if is_static {
+            let receiver_start = get_identifier_start(&contents, receiver_end);
+
+            scan_data
+                .resolved_names
+                .get(&file_path)
+                .and_then(|resolved_names| resolved_names.get(&receiver_start))
+                .copied()
+        } else {
+            analysis_result
+                .hover_types
+                .get(&file_path)
+                .and_then(|file_hover_types| {
+                    file_hover_types
+                        .iter()
+                        .filter(|((_, end_offset), _)| *end_offset as usize == receiver_end)
+                        .min_by_key(|((start_offset, end_offset), _)| end_offset - start_offset)
+                })
+                .and_then(|(_, hover_type)| {
+                    get_receiver_class_name(hover_type, &scan_data.interner)
+                })
+        };
+
+        let classlike_info = if let Some(classlike_info) =
+            classlike_name.and_then(|name| scan_data.codebase.classlike_infos.get(&name))
+        {
+            classlike_info
+        } else {
+            return Ok(Some(CompletionResponse::Array(vec![])));
+        };
+
+        Ok(Some(CompletionResponse::Array(
+            get_member_completion_items(
+                &scan_data.codebase,
+                &scan_data.interner,
+                classlike_info,
+                is_static,
+            ),
+        )))
+    }
+
+    #[allow(deprecated)]
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let scan_data_guard = self.previous_scan_data.read().await;
+
+        let scan_data = if let Some(scan_data) = scan_data_guard.as_ref() {
+            scan_data
+        } else {
+            return Ok(None);
+        };
+
+        let query = params.query.to_lowercase();
+
+        let mut matches = vec![];
+
+        for (file_path, file_info) in &scan_data.codebase.files {
+            for node in &file_info.ast_nodes {
+                let kind = get_top_level_symbol_kind(&scan_data.codebase, node);
+                let name = scan_data.interner.lookup(&node.name);
+
+                if let Some(score) = get_symbol_match_score(name, &query) {
+                    matches.push((score, name, kind, None, file_path, node));
+                }
+
+                for child in &node.children {
+                    let child_name = scan_data.interner.lookup(&child.name);
+
+                    if let Some(score) = get_symbol_match_score(child_name, &query) {
+                        matches.push((
+                            score,
+                            child_name,
+                            get_member_symbol_kind(child, kind),
+                            Some(name),
+                            file_path,
+                            child,
+                        ));
+                    }
+                }
+            }
+        }
+
+        matches.sort_by_key(|(score, name, ..)| (*score, name.len(), *name));
+
+        let limit = *self.workspace_symbol_limit.read().await;
+
+        let mut symbols = vec![];
+
+        for (_, name, kind, container_name, file_path, node) in matches {
+            if symbols.len() >= limit {
+                break;
+            }
+
+            // builtin stubs don't have a real location on disk
+            let uri = if let Ok(uri) = Url::from_file_path(scan_data.interner.lookup(&file_path.0))
+            {
+                uri
+            } else {
+                continue;
+            };
+
+            symbols.push(SymbolInformation {
+                name: name.to_string(),
+                kind,
+                tags: None,
+                deprecated: None,
+                location: Location {
+                    uri,
+                    range: get_node_range(node),
+                },
+                container_name: container_name.map(|name| name.to_string()),
+            });
+        }
+
+        Ok(Some(symbols))
+    }
+
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        if params.command != REANALYZE_COMMAND {
+            return Err(JsonRpcError::invalid_params(format!(
+                "Unknown command {}",
+                params.command
+            )));
+        }
+
+        self.client
+            .log_message(MessageType::INFO, "full re-analysis requested")
+            .await;
+
+        self.reanalyze().await;
+
         Ok(None)
     }
 
+    // Settings can be sent either at the top level or under a "hakana" section:
+    //   allowedIssues: string[] — only report these issue kinds, or every kind if empty
+    //   findUnusedDefinitions: boolean — whether to report unused functions, classes etc.
+    // Any setting that's left out falls back to its value in hakana.json.
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let settings = params.settings.get("hakana").unwrap_or(&params.settings);
+
+        let allowed_issues = match settings
+            .get("allowedIssues")
+            .and_then(|issue_names| issue_names.as_array())
+        {
+            None => Ok(self.file_allowed_issues.clone()),
+            Some(issue_names) => {
+                let analysis_config = self.analysis_config.read().await;
+
+                issue_names
+                    .iter()
+                    .map(|issue_name| {
+                        issue_name
+                            .as_str()
+                            .ok_or_else(|| format!("Invalid issue name {}", issue_name))
+                            .and_then(|issue_name| {
+                                IssueKind::from_str_custom(
+                                    issue_name,
+                                    &analysis_config.all_custom_issues,
+                                )
+                            })
+                    })
+                    .collect::<std::result::Result<FxHashSet<_>, _>>()
+                    .map(|issue_kinds| Some(issue_kinds).filter(|kinds| !kinds.is_empty()))
+            }
+        };
+
+        let allowed_issues = match allowed_issues {
+            Ok(allowed_issues) => allowed_issues,
+            Err(error) => {
+                self.client
+                    .log_message(
+                        MessageType::ERROR,
+                        format!("Ignoring configuration change: {}", error),
+                    )
+                    .await;
+                return;
+            }
+        };
+
+        let find_unused_definitions = settings
+            .get("findUnusedDefinitions")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(self.file_find_unused_definitions);
+
+        {
+            let mut allowed_issues_guard = self.allowed_issues.write().await;
+            let mut find_unused_definitions_guard = self.find_unused_definitions.write().await;
+
+            if *allowed_issues_guard == allowed_issues
+                && *find_unused_definitions_guard == find_unused_definitions
+            {
+                return;
+            }
+
+            *allowed_issues_guard = allowed_issues;
+            *find_unused_definitions_guard = find_unused_definitions;
+        }
+
+        self.client
+            .log_message(MessageType::INFO, "configuration changed")
+            .await;
+
+        // issues are filtered as they're found, so previous results can't be reused
+        self.reanalyze().await;
+    }
+
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
 }
 
 impl Backend {
+    // drops all incremental state and analyzes the whole codebase again
+    async fn reanalyze(&self) {
+        // without previous scan data the next run scans every file, so pending changes are moot
+        *self.previous_scan_data.write().await = None;
+        *self.previous_analysis_result.write().await = None;
+        *self.file_changes.write().await = None;
+
+        self.do_analysis().await;
+        self.emit_issues().await;
+    }
+
+    async fn has_hover_types(&self, uri: &Url) -> bool {
+        let scan_data_guard = self.previous_scan_data.read().await;
+        let analysis_result_guard = self.previous_analysis_result.read().await;
+
+        let (scan_data, analysis_result) = if let (Some(scan_data), Some(analysis_result)) =
+            (scan_data_guard.as_ref(), analysis_result_guard.as_ref())
+        {
+            (scan_data, analysis_result)
+        } else {
+            return false;
+        };
+
+        uri.to_file_path()
+            .ok()
+            .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+            .map_or(false, |file_path| {
+                analysis_result
+                    .hover_types
+                    .contains_key(&FilePath(file_path))
+            })
+    }
+
+    // re-analyzes a file whose contents in the editor may now differ from the last analysis
+    async fn analyze_open_document(&self, uri: &Url) {
+        if self.queue_open_document(uri).await {
+            self.do_analysis().await;
+            self.emit_issues().await;
+        }
+    }
+
+    // marks an open file as modified for the next analysis, returning false if there's
+    // nothing to analyze
+    async fn queue_open_document(&self, uri: &Url) -> bool {
+        let file_path = uri.path().to_string();
+
+        if !file_path.ends_with(".php") && !file_path.ends_with(".hack") {
+            return false;
+        }
+
+        // the initial analysis covers every file, and incremental runs build on it
+        if self.previous_scan_data.read().await.is_none() {
+            return false;
+        }
+
+        self.file_changes
+            .write()
+            .await
+            .get_or_insert_with(FxHashMap::default)
+            .insert(file_path, FileStatus::Modified(0, 0));
+
+        true
+    }
+
+    // applies the editor's settings to the config, and returns the config for this run
+    async fn get_analysis_config(&self) -> Arc<Config> {
+        let mut analysis_config_guard = self.analysis_config.write().await;
+
+        let allowed_issues = self.allowed_issues.read().await.clone();
+        let find_unused_definitions = *self.find_unused_definitions.read().await;
+
+        if analysis_config_guard.allowed_issues != allowed_issues
+            || analysis_config_guard.find_unused_definitions != find_unused_definitions
+        {
+            // the previous run has finished by now, so this only fails if something else
+            // still holds the config, in which case the settings wait for the run after
+            if let Some(analysis_config) = Arc::get_mut(&mut analysis_config_guard) {
+                analysis_config.allowed_issues = allowed_issues;
+                analysis_config.find_unused_definitions = find_unused_definitions;
+            }
+        }
+
+        analysis_config_guard.clone()
+    }
+
     async fn do_analysis(&self) {
         let mut previous_scan_data_guard = self.previous_scan_data.write().await;
         let mut previous_analysis_result_guard = self.previous_analysis_result.write().await;
         let mut all_diagnostics_guard = self.all_diagnostics.write().await;
 
+        let analysis_config = self.get_analysis_config().await;
+
         let successful_scan_data = previous_scan_data_guard.take();
 
-        let analysis_result = previous_analysis_result_guard.take();
+        let mut analysis_result = previous_analysis_result_guard.take();
+
+        // only changed symbols get re-analyzed, so hover types for everything else
+        // have to be carried over from the previous run
+        let previous_hover_types = analysis_result
+            .as_mut()
+            .map(|analysis_result| std::mem::take(&mut analysis_result.hover_types))
+            .unwrap_or_default();
 
         let mut file_changes_guard = self.file_changes.write().await;
 
         let file_changes = file_changes_guard.take();
 
+        let changed_files = file_changes
+            .as_ref()
+            .map(|file_changes| file_changes.keys().cloned().collect::<FxHashSet<_>>())
+            .unwrap_or_default();
+
+        let unsaved_file_contents = self
+            .open_documents
+            .read()
+            .await
+            .iter()
+            .filter_map(|(uri, contents)| {
+                let path = uri.to_file_path().ok()?;
+
+                Some((path.to_str()?.to_string(), contents.clone()))
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        let open_file_paths = unsaved_file_contents
+            .keys()
+            .cloned()
+            .collect::<FxHashSet<_>>();
+
         self.client
             .log_message(
                 MessageType::INFO,
@@ -214,7 +1155,7 @@ impl Backend {
             Vec::new(),
             None,
             None,
-            self.analysis_config.clone(),
+            analysis_config.clone(),
             8,
             &self.client,
             "",
@@ -222,22 +1163,49 @@ impl Backend {
             successful_scan_data,
             analysis_result,
             file_changes,
+            unsaved_file_contents,
         )
         .await;
 
         *file_changes_guard = None;
 
         match result {
-            Ok((analysis_result, successful_scan_data)) => {
+            Ok((mut analysis_result, successful_scan_data)) => {
+                for (file_path, file_hover_types) in previous_hover_types {
+                    if changed_files.contains(successful_scan_data.interner.lookup(&file_path.0)) {
+                        continue;
+                    }
+
+                    let new_file_hover_types =
+                        analysis_result.hover_types.entry(file_path).or_default();
+
+                    for (offsets, hover_type) in file_hover_types {
+                        new_file_hover_types.entry(offsets).or_insert(hover_type);
+                    }
+                }
+
+                // every analyzed expression has a hover type, so keeping them for the whole
+                // codebase would use far more memory than the open documents need
+                analysis_result.hover_types.retain(|file_path, _| {
+                    open_file_paths.contains(successful_scan_data.interner.lookup(&file_path.0))
+                });
+
                 let mut all_diagnostics = FxHashMap::default();
 
                 for (file, emitted_issues) in analysis_result.get_all_issues(
                     &successful_scan_data.interner,
-                    &self.analysis_config.root_dir,
+                    &analysis_config.root_dir,
                     false,
                 ) {
                     let mut diagnostics = vec![];
                     for emitted_issue in emitted_issues {
+                        let severity = match analysis_config.get_issue_severity(&emitted_issue.kind)
+                        {
+                            IssueSeverity::Error => DiagnosticSeverity::ERROR,
+                            IssueSeverity::Warning => DiagnosticSeverity::WARNING,
+                            IssueSeverity::Info => DiagnosticSeverity::INFORMATION,
+                        };
+
                         diagnostics.push(Diagnostic::new(
                             Range {
                                 start: Position {
@@ -249,7 +1217,7 @@ impl Backend {
                                     character: emitted_issue.pos.end_column as u32 - 1,
                                 },
                             },
-                            Some(DiagnosticSeverity::ERROR),
+                            Some(severity),
                             Some(NumberOrString::String(emitted_issue.kind.to_string())),
                             Some("Hakana".to_string()),
                             emitted_issue.description.clone(),
@@ -322,6 +1290,446 @@ impl Backend {
     }
 }
 
+fn get_top_level_symbol_kind(codebase: &CodebaseInfo, node: &DefSignatureNode) -> SymbolKind {
+    if node.is_function {
+        return SymbolKind::FUNCTION;
+    }
+
+    if node.is_constant {
+        return SymbolKind::CONSTANT;
+    }
+
+    match codebase.symbols.all.get(&node.name) {
+        Some(CodebaseSymbolKind::Enum | CodebaseSymbolKind::EnumClass) => SymbolKind::ENUM,
+        Some(CodebaseSymbolKind::Interface) => SymbolKind::INTERFACE,
+        // LSP has no kind for type aliases, and editors show STRUCT as a type
+        Some(CodebaseSymbolKind::TypeDefinition) => SymbolKind::STRUCT,
+        _ => SymbolKind::CLASS,
+    }
+}
+
+#[allow(deprecated)]
+fn get_document_symbol(
+    node: &DefSignatureNode,
+    kind: SymbolKind,
+    interner: &Interner,
+) -> DocumentSymbol {
+    let range = get_node_range(node);
+
+    let children = node
+        .children
+        .iter()
+        .map(|child| get_document_symbol(child, get_member_symbol_kind(child, kind), interner))
+        .collect::<Vec<_>>();
+
+    DocumentSymbol {
+        name: interner.lookup(&node.name).to_string(),
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range: range,
+        children: if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        },
+    }
+}
+
+fn get_member_symbol_kind(node: &DefSignatureNode, parent_kind: SymbolKind) -> SymbolKind {
+    if node.is_function {
+        SymbolKind::METHOD
+    } else if node.is_constant {
+        if parent_kind == SymbolKind::ENUM {
+            SymbolKind::ENUM_MEMBER
+        } else {
+            SymbolKind::CONSTANT
+        }
+    } else {
+        SymbolKind::PROPERTY
+    }
+}
+
+// Only receivers whose type is a single class are supported, e.g. Foo or ?Foo<int>
+fn get_receiver_class_name(hover_type: &str, interner: &Interner) -> Option<StrId> {
+    let hover_type = hover_type.strip_prefix('?').unwrap_or(hover_type);
+    let class_name = hover_type.split('<').next()?;
+
+    if !class_name.bytes().all(is_identifier_char) {
+        return None;
+    }
+
+    interner.get(class_name)
+}
+
+fn get_member_completion_items(
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    classlike_info: &ClassLikeInfo,
+    is_static: bool,
+) -> Vec<CompletionItem> {
+    let mut items = vec![];
+
+    for (method_name, declaring_class) in &classlike_info.declaring_method_ids {
+        let Some(functionlike_info) = codebase
+            .functionlike_infos
+            .get(&(*declaring_class, *method_name))
+        else {
+            continue;
+        };
+
+        let Some(method_info) = &functionlike_info.method_info else {
+            continue;
+        };
+
+        if method_info.is_static != is_static
+            || (matches!(method_info.visibility, MemberVisibility::Private)
+                && *declaring_class != classlike_info.name)
+        {
+            continue;
+        }
+
+        let method_name = interner.lookup(method_name);
+
+        items.push(CompletionItem {
+            label: method_name.to_string(),
+            kind: Some(CompletionItemKind::METHOD),
+            detail: Some(get_method_signature(
+                method_name,
+                functionlike_info,
+                interner,
+            )),
+            ..CompletionItem::default()
+        });
+    }
+
+    for (property_name, declaring_class) in &classlike_info.declaring_property_ids {
+        let Some(property_info) =
+            codebase
+                .classlike_infos
+                .get(declaring_class)
+                .and_then(|declaring_classlike_info| {
+                    declaring_classlike_info.properties.get(property_name)
+                })
+        else {
+            continue;
+        };
+
+        if property_info.is_static != is_static
+            || (matches!(property_info.visibility, MemberVisibility::Private)
+                && *declaring_class != classlike_info.name)
+        {
+            continue;
+        }
+
+        items.push(CompletionItem {
+            label: if is_static {
+                format!("${}", interner.lookup(property_name))
+            } else {
+                interner.lookup(property_name).to_string()
+            },
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: Some(property_info.type_.get_id(Some(interner))),
+            ..CompletionItem::default()
+        });
+    }
+
+    if is_static {
+        for (constant_name, constant_info) in &classlike_info.constants {
+            items.push(CompletionItem {
+                label: interner.lookup(constant_name).to_string(),
+                kind: Some(if matches!(classlike_info.kind, CodebaseSymbolKind::Enum) {
+                    CompletionItemKind::ENUM_MEMBER
+                } else {
+                    CompletionItemKind::CONSTANT
+                }),
+                detail: constant_info
+                    .provided_type
+                    .as_ref()
+                    .map(|constant_type| constant_type.get_id(Some(interner))),
+                ..CompletionItem::default()
+            });
+        }
+    }
+
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+
+    items
+}
+
+fn get_method_signature(
+    method_name: &str,
+    functionlike_info: &FunctionLikeInfo,
+    interner: &Interner,
+) -> String {
+    let params = functionlike_info
+        .params
+        .iter()
+        .map(|param| {
+            format!(
+                "{}{}{}",
+                param
+                    .signature_type
+                    .as_ref()
+                    .map(|param_type| format!("{} ", param_type.get_id(Some(interner))))
+                    .unwrap_or_default(),
+                if param.is_variadic { "..." } else { "" },
+                interner.lookup(&param.name.0)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "function {}({}): {}",
+        method_name,
+        params.join(", "),
+        functionlike_info
+            .return_type
+            .as_ref()
+            .map(|return_type| return_type.get_id(Some(interner)))
+            .unwrap_or_else(|| "mixed".to_string())
+    )
+}
+
+// single-line definitions have nothing to fold
+fn add_folding_range(node: &DefSignatureNode, folding_ranges: &mut Vec<FoldingRange>) {
+    if node.end_line > node.start_line {
+        folding_ranges.push(FoldingRange {
+            start_line: node.start_line.saturating_sub(1),
+            start_character: None,
+            end_line: node.end_line.saturating_sub(1),
+            end_character: None,
+            kind: None,
+            collapsed_text: None,
+        });
+    }
+}
+
+fn get_node_range(node: &DefSignatureNode) -> Range {
+    Range {
+        start: Position {
+            line: node.start_line.saturating_sub(1),
+            character: (node.start_colum as u32).saturating_sub(1),
+        },
+        end: Position {
+            line: node.end_line.saturating_sub(1),
+            character: (node.end_column as u32).saturating_sub(1),
+        },
+    }
+}
+
+// Lower scores rank first: exact matches, then prefixes, then substrings, then
+// camel-case initials (so "UPS" finds "UserProfileService"), then any subsequence.
+// Namespaced names are matched on both their full and their unqualified forms.
+fn get_symbol_match_score(name: &str, query: &str) -> Option<u8> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut best_score = None;
+
+    let candidates = if let Some((_, short_name)) = name.rsplit_once('\\') {
+        vec![name, short_name]
+    } else {
+        vec![name]
+    };
+
+    for candidate in candidates {
+        let lowercase_candidate = candidate.to_lowercase();
+
+        let score = if lowercase_candidate == query {
+            0
+        } else if lowercase_candidate.starts_with(query) {
+            1
+        } else if lowercase_candidate.contains(query) {
+            2
+        } else if is_subsequence(query, &get_word_initials(candidate)) {
+            3
+        } else if is_subsequence(query, &lowercase_candidate) {
+            4
+        } else {
+            continue;
+        };
+
+        best_score = Some(best_score.map_or(score, |best: u8| best.min(score)));
+    }
+
+    best_score
+}
+
+fn get_word_initials(name: &str) -> String {
+    let mut initials = String::new();
+    let mut previous = None;
+
+    for c in name.chars() {
+        let is_word_start = match previous {
+            None | Some('_') | Some('\\') => c.is_alphanumeric(),
+            Some(previous) => c.is_uppercase() && !previous.is_uppercase(),
+        };
+
+        if is_word_start {
+            initials.extend(c.to_lowercase());
+        }
+
+        previous = Some(c);
+    }
+
+    initials
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack_chars = haystack.chars();
+
+    needle.chars().all(|c| haystack_chars.any(|h| h == c))
+}
+
+// Returns the contents a file had when it was last analyzed, which is what the offsets
+// in the scan data and analysis result refer to
+fn get_analyzed_contents(scan_data: &SuccessfulScanData, file_path: &FilePath) -> Option<String> {
+    let str_path = scan_data.interner.lookup(&file_path.0);
+
+    if let Some(contents) = scan_data.file_system.unsaved_file_contents.get(str_path) {
+        Some(contents.clone())
+    } else {
+        fs::read_to_string(str_path).ok()
+    }
+}
+
+fn get_symbol_at_position(
+    scan_data: &SuccessfulScanData,
+    position_params: &TextDocumentPositionParams,
+) -> Option<(StrId, Range)> {
+    let file_path = position_params
+        .text_document
+        .uri
+        .to_file_path()
+        .ok()
+        .and_then(|path| path.to_str().and_then(|path| scan_data.interner.get(path)))
+        .map(FilePath)?;
+
+    let contents = get_analyzed_contents(scan_data, &file_path)?;
+
+    let start = get_identifier_start(&contents, get_offset(&contents, position_params.position)?);
+
+    let symbol = scan_data.resolved_names.get(&file_path)?.get(&start)?;
+
+    let start = start as usize;
+    let end = get_identifier_end(&contents, start);
+    let segment_start = contents[start..end]
+        .rfind('\\')
+        .map_or(start, |pos| start + pos + 1);
+
+    Some((
+        *symbol,
+        Range {
+            start: get_position(&contents, segment_start),
+            end: get_position(&contents, end),
+        },
+    ))
+}
+
+// Returns the sorted offsets of every resolved reference to a symbol, grouped by file
+fn get_reference_offsets(
+    scan_data: &SuccessfulScanData,
+    symbol: StrId,
+) -> Vec<(&FilePath, Vec<u32>)> {
+    let mut references = vec![];
+
+    for (reference_file_path, file_resolved_names) in &scan_data.resolved_names {
+        let mut offsets = file_resolved_names
+            .iter()
+            .filter(|(_, name)| **name == symbol)
+            .map(|(offset, _)| *offset)
+            .collect::<Vec<_>>();
+
+        if offsets.is_empty() {
+            continue;
+        }
+
+        offsets.sort();
+
+        references.push((reference_file_path, offsets));
+    }
+
+    references
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn get_declaration_pos(codebase: &CodebaseInfo, symbol: &StrId) -> Option<HPos> {
+    if let Some(classlike_info) = codebase.classlike_infos.get(symbol) {
+        Some(classlike_info.def_location)
+    } else if let Some(functionlike_info) =
+        codebase.functionlike_infos.get(&(*symbol, StrId::EMPTY))
+    {
+        Some(functionlike_info.def_location)
+    } else if let Some(type_definition_info) = codebase.type_definitions.get(symbol) {
+        Some(type_definition_info.location)
+    } else {
+        codebase
+            .constant_infos
+            .get(symbol)
+            .map(|constant_info| constant_info.pos)
+    }
+}
+
+fn is_identifier_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'\\'
+}
+
+fn get_identifier_start(contents: &str, offset: usize) -> u32 {
+    let bytes = contents.as_bytes();
+    let mut start = offset.min(bytes.len());
+
+    while start > 0 && is_identifier_char(bytes[start - 1]) {
+        start -= 1;
+    }
+
+    start as u32
+}
+
+fn get_identifier_end(contents: &str, offset: usize) -> usize {
+    let bytes = contents.as_bytes();
+    let mut end = offset;
+
+    while end < bytes.len() && is_identifier_char(bytes[end]) {
+        end += 1;
+    }
+
+    end
+}
+
+// Hakana positions are byte-based, and diagnostics are emitted using byte columns too
+fn get_offset(contents: &str, position: Position) -> Option<usize> {
+    let mut line_start = 0;
+
+    for _ in 0..position.line {
+        line_start += contents[line_start..].find('\n')? + 1;
+    }
+
+    Some((line_start + position.character as usize).min(contents.len()))
+}
+
+fn get_position(contents: &str, offset: usize) -> Position {
+    let before = &contents.as_bytes()[..offset.min(contents.len())];
+    let line_start = before
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |pos| pos + 1);
+
+    Position {
+        line: before.iter().filter(|c| **c == b'\n').count() as u32,
+        character: (before.len() - line_start) as u32,
+    }
+}
+
 pub fn get_config(
     plugins: Vec<Box<dyn CustomHook>>,
     cwd: &String,
@@ -345,16 +1753,18 @@ pub fn get_config(
     config.find_unused_definitions = true;
     config.ignore_mixed_issues = true;
     config.ast_diff = true;
+    config.collect_hover_types = true;
 
     config.hooks = plugins;
 
-    let config_path_str = format!("{}/hakana.json", cwd);
-
-    let config_path = Path::new(&config_path_str);
+    let config_path = config::get_default_config_path(cwd);
 
     if config_path.exists() {
-        config.update_from_file(cwd, config_path, interner)?;
+        config.update_from_file(cwd, &config_path, interner)?;
     }
 
+    config.update_from_ignore_file()?;
+    config.update_from_overlay_files()?;
+
     Ok(config)
 }
@@ -15,6 +15,7 @@ fn main() {
         vec![],
         vec![],
         vec![],
+        vec![],
         header.as_str(),
         &TestRunner(Box::new(CoreHooksProvider {})),
     );
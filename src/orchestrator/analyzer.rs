@@ -1,5 +1,6 @@
 use crate::file::get_file_contents_hash;
-use crate::{get_aast_for_path, update_progressbar, SuccessfulScanData};
+use crate::{get_aast_for_path, is_ignored_path, update_progressbar, SuccessfulScanData};
+use hakana_aast_helper::get_aast_for_path_and_contents;
 use hakana_analyzer::config::Config;
 use hakana_analyzer::file_analyzer;
 use hakana_logger::Logger;
@@ -7,6 +8,7 @@ use hakana_code_info::analysis_result::AnalysisResult;
 use hakana_code_info::code_location::{FilePath, HPos};
 use hakana_code_info::codebase_info::CodebaseInfo;
 use hakana_code_info::file_info::ParserError;
+use hakana_code_info::functionlike_identifier::get_functionlike_id;
 use hakana_code_info::issue::{Issue, IssueKind};
 use hakana_code_info::symbol_references::SymbolReferences;
 use hakana_code_info::FileSource;
@@ -39,14 +41,29 @@ pub fn analyze_files(
         paths.retain(|str_path| str_path.matches(filter.as_str()).count() > 0);
     }
 
-    paths.retain(|str_path| config.allow_issues_in_file(str_path));
+    if let Some(analyze_file_allowlist) = &config.analyze_file_allowlist {
+        paths.retain(|str_path| analyze_file_allowlist.contains(str_path));
+    }
+
+    // only the file that defines the symbol needs to be analyzed
+    if let Some(analyze_symbol) = &config.analyze_symbol {
+        if let Some(functionlike_info) = get_functionlike_id(analyze_symbol, &scan_data.interner)
+            .and_then(|functionlike_id| scan_data.codebase.functionlike_infos.get(&functionlike_id))
+        {
+            let symbol_file_path = scan_data
+                .interner
+                .lookup(&functionlike_info.def_location.file_path.0);
 
-    if let Some(ignored_paths) = &ignored_paths {
-        for ignored_path in ignored_paths {
-            paths.retain(|str_path| str_path.matches(ignored_path.as_str()).count() == 0);
+            paths.retain(|str_path| str_path == symbol_file_path);
+        } else {
+            paths.clear();
         }
     }
 
+    paths.retain(|str_path| {
+        config.allow_issues_in_file(str_path) && !is_ignored_path(&config, ignored_paths, str_path)
+    });
+
     let total_file_count = paths.len() as u64;
 
     if (paths.len() / group_size) < 4 {
@@ -106,10 +123,11 @@ pub fn analyze_files(
                 let file_path = FilePath(interner.get(str_path).unwrap());
 
                 if let Some(resolved_names) = resolved_names.get(&file_path) {
-                    file_analysis_time += analyze_file(
+                    let elapsed = analyze_file(
                         file_path,
                         str_path,
                         scan_data.file_system.file_hashes_and_times.get(&file_path),
+                        scan_data.file_system.unsaved_file_contents.get(str_path),
                         codebase,
                         interner,
                         &analysis_config,
@@ -117,6 +135,11 @@ pub fn analyze_files(
                         resolved_names,
                         &logger,
                     );
+
+                    file_analysis_time += elapsed;
+                    new_analysis_result
+                        .file_analysis_times
+                        .insert(file_path, elapsed);
                 }
 
                 let mut tally = files_processed.lock().unwrap();
@@ -153,6 +176,7 @@ fn analyze_file(
     file_path: FilePath,
     str_path: &String,
     last_hash_and_time: Option<&(u64, u64)>,
+    unsaved_contents: Option<&String>,
     codebase: &CodebaseInfo,
     interner: &Interner,
     config: &Arc<Config>,
@@ -162,7 +186,8 @@ fn analyze_file(
 ) -> Duration {
     logger.log_debug_sync(&format!("Analyzing {}", &str_path));
 
-    if let Ok(metadata) = fs::metadata(str_path) {
+    // what's on disk doesn't matter for files that are being edited
+    if let (None, Ok(metadata)) = (unsaved_contents, fs::metadata(str_path)) {
         let updated_time = metadata
             .modified()
             .unwrap()
@@ -199,7 +224,13 @@ fn analyze_file(
         }
     }
 
-    let aast = match get_aast_for_path(file_path, str_path) {
+    let aast = if let Some(unsaved_contents) = unsaved_contents {
+        get_aast_for_path_and_contents(file_path, str_path, unsaved_contents.clone())
+    } else {
+        get_aast_for_path(file_path, str_path)
+    };
+
+    let aast = match aast {
         Ok(aast) => (aast.0, aast.1),
         Err(err) => {
             analysis_result.has_invalid_hack_files = true;
@@ -6,7 +6,9 @@ use hakana_code_info::symbol_references::SymbolReferences;
 use hakana_str::Interner;
 use hakana_str::StrId;
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::{self, Write};
 use std::path::Path;
 
 use crate::file::VirtualFileSystem;
@@ -111,3 +113,51 @@ pub(crate) fn get_file_manifest(cache_dir: &String) -> Option<VirtualFileSystem>
 
     None
 }
+
+// The result of scanning just the embedded HHI and HSL stubs, which only changes when
+// hakana itself does
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CachedStubs {
+    pub build_checksum: String,
+    pub interner: Interner,
+    pub codebase: CodebaseInfo,
+    pub resolved_names: FxHashMap<FilePath, FxHashMap<u32, StrId>>,
+}
+
+pub(crate) fn get_stubs_path(stub_cache_dir: &str) -> String {
+    format!("{}/stubs", stub_cache_dir)
+}
+
+pub(crate) fn load_cached_stubs(
+    stubs_path: &String,
+    build_checksum: &str,
+    starter_interner: &Interner,
+    logger: &Logger,
+) -> Option<CachedStubs> {
+    if Path::new(stubs_path).exists() {
+        logger.log_sync("Deserializing stored stubs cache");
+        let serialized =
+            fs::read(stubs_path).unwrap_or_else(|_| panic!("Could not read file {}", &stubs_path));
+        if let Ok(d) = bincode::deserialize::<CachedStubs>(&serialized) {
+            // the ids in the cached codebase were allocated after the starter interner's own,
+            // so they're only valid if it hasn't changed since
+            if d.build_checksum == build_checksum && d.interner.extends(starter_interner) {
+                return Some(d);
+            }
+        }
+    }
+
+    None
+}
+
+pub(crate) fn write_cached_stubs(
+    stubs_path: &String,
+    cached_stubs: &CachedStubs,
+) -> io::Result<()> {
+    if let Some(stub_cache_dir) = Path::new(stubs_path).parent() {
+        fs::create_dir_all(stub_cache_dir)?;
+    }
+
+    let mut stubs_file = fs::File::create(stubs_path)?;
+    stubs_file.write_all(&bincode::serialize(cached_stubs).unwrap())
+}
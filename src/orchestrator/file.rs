@@ -19,6 +19,9 @@ pub enum FileStatus {
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 pub struct VirtualFileSystem {
     pub file_hashes_and_times: FxHashMap<FilePath, (u64, u64)>,
+    // contents of files open in an editor, which take precedence over what's on disk
+    #[serde(skip)]
+    pub unsaved_file_contents: FxHashMap<String, String>,
 }
 
 impl VirtualFileSystem {
@@ -255,10 +258,12 @@ impl VirtualFileSystem {
                             .file_hashes_and_times
                             .get(&interned_file_path)
                         {
-                            if old_update_time == &updated_time {
+                            if old_update_time == &updated_time
+                                && !self.unsaved_file_contents.contains_key(&str_path)
+                            {
                                 *old_contents_hash
                             } else if calculate_file_hashes {
-                                get_file_contents_hash(&str_path).unwrap_or(0)
+                                self.get_contents_hash(&str_path)
                             } else {
                                 0
                             }
@@ -266,7 +271,7 @@ impl VirtualFileSystem {
                             0
                         }
                     } else if calculate_file_hashes {
-                        get_file_contents_hash(&str_path).unwrap_or(0)
+                        self.get_contents_hash(&str_path)
                     } else {
                         0
                     };
@@ -289,11 +294,20 @@ impl VirtualFileSystem {
             }
         }
     }
+
+    fn get_contents_hash(&self, str_path: &String) -> u64 {
+        if let Some(contents) = self.unsaved_file_contents.get(str_path) {
+            xxhash_rust::xxh3::xxh3_64(contents.as_bytes())
+        } else {
+            get_file_contents_hash(str_path).unwrap_or(0)
+        }
+    }
 }
 
+// hashes the raw bytes, so files that aren't valid UTF-8 still have their changes noticed
 pub fn get_file_contents_hash(file_path: &String) -> Result<u64, std::io::Error> {
-    match fs::read_to_string(file_path) {
-        Ok(file_contents) => Ok(xxhash_rust::xxh3::xxh3_64(file_contents.as_bytes())),
+    match fs::read(file_path) {
+        Ok(file_contents) => Ok(xxhash_rust::xxh3::xxh3_64(&file_contents)),
         Err(error) => Err(error),
     }
 }
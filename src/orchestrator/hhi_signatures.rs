@@ -0,0 +1,160 @@
+use hakana_analyzer::config::Config;
+use hakana_code_info::analysis_result::AnalysisResult;
+use hakana_code_info::code_location::HPos;
+use hakana_code_info::codebase_info::CodebaseInfo;
+use hakana_code_info::issue::{Issue, IssueKind};
+use hakana_code_info::t_atomic::TAtomic;
+use hakana_code_info::t_union::{TUnion, TypeNode};
+use hakana_str::{Interner, StrId};
+
+// .hhi stubs aren't user-defined, so their signatures are never checked when analyzing
+// function bodies. For stubs matched by analyze_hhi_signatures this reports any type in a
+// signature that still refers to an unknown classlike once the codebase has been populated.
+pub(crate) fn find_unresolved_hhi_signature_types(
+    analysis_result: &mut AnalysisResult,
+    config: &Config,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) {
+    let mut issues = vec![];
+
+    for (functionlike_name, functionlike_info) in &codebase.functionlike_infos {
+        if functionlike_info.user_defined
+            || functionlike_info.is_closure
+            || !is_hhi_signature_file(&functionlike_info.def_location, config, interner)
+        {
+            continue;
+        }
+
+        for param in &functionlike_info.params {
+            if let Some(signature_type) = &param.signature_type {
+                add_unresolved_type_issues(
+                    signature_type,
+                    param.signature_type_location.unwrap_or(param.location),
+                    *functionlike_name,
+                    interner,
+                    &mut issues,
+                );
+            }
+        }
+
+        if let Some(return_type) = &functionlike_info.return_type {
+            add_unresolved_type_issues(
+                return_type,
+                functionlike_info
+                    .return_type_location
+                    .unwrap_or(functionlike_info.def_location),
+                *functionlike_name,
+                interner,
+                &mut issues,
+            );
+        }
+    }
+
+    for (classlike_name, classlike_info) in &codebase.classlike_infos {
+        if classlike_info.user_defined
+            || !is_hhi_signature_file(&classlike_info.name_location, config, interner)
+        {
+            continue;
+        }
+
+        for (property_name, property_info) in &classlike_info.properties {
+            if let Some(type_pos) = property_info.type_pos {
+                add_unresolved_type_issues(
+                    &property_info.type_,
+                    type_pos,
+                    (*classlike_name, *property_name),
+                    interner,
+                    &mut issues,
+                );
+            }
+        }
+    }
+
+    for (type_name, type_definition_info) in &codebase.type_definitions {
+        if type_definition_info.user_defined
+            || !is_hhi_signature_file(&type_definition_info.location, config, interner)
+        {
+            continue;
+        }
+
+        add_unresolved_type_issues(
+            &type_definition_info.actual_type,
+            type_definition_info.location,
+            (*type_name, StrId::EMPTY),
+            interner,
+            &mut issues,
+        );
+
+        if let Some(as_type) = &type_definition_info.as_type {
+            add_unresolved_type_issues(
+                as_type,
+                type_definition_info.location,
+                (*type_name, StrId::EMPTY),
+                interner,
+                &mut issues,
+            );
+        }
+    }
+
+    for issue in issues {
+        let file_path = interner.lookup(&issue.pos.file_path.0);
+
+        if !config.can_add_issue(&issue, file_path)
+            || !config.allow_issue_kind_in_file(&issue.kind, file_path)
+        {
+            continue;
+        }
+
+        *analysis_result
+            .issue_counts
+            .entry(issue.kind.clone())
+            .or_insert(0) += 1;
+        analysis_result
+            .emitted_definition_issues
+            .entry(issue.pos.file_path)
+            .or_default()
+            .push(issue);
+    }
+}
+
+fn is_hhi_signature_file(pos: &HPos, config: &Config, interner: &Interner) -> bool {
+    let file_path = interner.lookup(&pos.file_path.0);
+
+    file_path.ends_with(".hhi") && config.analyze_hhi_signatures_in_file(file_path)
+}
+
+fn add_unresolved_type_issues(
+    signature_type: &TUnion,
+    pos: HPos,
+    symbol: (StrId, StrId),
+    interner: &Interner,
+    issues: &mut Vec<Issue>,
+) {
+    let mut unresolved_names = vec![];
+
+    for type_node in signature_type.get_all_child_nodes() {
+        if let TypeNode::Atomic(TAtomic::TReference { name, .. }) = type_node {
+            if !unresolved_names.contains(name) {
+                unresolved_names.push(*name);
+            }
+        }
+    }
+
+    for name in unresolved_names {
+        let mut issue = Issue::new(
+            IssueKind::NonExistentClasslike,
+            format!(
+                "Class, enum or interface {} cannot be found",
+                interner.lookup(&name)
+            ),
+            pos,
+            &None,
+        );
+
+        // the signature's own symbol, rather than the file it's in
+        issue.symbol = symbol;
+
+        issues.push(issue);
+    }
+}
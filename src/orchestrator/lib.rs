@@ -15,6 +15,7 @@ use hakana_code_info::issue::{Issue, IssueKind};
 use hakana_code_info::symbol_references::SymbolReferences;
 use hakana_logger::Logger;
 use hakana_str::{Interner, StrId};
+use hhi_signatures::find_unresolved_hhi_signature_types;
 use indicatif::ProgressBar;
 use oxidized::aast;
 use oxidized::scoured_comments::ScouredComments;
@@ -22,8 +23,11 @@ use populator::populate_codebase;
 use rust_embed::RustEmbed;
 use rustc_hash::{FxHashMap, FxHashSet};
 use scanner::{scan_files, ScanFilesResult};
+use serde::Serialize;
 use std::fs;
+use std::hash::Hash;
 use std::io::{self, Write};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tower_lsp::lsp_types::MessageType;
@@ -35,6 +39,7 @@ mod ast_differ;
 mod cache;
 mod diff;
 pub mod file;
+mod hhi_signatures;
 pub mod scanner;
 mod unused_symbols;
 pub mod wasm;
@@ -86,9 +91,10 @@ pub async fn scan_and_analyze_async(
     previous_scan_data: Option<SuccessfulScanData>,
     previous_analysis_result: Option<AnalysisResult>,
     language_server_changes: Option<FxHashMap<String, FileStatus>>,
+    unsaved_file_contents: FxHashMap<String, String>,
 ) -> io::Result<(AnalysisResult, SuccessfulScanData)> {
     let mut all_scanned_dirs = stubs_dirs.clone();
-    all_scanned_dirs.push(config.root_dir.clone());
+    all_scanned_dirs.extend(config.root_dirs.iter().cloned());
 
     lsp_client
         .log_message(MessageType::INFO, "Scanning files")
@@ -114,6 +120,7 @@ pub async fn scan_and_analyze_async(
         &interner,
         previous_scan_data,
         language_server_changes,
+        unsaved_file_contents,
     )?;
 
     let mut cached_analysis = if config.ast_diff {
@@ -188,6 +195,15 @@ pub async fn scan_and_analyze_async(
 
     add_invalid_files(&scan_data, &mut analysis_result);
 
+    if !config.analyze_hhi_signatures.is_empty() {
+        find_unresolved_hhi_signature_types(
+            &mut analysis_result,
+            &config,
+            &scan_data.codebase,
+            &scan_data.interner,
+        );
+    }
+
     if config.find_unused_definitions {
         find_unused_definitions(
             &mut analysis_result,
@@ -218,7 +234,7 @@ pub fn scan_and_analyze<F: FnOnce()>(
     chaos_monkey: F,
 ) -> io::Result<(AnalysisResult, SuccessfulScanData)> {
     let mut all_scanned_dirs = stubs_dirs.clone();
-    all_scanned_dirs.push(config.root_dir.clone());
+    all_scanned_dirs.extend(config.root_dirs.iter().cloned());
 
     let file_discovery_and_scanning_now = Instant::now();
 
@@ -242,6 +258,7 @@ pub fn scan_and_analyze<F: FnOnce()>(
         &Arc::new(interner),
         previous_scan_data,
         language_server_changes,
+        FxHashMap::default(),
     )?;
 
     let file_discovery_and_scanning_elapsed = file_discovery_and_scanning_now.elapsed();
@@ -323,6 +340,8 @@ pub fn scan_and_analyze<F: FnOnce()>(
 
     chaos_monkey();
 
+    let analyzing_now = Instant::now();
+
     analyze_files(
         files_to_analyze,
         arc_scan_data.clone(),
@@ -342,9 +361,16 @@ pub fn scan_and_analyze<F: FnOnce()>(
         ));
     }
 
+    let analyzing_elapsed = analyzing_now.elapsed();
+
     let mut analysis_result = (*analysis_result.lock().unwrap()).clone();
 
     analysis_result.time_in_analysis = pure_file_analysis_time;
+    analysis_result.phase_times = vec![
+        ("scan", file_discovery_and_scanning_elapsed),
+        ("populate", populating_elapsed),
+        ("analyze", analyzing_elapsed),
+    ];
 
     cache_analysis_data(cache_dir, &analysis_result)?;
 
@@ -352,7 +378,18 @@ pub fn scan_and_analyze<F: FnOnce()>(
 
     add_invalid_files(&scan_data, &mut analysis_result);
 
+    if !config.analyze_hhi_signatures.is_empty() {
+        find_unresolved_hhi_signature_types(
+            &mut analysis_result,
+            &config,
+            &scan_data.codebase,
+            &scan_data.interner,
+        );
+    }
+
     if config.find_unused_definitions {
+        let unused_definitions_now = Instant::now();
+
         find_unused_definitions(
             &mut analysis_result,
             &config,
@@ -361,9 +398,15 @@ pub fn scan_and_analyze<F: FnOnce()>(
             &ignored_paths,
             &mut scan_data.file_system,
         );
+
+        analysis_result
+            .phase_times
+            .push(("unused_definitions", unused_definitions_now.elapsed()));
     }
 
     if let GraphKind::WholeProgram(whole_program_kind) = config.graph_kind {
+        let dataflow_now = Instant::now();
+
         let issues = match whole_program_kind {
             WholeProgramKind::Taint => find_tainted_data(
                 &analysis_result.program_dataflow_graph,
@@ -379,6 +422,10 @@ pub fn scan_and_analyze<F: FnOnce()>(
             ),
         };
 
+        analysis_result
+            .phase_times
+            .push(("dataflow", dataflow_now.elapsed()));
+
         for issue in issues {
             analysis_result
                 .emitted_issues
@@ -435,6 +482,21 @@ fn cache_analysis_data(
     Ok(())
 }
 
+// Paths passed with --ignore match anywhere in a file's path, and .hakanaignore patterns are
+// merged in alongside them
+pub(crate) fn is_ignored_path(
+    config: &Config,
+    ignored_paths: &Option<FxHashSet<String>>,
+    file_path: &str,
+) -> bool {
+    config.is_in_hakana_ignore(file_path)
+        || ignored_paths.as_ref().map_or(false, |ignored_paths| {
+            ignored_paths
+                .iter()
+                .any(|ignored_path| file_path.contains(ignored_path.as_str()))
+        })
+}
+
 fn get_issues_path(cache_dir: Option<&String>) -> Option<String> {
     cache_dir.map(|cache_dir| format!("{}/issues", cache_dir))
 }
@@ -443,6 +505,217 @@ fn get_references_path(cache_dir: Option<&String>) -> Option<String> {
     cache_dir.map(|cache_dir| format!("{}/references", cache_dir))
 }
 
+// the number of files in a cache directory's manifest, if it has one that can be read
+pub fn get_cached_file_count(cache_dir: &String) -> Option<usize> {
+    cache::get_file_manifest(cache_dir).map(|file_system| file_system.file_hashes_and_times.len())
+}
+
+// Populates one copy of a scanned codebase serially and another in parallel, and lists the
+// parts of the results that differ, so tests can check the parallel path
+pub fn get_population_differences(
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    config: &Config,
+) -> Vec<String> {
+    let mut serial_codebase = codebase.clone();
+    let mut serial_symbol_references = SymbolReferences::new();
+
+    populator::populate_codebase_with_threading(
+        &mut serial_codebase,
+        interner,
+        &mut serial_symbol_references,
+        FxHashSet::default(),
+        FxHashSet::default(),
+        config,
+        false,
+    );
+
+    let mut parallel_codebase = codebase.clone();
+    let mut parallel_symbol_references = SymbolReferences::new();
+
+    populator::populate_codebase_with_threading(
+        &mut parallel_codebase,
+        interner,
+        &mut parallel_symbol_references,
+        FxHashSet::default(),
+        FxHashSet::default(),
+        config,
+        true,
+    );
+
+    let mut differences = vec![];
+
+    // both copies start with the same map layouts, and population only changes values in
+    // place, so equivalent codebases serialize identically
+    if bincode::serialize(&serial_codebase).unwrap()
+        != bincode::serialize(&parallel_codebase).unwrap()
+    {
+        differences.push("populated codebase".to_string());
+    }
+
+    if serial_symbol_references.symbol_references_to_symbols
+        != parallel_symbol_references.symbol_references_to_symbols
+    {
+        differences.push("symbol_references_to_symbols".to_string());
+    }
+
+    if serial_symbol_references.symbol_references_to_symbols_in_signature
+        != parallel_symbol_references.symbol_references_to_symbols_in_signature
+    {
+        differences.push("symbol_references_to_symbols_in_signature".to_string());
+    }
+
+    if serial_symbol_references.symbol_references_to_overridden_members
+        != parallel_symbol_references.symbol_references_to_overridden_members
+    {
+        differences.push("symbol_references_to_overridden_members".to_string());
+    }
+
+    if serial_symbol_references.functionlike_references_to_functionlike_returns
+        != parallel_symbol_references.functionlike_references_to_functionlike_returns
+    {
+        differences.push("functionlike_references_to_functionlike_returns".to_string());
+    }
+
+    if serial_symbol_references.functionlike_references_to_functionlike_calls
+        != parallel_symbol_references.functionlike_references_to_functionlike_calls
+    {
+        differences.push("functionlike_references_to_functionlike_calls".to_string());
+    }
+
+    differences
+}
+
+// Scans the config's roots twice, first with an empty stub cache (which saves the stubs)
+// and then loading the stubs from that cache, and lists the parts of the results that
+// differ, so tests can check the cached path
+pub fn get_stub_cache_differences(
+    config: Arc<Config>,
+    logger: Arc<Logger>,
+    build_checksum: &str,
+    interner: Interner,
+) -> Vec<String> {
+    let stubs_path = if let Some(stub_cache_dir) = &config.stub_cache_dir {
+        cache::get_stubs_path(stub_cache_dir)
+    } else {
+        return vec!["stub cache directory isn't set".to_string()];
+    };
+
+    if Path::new(&stubs_path).exists() {
+        fs::remove_file(&stubs_path).unwrap();
+    }
+
+    let interner = Arc::new(interner);
+
+    // one thread keeps the order strings are interned in the same between the two scans
+    let scan = || {
+        scan_files(
+            &config.root_dirs,
+            None,
+            &config,
+            1,
+            logger.clone(),
+            build_checksum,
+            &interner,
+            None,
+            None,
+            FxHashMap::default(),
+        )
+        .unwrap()
+    };
+
+    let fresh_result = scan();
+
+    if !Path::new(&stubs_path).exists() {
+        return vec!["stub cache wasn't written".to_string()];
+    }
+
+    let cached_result = scan();
+
+    let mut differences = vec![];
+
+    if fresh_result.interner.get_map() != cached_result.interner.get_map() {
+        differences.push("interner".to_string());
+    }
+
+    if fresh_result.resolved_names != cached_result.resolved_names {
+        differences.push("resolved_names".to_string());
+    }
+
+    let fresh_codebase = &fresh_result.codebase;
+    let cached_codebase = &cached_result.codebase;
+
+    // these hold no hash maps, so equal entries serialize identically whichever order they
+    // were built in
+    if get_serialized_entries(&fresh_codebase.functionlike_infos)
+        != get_serialized_entries(&cached_codebase.functionlike_infos)
+    {
+        differences.push("functionlike_infos".to_string());
+    }
+
+    if get_serialized_entries(&fresh_codebase.constant_infos)
+        != get_serialized_entries(&cached_codebase.constant_infos)
+    {
+        differences.push("constant_infos".to_string());
+    }
+
+    if get_serialized_entries(&fresh_codebase.files)
+        != get_serialized_entries(&cached_codebase.files)
+    {
+        differences.push("files".to_string());
+    }
+
+    if get_serialized_entries(&fresh_codebase.symbols.all)
+        != get_serialized_entries(&cached_codebase.symbols.all)
+    {
+        differences.push("symbols".to_string());
+    }
+
+    // classlikes and type definitions hold hash maps, whose serialized order depends on
+    // how they were built, so only the names are compared
+    if fresh_codebase
+        .classlike_infos
+        .keys()
+        .collect::<FxHashSet<_>>()
+        != cached_codebase
+            .classlike_infos
+            .keys()
+            .collect::<FxHashSet<_>>()
+    {
+        differences.push("classlike_infos".to_string());
+    }
+
+    if fresh_codebase
+        .type_definitions
+        .keys()
+        .collect::<FxHashSet<_>>()
+        != cached_codebase
+            .type_definitions
+            .keys()
+            .collect::<FxHashSet<_>>()
+    {
+        differences.push("type_definitions".to_string());
+    }
+
+    if fresh_codebase.closures_in_files != cached_codebase.closures_in_files {
+        differences.push("closures_in_files".to_string());
+    }
+
+    if fresh_codebase.const_files != cached_codebase.const_files {
+        differences.push("const_files".to_string());
+    }
+
+    differences
+}
+
+fn get_serialized_entries<K: Copy + Eq + Hash, V: Serialize>(
+    map: &FxHashMap<K, V>,
+) -> FxHashMap<K, Vec<u8>> {
+    map.iter()
+        .map(|(k, v)| (*k, bincode::serialize(v).unwrap()))
+        .collect()
+}
+
 pub fn get_aast_for_path(
     file_path: FilePath,
     file_path_str: &str,
@@ -472,8 +745,17 @@ pub fn get_aast_for_path(
         .unwrap_or_else(|_| panic!("Could not convert HHI file {}", file_path_str))
         .to_string()
     } else {
-        match fs::read_to_string(file_path_str) {
-            Ok(str_file) => str_file,
+        match fs::read(file_path_str) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(str_file) => str_file,
+                Err(error) => {
+                    return Err(get_invalid_utf8_error(
+                        file_path,
+                        error.as_bytes(),
+                        error.utf8_error().valid_up_to(),
+                    ))
+                }
+            },
             Err(_) => return Err(ParserError::CannotReadFile),
         }
     };
@@ -481,6 +763,28 @@ pub fn get_aast_for_path(
     get_aast_for_path_and_contents(file_path, file_path_str, file_contents)
 }
 
+// Points at the first invalid byte, working out its line and column from the valid
+// contents before it
+fn get_invalid_utf8_error(file_path: FilePath, bytes: &[u8], offset: usize) -> ParserError {
+    let valid_contents = String::from_utf8_lossy(&bytes[0..offset]);
+    let lines = valid_contents.split('\n').collect::<Vec<_>>();
+    let column = lines.last().unwrap().len();
+    let line_count = lines.len();
+
+    ParserError::SyntaxError {
+        message: format!("Invalid UTF-8 sequence at byte {}", offset),
+        pos: HPos {
+            file_path,
+            start_offset: offset as u32,
+            end_offset: offset as u32 + 1,
+            start_line: line_count as u32,
+            end_line: line_count as u32,
+            start_column: (column as u16) + 1,
+            end_column: (column as u16) + 2,
+        },
+    }
+}
+
 fn update_progressbar(percentage: u64, bar: Option<Arc<ProgressBar>>) {
     if let Some(bar) = bar {
         bar.set_position(percentage);
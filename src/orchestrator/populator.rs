@@ -12,6 +12,7 @@ use hakana_code_info::t_union::{populate_union_type, TUnion};
 use hakana_code_info::GenericParent;
 use hakana_str::{Interner, StrId};
 use indexmap::IndexMap;
+use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 // as currently constructed this is not efficient memory-wise
@@ -22,6 +23,28 @@ pub fn populate_codebase(
     safe_symbols: FxHashSet<StrId>,
     safe_symbol_members: FxHashSet<(StrId, StrId)>,
     config: &Config,
+) {
+    populate_codebase_with_threading(
+        codebase,
+        interner,
+        symbol_references,
+        safe_symbols,
+        safe_symbol_members,
+        config,
+        true,
+    );
+}
+
+// The serial path only exists so that tests can check it gives the same results as the
+// parallel one
+pub(crate) fn populate_codebase_with_threading(
+    codebase: &mut CodebaseInfo,
+    interner: &Interner,
+    symbol_references: &mut SymbolReferences,
+    safe_symbols: FxHashSet<StrId>,
+    safe_symbol_members: FxHashSet<(StrId, StrId)>,
+    config: &Config,
+    in_parallel: bool,
 ) {
     let new_classlike_names = codebase
         .classlike_infos
@@ -32,16 +55,50 @@ pub fn populate_codebase(
         .map(|(k, _)| *k)
         .collect::<Vec<_>>();
 
-    for k in &new_classlike_names {
-        if let Some(classlike_info) = codebase.classlike_infos.get_mut(k) {
-            classlike_info.is_populated = false;
-            classlike_info.declaring_property_ids = FxHashMap::default();
-            classlike_info.appearing_property_ids = FxHashMap::default();
-            classlike_info.declaring_method_ids = FxHashMap::default();
-            classlike_info.appearing_method_ids = FxHashMap::default();
+    // Inheritance is resolved in two passes. The first only looks at each classlike's own
+    // members, so every classlike can go through it at once, with each rayon worker
+    // collecting its own references to merge afterwards
+    let codebase_symbols = &codebase.symbols;
+    let new_classlike_name_set = new_classlike_names.iter().collect::<FxHashSet<_>>();
+
+    if in_parallel {
+        let own_symbol_references = codebase
+            .classlike_infos
+            .par_iter_mut()
+            .filter(|(name, _)| new_classlike_name_set.contains(name))
+            .fold(
+                SymbolReferences::new,
+                |mut symbol_references, (name, storage)| {
+                    populate_own_classlike_data(
+                        *name,
+                        storage,
+                        codebase_symbols,
+                        &mut symbol_references,
+                        &safe_symbols,
+                    );
+
+                    symbol_references
+                },
+            )
+            .reduce(SymbolReferences::new, merge_symbol_references);
+
+        symbol_references.extend(own_symbol_references);
+    } else {
+        for (name, storage) in codebase.classlike_infos.iter_mut() {
+            if new_classlike_name_set.contains(name) {
+                populate_own_classlike_data(
+                    *name,
+                    storage,
+                    codebase_symbols,
+                    symbol_references,
+                    &safe_symbols,
+                );
+            }
         }
     }
 
+    // The second pass merges in data from parents, interfaces and traits. A classlike can
+    // only be merged once its parents have been, so this stays serial
     for k in &new_classlike_names {
         populate_classlike_storage(k, codebase, symbol_references, &safe_symbols);
     }
@@ -62,102 +119,13 @@ pub fn populate_codebase(
     }
 
     for (name, storage) in codebase.classlike_infos.iter_mut() {
-        let userland_force_repopulation = storage.user_defined && !safe_symbols.contains(name);
-
-        for (prop_name, v) in storage.properties.iter_mut() {
-            populate_union_type(
-                &mut v.type_,
-                &codebase.symbols,
-                &ReferenceSource::ClasslikeMember(true, *name, *prop_name),
-                symbol_references,
-                userland_force_repopulation,
-            );
-        }
-
-        for (_, map) in storage.template_extended_params.iter_mut() {
-            for (_, v) in map {
-                if v.needs_population() || userland_force_repopulation {
-                    populate_union_type(
-                        Arc::make_mut(v),
-                        &codebase.symbols,
-                        &ReferenceSource::Symbol(true, *name),
-                        symbol_references,
-                        userland_force_repopulation,
-                    );
-                }
-            }
-        }
-
-        for (_, map) in storage.template_types.iter_mut() {
-            for (_, v) in map {
-                if v.needs_population() || userland_force_repopulation {
-                    populate_union_type(
-                        Arc::make_mut(v),
-                        &codebase.symbols,
-                        &ReferenceSource::Symbol(true, *name),
-                        symbol_references,
-                        userland_force_repopulation,
-                    );
-                }
-            }
-        }
-
-        for (constant_name, constant) in storage.constants.iter_mut() {
-            if let Some(provided_type) = constant.provided_type.as_mut() {
-                populate_union_type(
-                    provided_type,
-                    &codebase.symbols,
-                    &ReferenceSource::ClasslikeMember(true, *name, *constant_name),
-                    symbol_references,
-                    userland_force_repopulation,
-                );
-            }
-
-            if let Some(inferred_type) = constant.inferred_type.as_mut() {
-                populate_atomic_type(
-                    inferred_type,
-                    &codebase.symbols,
-                    &ReferenceSource::ClasslikeMember(true, *name, *constant_name),
-                    symbol_references,
-                    userland_force_repopulation,
-                );
-            }
-        }
-
-        for (constant_name, type_constant_info) in storage.type_constants.iter_mut() {
-            match type_constant_info {
-                ClassConstantType::Concrete(type_) | ClassConstantType::Abstract(Some(type_)) => {
-                    populate_union_type(
-                        type_,
-                        &codebase.symbols,
-                        &ReferenceSource::ClasslikeMember(true, *name, *constant_name),
-                        symbol_references,
-                        userland_force_repopulation,
-                    );
-                }
-                _ => {}
-            }
-        }
-
-        if let Some(ref mut enum_type) = storage.enum_type {
-            populate_atomic_type(
-                enum_type,
-                &codebase.symbols,
-                &ReferenceSource::Symbol(true, *name),
-                symbol_references,
-                userland_force_repopulation,
-            );
-        }
-
-        if let Some(ref mut enum_constraint) = storage.enum_constraint {
-            populate_atomic_type(
-                enum_constraint,
-                &codebase.symbols,
-                &ReferenceSource::Symbol(true, *name),
-                symbol_references,
-                userland_force_repopulation,
-            );
-        }
+        populate_classlike_member_types(
+            *name,
+            storage,
+            &codebase.symbols,
+            symbol_references,
+            storage.user_defined && !safe_symbols.contains(name),
+        );
     }
 
     for (name, type_alias) in codebase.type_definitions.iter_mut() {
@@ -256,6 +224,117 @@ pub fn populate_codebase(
     codebase.safe_symbol_members = safe_symbol_members;
 }
 
+fn merge_symbol_references(
+    mut symbol_references: SymbolReferences,
+    other: SymbolReferences,
+) -> SymbolReferences {
+    symbol_references.extend(other);
+    symbol_references
+}
+
+fn populate_classlike_member_types(
+    name: StrId,
+    storage: &mut ClassLikeInfo,
+    codebase_symbols: &Symbols,
+    symbol_references: &mut SymbolReferences,
+    userland_force_repopulation: bool,
+) {
+    for (prop_name, v) in storage.properties.iter_mut() {
+        populate_union_type(
+            &mut v.type_,
+            codebase_symbols,
+            &ReferenceSource::ClasslikeMember(true, name, *prop_name),
+            symbol_references,
+            userland_force_repopulation,
+        );
+    }
+
+    for (_, map) in storage.template_extended_params.iter_mut() {
+        for (_, v) in map {
+            if v.needs_population() || userland_force_repopulation {
+                populate_union_type(
+                    Arc::make_mut(v),
+                    codebase_symbols,
+                    &ReferenceSource::Symbol(true, name),
+                    symbol_references,
+                    userland_force_repopulation,
+                );
+            }
+        }
+    }
+
+    for (_, map) in storage.template_types.iter_mut() {
+        for (_, v) in map {
+            if v.needs_population() || userland_force_repopulation {
+                populate_union_type(
+                    Arc::make_mut(v),
+                    codebase_symbols,
+                    &ReferenceSource::Symbol(true, name),
+                    symbol_references,
+                    userland_force_repopulation,
+                );
+            }
+        }
+    }
+
+    for (constant_name, constant) in storage.constants.iter_mut() {
+        if let Some(provided_type) = constant.provided_type.as_mut() {
+            populate_union_type(
+                provided_type,
+                codebase_symbols,
+                &ReferenceSource::ClasslikeMember(true, name, *constant_name),
+                symbol_references,
+                userland_force_repopulation,
+            );
+        }
+
+        if let Some(inferred_type) = constant.inferred_type.as_mut() {
+            populate_atomic_type(
+                inferred_type,
+                codebase_symbols,
+                &ReferenceSource::ClasslikeMember(true, name, *constant_name),
+                symbol_references,
+                userland_force_repopulation,
+            );
+        }
+    }
+
+    for (constant_name, type_constant_info) in storage.type_constants.iter_mut() {
+        match type_constant_info {
+            ClassConstantType::Concrete(type_) | ClassConstantType::Abstract(Some(type_)) => {
+                populate_union_type(
+                    type_,
+                    codebase_symbols,
+                    &ReferenceSource::ClasslikeMember(true, name, *constant_name),
+                    symbol_references,
+                    userland_force_repopulation,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(ref mut enum_type) = storage.enum_type {
+        populate_atomic_type(
+            enum_type,
+            codebase_symbols,
+            &ReferenceSource::Symbol(true, name),
+            symbol_references,
+            userland_force_repopulation,
+        );
+    }
+
+    if let Some(ref mut enum_constraint) = storage.enum_constraint {
+        populate_atomic_type(
+            enum_constraint,
+            codebase_symbols,
+            &ReferenceSource::Symbol(true, name),
+            symbol_references,
+            userland_force_repopulation,
+        );
+    }
+}
+
 fn populate_functionlike_storage(
     storage: &mut FunctionLikeInfo,
     codebase_symbols: &Symbols,
@@ -364,22 +443,20 @@ fn populate_functionlike_storage(
     }
 }
 
-fn populate_classlike_storage(
-    classlike_name: &StrId,
-    codebase: &mut CodebaseInfo,
+// Resets a classlike's inherited data and fills in what comes from its own members, which
+// doesn't depend on any other classlike
+fn populate_own_classlike_data(
+    classlike_name: StrId,
+    storage: &mut ClassLikeInfo,
+    codebase_symbols: &Symbols,
     symbol_references: &mut SymbolReferences,
     safe_symbols: &FxHashSet<StrId>,
 ) {
-    let mut storage = if let Some(storage) = codebase.classlike_infos.remove(classlike_name) {
-        storage
-    } else {
-        return;
-    };
-
-    if storage.is_populated {
-        codebase.classlike_infos.insert(*classlike_name, storage);
-        return;
-    }
+    storage.is_populated = false;
+    storage.declaring_property_ids = FxHashMap::default();
+    storage.appearing_property_ids = FxHashMap::default();
+    storage.declaring_method_ids = FxHashMap::default();
+    storage.appearing_method_ids = FxHashMap::default();
 
     for attribute_info in &storage.attributes {
         symbol_references.add_symbol_reference_to_symbol(storage.name, attribute_info.name, true);
@@ -388,32 +465,50 @@ fn populate_classlike_storage(
     for property_id in storage.properties.keys() {
         storage
             .declaring_property_ids
-            .insert(*property_id, *classlike_name);
+            .insert(*property_id, classlike_name);
         storage
             .appearing_property_ids
-            .insert(*property_id, *classlike_name);
+            .insert(*property_id, classlike_name);
     }
 
     for method_name in &storage.methods {
         storage
             .declaring_method_ids
-            .insert(*method_name, *classlike_name);
+            .insert(*method_name, classlike_name);
         storage
             .appearing_method_ids
-            .insert(*method_name, *classlike_name);
+            .insert(*method_name, classlike_name);
     }
 
     for (_, param_types) in storage.template_extended_offsets.iter_mut() {
         for param_type in param_types {
             populate_union_type(
                 Arc::make_mut(param_type),
-                &codebase.symbols,
-                &ReferenceSource::Symbol(true, *classlike_name),
+                codebase_symbols,
+                &ReferenceSource::Symbol(true, classlike_name),
                 symbol_references,
-                !safe_symbols.contains(classlike_name),
+                !safe_symbols.contains(&classlike_name),
             );
         }
     }
+}
+
+fn populate_classlike_storage(
+    classlike_name: &StrId,
+    codebase: &mut CodebaseInfo,
+    symbol_references: &mut SymbolReferences,
+    safe_symbols: &FxHashSet<StrId>,
+) {
+    let mut storage = if let Some(storage) = codebase.classlike_infos.remove(classlike_name) {
+        storage
+    } else {
+        return;
+    };
+
+    if storage.is_populated {
+        codebase.classlike_infos.insert(*classlike_name, storage);
+        return;
+    }
 
     for trait_name in &storage.used_traits.clone() {
         populate_data_from_trait(
@@ -11,14 +11,19 @@ use super::HhiAsset;
 use super::HslAsset;
 use crate::ast_differ;
 use crate::cache::get_file_manifest;
+use crate::cache::get_stubs_path;
 use crate::cache::load_cached_aast_names;
 use crate::cache::load_cached_codebase;
 use crate::cache::load_cached_interner;
+use crate::cache::load_cached_stubs;
+use crate::cache::write_cached_stubs;
+use crate::cache::CachedStubs;
 use crate::file::FileStatus;
 use crate::file::VirtualFileSystem;
 use crate::get_aast_for_path;
 use crate::SuccessfulScanData;
 use ast_differ::get_diff;
+use hakana_aast_helper::get_aast_for_path_and_contents;
 use hakana_aast_helper::name_context::NameContext;
 use hakana_analyzer::config::Config;
 use hakana_code_info::code_location::FilePath;
@@ -58,6 +63,7 @@ pub fn scan_files(
     starter_interner: &Arc<Interner>,
     starter_data: Option<SuccessfulScanData>,
     language_server_changes: Option<FxHashMap<String, FileStatus>>,
+    unsaved_file_contents: FxHashMap<String, String>,
 ) -> io::Result<ScanFilesResult> {
     logger.log_debug_sync(&format!("{:#?}", scan_dirs));
 
@@ -122,16 +128,43 @@ pub fn scan_files(
     let file_discovery_now = Instant::now();
     let load_from_cache_now = Instant::now();
 
+    let mut has_cached_interner = false;
+
     if let Some(symbols_path) = &symbols_path {
         if let Some(cached_interner) =
             load_cached_interner(symbols_path, use_codebase_cache, &logger)
         {
             interner = cached_interner;
+            has_cached_interner = true;
+        }
+    }
+
+    // the stub cache is only needed when everything, stubs included, would be scanned
+    let stubs_path = if has_starter || has_cached_interner {
+        None
+    } else {
+        config
+            .stub_cache_dir
+            .as_ref()
+            .map(|stub_cache_dir| get_stubs_path(stub_cache_dir))
+    };
+
+    let mut has_cached_stubs = false;
+
+    if let Some(stubs_path) = &stubs_path {
+        if let Some(cached_stubs) =
+            load_cached_stubs(stubs_path, build_checksum, &interner, &logger)
+        {
+            interner = cached_stubs.interner;
+            codebase = cached_stubs.codebase;
+            resolved_names = cached_stubs.resolved_names;
+            has_cached_stubs = true;
         }
     }
 
     let file_system = if let Some(language_server_changes) = language_server_changes {
         let mut file_system = existing_file_system.clone().unwrap();
+        file_system.unsaved_file_contents = unsaved_file_contents;
 
         file_system.apply_language_server_changes(
             language_server_changes,
@@ -152,6 +185,7 @@ pub fn scan_files(
             config,
             cache_dir,
             &mut files_to_analyze,
+            unsaved_file_contents,
         )
     };
 
@@ -167,29 +201,45 @@ pub fn scan_files(
     let file_statuses =
         file_system.get_file_statuses(&files_to_scan, &interner, &existing_file_system);
 
+    let stub_file_paths = if stubs_path.is_some() {
+        HhiAsset::iter()
+            .chain(HslAsset::iter())
+            .filter_map(|file| interner.get(&file))
+            .map(FilePath)
+            .collect::<FxHashSet<_>>()
+    } else {
+        FxHashSet::default()
+    };
+
+    // stubs loaded from the stub cache are already in the codebase
     let changed_files = file_statuses
         .iter()
-        .filter(|(_, v)| !matches!(v, FileStatus::Unchanged(..)))
+        .filter(|(k, v)| {
+            !matches!(v, FileStatus::Unchanged(..))
+                && !(has_cached_stubs && stub_file_paths.contains(*k))
+        })
         .map(|(k, _)| *k)
         .collect::<FxHashSet<_>>();
 
     // this needs to come after we've loaded interned strings
-    if !has_starter {
-        if let Some(codebase_path) = &codebase_path {
-            if let Some(cache_codebase) =
-                load_cached_codebase(codebase_path, use_codebase_cache, &logger)
-            {
-                codebase = cache_codebase;
+    if stubs_path.is_none() {
+        if !has_starter {
+            if let Some(codebase_path) = &codebase_path {
+                if let Some(cache_codebase) =
+                    load_cached_codebase(codebase_path, use_codebase_cache, &logger)
+                {
+                    codebase = cache_codebase;
+                }
             }
         }
-    }
 
-    if let Some(aast_names_path) = &aast_names_path {
-        if let Some(cached_resolved_names) =
-            load_cached_aast_names(aast_names_path, use_codebase_cache, &logger)
-        {
-            resolved_names = cached_resolved_names
-        };
+        if let Some(aast_names_path) = &aast_names_path {
+            if let Some(cached_resolved_names) =
+                load_cached_aast_names(aast_names_path, use_codebase_cache, &logger)
+            {
+                resolved_names = cached_resolved_names
+            };
+        }
     }
 
     let load_from_cache_elapsed = load_from_cache_now.elapsed();
@@ -204,10 +254,19 @@ pub fn scan_files(
     invalidate_changed_codebase_elements(&mut codebase, &changed_files);
 
     let mut files_to_scan = vec![];
+    let mut stub_files_to_scan = vec![];
 
     for (target_file, status) in &file_statuses {
         if matches!(status, FileStatus::Added(..) | FileStatus::Modified(..)) {
-            files_to_scan.push(target_file);
+            // with a stub cache, stubs are scanned on their own first, so that they can be
+            // saved before any other code is added to the interner and codebase
+            if stub_file_paths.contains(target_file) {
+                if !has_cached_stubs {
+                    stub_files_to_scan.push(*target_file);
+                }
+            } else {
+                files_to_scan.push(*target_file);
+            }
         }
     }
 
@@ -251,147 +310,47 @@ pub fn scan_files(
     let interner = Arc::new(Mutex::new(interner));
     let resolved_names = Arc::new(Mutex::new(resolved_names));
 
-    let has_new_files = !files_to_scan.is_empty() || !changed_files.is_empty();
+    let has_new_files =
+        !files_to_scan.is_empty() || !stub_files_to_scan.is_empty() || !changed_files.is_empty();
 
     let invalid_files = Arc::new(Mutex::new(vec![]));
 
-    if !files_to_scan.is_empty() {
-        let file_scanning_now = Instant::now();
+    let unsaved_file_contents = Arc::new(file_system.unsaved_file_contents.clone());
 
-        let bar = if logger.show_progress() {
-            let pb = ProgressBar::new(files_to_scan.len() as u64);
-            let sty =
-                ProgressStyle::with_template("{bar:40.green/yellow} {pos:>7}/{len:7}").unwrap();
-            pb.set_style(sty);
-            Some(Arc::new(pb))
-        } else {
-            None
-        };
-
-        let files_processed: Arc<Mutex<u64>> = Arc::new(Mutex::new(0));
-
-        let mut group_size = threads as usize;
-
-        let mut path_groups = FxHashMap::default();
-
-        if files_to_scan.len() < 4 * group_size {
-            group_size = 1;
+    for (file_paths, is_stubs) in [(stub_files_to_scan, true), (files_to_scan, false)] {
+        if file_paths.is_empty() {
+            continue;
         }
 
-        for (i, str_path) in files_to_scan.into_iter().enumerate() {
-            let group = i % group_size;
-            path_groups
-                .entry(group)
-                .or_insert_with(Vec::new)
-                .push(str_path);
-        }
-
-        let mut handles = vec![];
-
-        let thread_codebases = Arc::new(Mutex::new(vec![]));
-
-        for (_, path_group) in path_groups {
-            let pgc = path_group.iter().map(|c| *(*c)).collect::<Vec<_>>();
-
-            let codebases = thread_codebases.clone();
-
-            let bar = bar.clone();
-            let files_processed = files_processed.clone();
-
-            let analyze_map = files_to_analyze
-                .clone()
-                .into_iter()
-                .collect::<FxHashSet<_>>();
-
-            let interner = interner.clone();
-
-            let resolved_names = resolved_names.clone();
-
-            let config = config.clone();
-            let logger = logger.clone();
-            let invalid_files = invalid_files.clone();
-
-            let handle = std::thread::spawn(move || {
-                let mut new_codebase = CodebaseInfo::new();
-                let mut new_interner = ThreadedInterner::new(interner);
-                let empty_name_context = NameContext::new(&mut new_interner);
-                let mut local_resolved_names = FxHashMap::default();
-
-                for file_path in &pgc {
-                    let str_path = new_interner
-                        .parent
-                        .lock()
-                        .unwrap()
-                        .lookup(&file_path.0)
-                        .to_string();
-
-                    match scan_file(
-                        &str_path,
-                        *file_path,
-                        &config.all_custom_issues,
-                        &mut new_codebase,
-                        &mut new_interner,
-                        empty_name_context.clone(),
-                        analyze_map.contains(&str_path),
-                        !config.test_files.iter().any(|p| p.matches(&str_path)),
-                        &logger.clone(),
-                    ) {
-                        Ok(scanner_result) => {
-                            local_resolved_names.insert(*file_path, scanner_result);
-                        }
-                        Err(parser_error) => {
-                            local_resolved_names.remove(file_path);
-                            new_codebase.files.insert(
-                                *file_path,
-                                FileInfo {
-                                    parser_errors: vec![parser_error],
-                                    ..FileInfo::default()
-                                },
-                            );
-                            invalid_files.lock().unwrap().push(*file_path);
-                        }
-                    };
-
-                    let mut tally = files_processed.lock().unwrap();
-                    *tally += 1;
-
-                    update_progressbar(*tally, bar.clone());
-                }
-
-                resolved_names.lock().unwrap().extend(local_resolved_names);
-
-                let mut codebases = codebases.lock().unwrap();
-                codebases.push(new_codebase);
-            });
-
-            handles.push(handle);
-        }
-
-        for handle in handles {
-            handle.join().unwrap();
-        }
-
-        if let Ok(thread_codebases) = Arc::try_unwrap(thread_codebases) {
-            for thread_codebase in thread_codebases.into_inner().unwrap().into_iter() {
-                if config.ast_diff {
-                    codebase_diff.extend(get_diff(&existing_changed_files, &thread_codebase.files));
-                }
-
-                codebase.extend(thread_codebase);
+        for thread_codebase in scan_file_paths(
+            file_paths,
+            &files_to_analyze,
+            config,
+            threads,
+            &logger,
+            &interner,
+            &resolved_names,
+            &invalid_files,
+            &unsaved_file_contents,
+        ) {
+            if config.ast_diff {
+                codebase_diff.extend(get_diff(&existing_changed_files, &thread_codebase.files));
             }
-        }
 
-        if let Some(bar) = &bar {
-            bar.finish_and_clear();
+            codebase.extend(thread_codebase);
         }
 
-        let file_scanning_elapsed = file_scanning_now.elapsed();
+        if let (true, Some(stubs_path)) = (is_stubs, &stubs_path) {
+            let cached_stubs = CachedStubs {
+                build_checksum: build_checksum.to_string(),
+                interner: interner.lock().unwrap().clone(),
+                codebase: codebase.clone(),
+                resolved_names: resolved_names.lock().unwrap().clone(),
+            };
 
-        if logger.can_log_timing() {
-            logger.log_sync(&format!(
-                "Scanning files took {:.2?}",
-                file_scanning_elapsed
-            ));
+            if let Err(error) = write_cached_stubs(stubs_path, &cached_stubs) {
+                logger.log_sync(&format!("Could not write stubs cache: {}", error));
+            }
         }
     }
 
@@ -444,6 +403,149 @@ pub fn scan_files(
     })
 }
 
+// Scans the given files across threads, returning one codebase per thread
+fn scan_file_paths(
+    files_to_scan: Vec<FilePath>,
+    files_to_analyze: &[String],
+    config: &Arc<Config>,
+    threads: u8,
+    logger: &Arc<Logger>,
+    interner: &Arc<Mutex<Interner>>,
+    resolved_names: &Arc<Mutex<FxHashMap<FilePath, FxHashMap<u32, StrId>>>>,
+    invalid_files: &Arc<Mutex<Vec<FilePath>>>,
+    unsaved_file_contents: &Arc<FxHashMap<String, String>>,
+) -> Vec<CodebaseInfo> {
+    let file_scanning_now = Instant::now();
+
+    let bar = if logger.show_progress() {
+        let pb = ProgressBar::new(files_to_scan.len() as u64);
+        let sty = ProgressStyle::with_template("{bar:40.green/yellow} {pos:>7}/{len:7}").unwrap();
+        pb.set_style(sty);
+        Some(Arc::new(pb))
+    } else {
+        None
+    };
+
+    let files_processed: Arc<Mutex<u64>> = Arc::new(Mutex::new(0));
+
+    let mut group_size = threads as usize;
+
+    let mut path_groups = FxHashMap::default();
+
+    if files_to_scan.len() < 4 * group_size {
+        group_size = 1;
+    }
+
+    for (i, str_path) in files_to_scan.into_iter().enumerate() {
+        let group = i % group_size;
+        path_groups
+            .entry(group)
+            .or_insert_with(Vec::new)
+            .push(str_path);
+    }
+
+    let mut handles = vec![];
+
+    let thread_codebases = Arc::new(Mutex::new(vec![]));
+
+    for (_, pgc) in path_groups {
+        let codebases = thread_codebases.clone();
+
+        let bar = bar.clone();
+        let files_processed = files_processed.clone();
+
+        let analyze_map = files_to_analyze.iter().cloned().collect::<FxHashSet<_>>();
+
+        let interner = interner.clone();
+
+        let resolved_names = resolved_names.clone();
+
+        let config = config.clone();
+        let logger = logger.clone();
+        let invalid_files = invalid_files.clone();
+        let unsaved_file_contents = unsaved_file_contents.clone();
+
+        let handle = std::thread::spawn(move || {
+            let mut new_codebase = CodebaseInfo::new();
+            let mut new_interner = ThreadedInterner::new(interner);
+            let empty_name_context = NameContext::new(&mut new_interner);
+            let mut local_resolved_names = FxHashMap::default();
+
+            for file_path in &pgc {
+                let str_path = new_interner
+                    .parent
+                    .lock()
+                    .unwrap()
+                    .lookup(&file_path.0)
+                    .to_string();
+
+                match scan_file(
+                    &str_path,
+                    *file_path,
+                    &config.all_custom_issues,
+                    &mut new_codebase,
+                    &mut new_interner,
+                    empty_name_context.clone(),
+                    analyze_map.contains(&str_path),
+                    config.is_production_file(&str_path),
+                    unsaved_file_contents.get(&str_path).cloned(),
+                    &logger.clone(),
+                ) {
+                    Ok(scanner_result) => {
+                        local_resolved_names.insert(*file_path, scanner_result);
+                    }
+                    Err(parser_error) => {
+                        local_resolved_names.remove(file_path);
+                        new_codebase.files.insert(
+                            *file_path,
+                            FileInfo {
+                                parser_errors: vec![parser_error],
+                                ..FileInfo::default()
+                            },
+                        );
+                        invalid_files.lock().unwrap().push(*file_path);
+                    }
+                };
+
+                let mut tally = files_processed.lock().unwrap();
+                *tally += 1;
+
+                update_progressbar(*tally, bar.clone());
+            }
+
+            resolved_names.lock().unwrap().extend(local_resolved_names);
+
+            let mut codebases = codebases.lock().unwrap();
+            codebases.push(new_codebase);
+        });
+
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let thread_codebases = Arc::try_unwrap(thread_codebases)
+        .map(|thread_codebases| thread_codebases.into_inner().unwrap())
+        .unwrap_or_default();
+
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+
+    let file_scanning_elapsed = file_scanning_now.elapsed();
+
+    if logger.can_log_timing() {
+        logger.log_sync(&format!(
+            "Scanning files took {:.2?}",
+            file_scanning_elapsed
+        ));
+    }
+
+    thread_codebases
+}
+
 fn get_filesystem(
     files_to_scan: &mut Vec<String>,
     interner: &mut Interner,
@@ -453,8 +555,12 @@ fn get_filesystem(
     config: &Arc<Config>,
     cache_dir: Option<&String>,
     files_to_analyze: &mut Vec<String>,
+    unsaved_file_contents: FxHashMap<String, String>,
 ) -> VirtualFileSystem {
-    let mut file_system = VirtualFileSystem::default();
+    let mut file_system = VirtualFileSystem {
+        unsaved_file_contents,
+        ..VirtualFileSystem::default()
+    };
 
     add_builtins_to_scan(files_to_scan, interner, &mut file_system);
 
@@ -509,11 +615,16 @@ pub(crate) fn scan_file(
     empty_name_context: NameContext<'_>,
     user_defined: bool,
     is_production_code: bool,
+    unsaved_contents: Option<String>,
     logger: &Logger,
 ) -> Result<FxHashMap<u32, StrId>, ParserError> {
     logger.log_debug_sync(&format!("scanning {}", str_path));
 
-    let aast = get_aast_for_path(file_path, str_path);
+    let aast = if let Some(unsaved_contents) = unsaved_contents {
+        get_aast_for_path_and_contents(file_path, str_path, unsaved_contents)
+    } else {
+        get_aast_for_path(file_path, str_path)
+    };
 
     let aast = match aast {
         Ok(aast) => aast,
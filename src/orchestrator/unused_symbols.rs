@@ -2,7 +2,7 @@ use chrono::Utc;
 use hakana_analyzer::config::Config;
 use hakana_code_info::analysis_result::{AnalysisResult, Replacement};
 use hakana_code_info::classlike_info::ClassLikeInfo;
-use hakana_code_info::code_location::StmtStart;
+use hakana_code_info::code_location::{HPos, StmtStart};
 use hakana_code_info::codebase_info::symbols::SymbolKind;
 use hakana_code_info::codebase_info::{CodebaseInfo, Symbols};
 use hakana_code_info::functionlike_identifier::FunctionLikeIdentifier;
@@ -15,6 +15,9 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 
 use crate::file::VirtualFileSystem;
+use crate::is_ignored_path;
+
+const MAX_LISTED_REFERENCES: usize = 5;
 
 pub(crate) fn find_unused_definitions(
     analysis_result: &mut AnalysisResult,
@@ -63,7 +66,7 @@ pub(crate) fn find_unused_definitions(
         return;
     }
 
-    let referenced_symbols_and_members = analysis_result.symbol_references.back_references();
+    let back_references = analysis_result.symbol_references.back_references();
     let mut test_symbols = codebase
         .classlike_infos
         .iter()
@@ -80,32 +83,33 @@ pub(crate) fn find_unused_definitions(
 
     let mut referenced_symbols_and_members_in_production = FxHashSet::default();
 
-    for (k, v) in referenced_symbols_and_members.clone().into_iter() {
+    for (k, v) in &back_references {
         if !v.is_subset(&test_symbols) {
-            referenced_symbols_and_members_in_production.insert(k);
+            referenced_symbols_and_members_in_production.insert(*k);
         }
     }
 
-    let referenced_symbols_and_members = referenced_symbols_and_members
-        .into_keys()
-        .collect::<FxHashSet<_>>();
+    let referenced_symbols_and_members = back_references.keys().copied().collect::<FxHashSet<_>>();
 
-    let referenced_overridden_class_members = analysis_result
+    let overridden_back_references = analysis_result
         .symbol_references
         .get_referenced_overridden_class_members();
 
     let mut referenced_overridden_class_members_in_production = FxHashSet::default();
 
-    for (k, v) in referenced_overridden_class_members.clone().into_iter() {
+    for (k, v) in &overridden_back_references {
         if !v.is_subset(&test_symbols) {
-            referenced_overridden_class_members_in_production.insert(k);
+            referenced_overridden_class_members_in_production.insert(*k);
         }
     }
 
-    let referenced_overridden_class_members = referenced_overridden_class_members
-        .into_keys()
+    let referenced_overridden_class_members = overridden_back_references
+        .keys()
+        .copied()
         .collect::<FxHashSet<_>>();
 
+    let all_back_references = [&back_references, &overridden_back_references];
+
     'outer1: for (functionlike_name, functionlike_info) in &codebase.functionlike_infos {
         if functionlike_name.1 == StrId::EMPTY
             && functionlike_info.user_defined
@@ -115,12 +119,8 @@ pub(crate) fn find_unused_definitions(
             let pos = functionlike_info.name_location.as_ref().unwrap();
             let file_path = interner.lookup(&pos.file_path.0);
 
-            if let Some(ignored_paths) = ignored_paths {
-                for ignored_path in ignored_paths {
-                    if file_path.matches(ignored_path.as_str()).count() > 0 {
-                        continue 'outer1;
-                    }
-                }
+            if is_ignored_path(config, ignored_paths, file_path) {
+                continue 'outer1;
             }
 
             if !referenced_symbols_and_members.contains(functionlike_name) {
@@ -138,7 +138,17 @@ pub(crate) fn find_unused_definitions(
 
                 let issue = Issue::new(
                     IssueKind::UnusedFunction,
-                    format!("Unused function {}", interner.lookup(&functionlike_name.0)),
+                    format!(
+                        "Unused function {}{}",
+                        interner.lookup(&functionlike_name.0),
+                        get_reference_summary(
+                            functionlike_name,
+                            &all_back_references,
+                            codebase,
+                            &config.root_dir,
+                            interner
+                        )
+                    ),
                     *pos,
                     &Some(FunctionLikeIdentifier::Function(functionlike_name.0)),
                 );
@@ -158,7 +168,7 @@ pub(crate) fn find_unused_definitions(
                         );
                 }
 
-                if config.can_add_issue(&issue) {
+                if config.can_add_issue(&issue, file_path) {
                     *analysis_result
                         .issue_counts
                         .entry(issue.kind.clone())
@@ -176,14 +186,21 @@ pub(crate) fn find_unused_definitions(
                 let issue = Issue::new(
                     IssueKind::OnlyUsedInTests,
                     format!(
-                        "Production-code function {} is only used in tests — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
-                        interner.lookup(&functionlike_name.0)
+                        "Production-code function {} is only used in tests{} — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
+                        interner.lookup(&functionlike_name.0),
+                        get_reference_summary(
+                            functionlike_name,
+                            &all_back_references,
+                            codebase,
+                            &config.root_dir,
+                            interner
+                        )
                     ),
                     *pos,
                     &Some(FunctionLikeIdentifier::Function(functionlike_name.0)),
                 );
 
-                if config.can_add_issue(&issue) {
+                if config.can_add_issue(&issue, file_path) {
                     *analysis_result
                         .issue_counts
                         .entry(issue.kind.clone())
@@ -203,12 +220,8 @@ pub(crate) fn find_unused_definitions(
             let pos = &classlike_info.name_location;
             let file_path = interner.lookup(&pos.file_path.0);
 
-            if let Some(ignored_paths) = ignored_paths {
-                for ignored_path in ignored_paths {
-                    if file_path.matches(ignored_path.as_str()).count() > 0 {
-                        continue 'outer2;
-                    }
-                }
+            if is_ignored_path(config, ignored_paths, file_path) {
+                continue 'outer2;
             }
 
             if !referenced_symbols_and_members.contains(&(*classlike_name, StrId::EMPTY)) {
@@ -224,8 +237,15 @@ pub(crate) fn find_unused_definitions(
                 let mut issue = Issue::new(
                     IssueKind::UnusedClass,
                     format!(
-                        "Unused class, interface or enum {}",
+                        "Unused class, interface or enum {}{}",
                         interner.lookup(classlike_name),
+                        get_reference_summary(
+                            &(*classlike_name, StrId::EMPTY),
+                            &all_back_references,
+                            codebase,
+                            &config.root_dir,
+                            interner
+                        ),
                     ),
                     *pos,
                     &Some(FunctionLikeIdentifier::Function(*classlike_name)),
@@ -253,7 +273,7 @@ pub(crate) fn find_unused_definitions(
                     add_newline: true,
                 });
 
-                if config.can_add_issue(&issue) {
+                if config.can_add_issue(&issue, file_path) {
                     if config.add_fixmes {
                         analysis_result
                             .replacements
@@ -296,14 +316,21 @@ pub(crate) fn find_unused_definitions(
                         let issue = Issue::new(
                             IssueKind::OnlyUsedInTests,
                             format!(
-                                "Production-code class {} is only used in tests — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
-                                interner.lookup(classlike_name)
+                                "Production-code class {} is only used in tests{} — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
+                                interner.lookup(classlike_name),
+                                get_reference_summary(
+                                    &(*classlike_name, StrId::EMPTY),
+                                    &all_back_references,
+                                    codebase,
+                                    &config.root_dir,
+                                    interner
+                                )
                             ),
                             *pos,
                             &Some(FunctionLikeIdentifier::Function(*classlike_name)),
                         );
 
-                        if config.can_add_issue(&issue) {
+                        if config.can_add_issue(&issue, file_path) {
                             *analysis_result
                                 .issue_counts
                                 .entry(issue.kind.clone())
@@ -371,9 +398,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedPrivateMethod,
                                     format!(
-                                        "Unused method {}::{}",
+                                        "Unused method {}::{}{}",
                                         interner.lookup(classlike_name),
-                                        interner.lookup(method_name_ptr)
+                                        interner.lookup(method_name_ptr),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        )
                                     ),
                                     functionlike_storage.name_location.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -385,9 +419,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedInheritedMethod,
                                     format!(
-                                        "Unused inherited method {}::{}",
+                                        "Unused inherited method {}::{}{}",
                                         interner.lookup(classlike_name),
-                                        interner.lookup(method_name_ptr)
+                                        interner.lookup(method_name_ptr),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        )
                                     ),
                                     functionlike_storage.name_location.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -399,9 +440,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedPublicOrProtectedMethod,
                                     format!(
-                                        "Unused public or protected method {}::{}",
+                                        "Unused public or protected method {}::{}{}",
                                         interner.lookup(classlike_name),
-                                        interner.lookup(method_name_ptr)
+                                        interner.lookup(method_name_ptr),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        )
                                     ),
                                     functionlike_storage.name_location.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -439,7 +487,7 @@ pub(crate) fn find_unused_definitions(
                                             - meta_start.start_column as u32,
                                     ),
                                 );
-                        } else if config.can_add_issue(&issue) {
+                        } else if config.can_add_issue(&issue, file_path) {
                             *analysis_result
                                 .issue_counts
                                 .entry(issue.kind.clone())
@@ -471,15 +519,22 @@ pub(crate) fn find_unused_definitions(
                         let issue = Issue::new(
                                 IssueKind::OnlyUsedInTests,
                                 format!(
-                                    "Production-code method {}::{} is only used in tests — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
+                                    "Production-code method {}::{} is only used in tests{} — if this is deliberate add the <<Hakana\\TestOnly>> attribute",
                                     interner.lookup(classlike_name),
-                                    interner.lookup(method_name_ptr)
+                                    interner.lookup(method_name_ptr),
+                                    get_reference_summary(
+                                        &pair,
+                                        &all_back_references,
+                                        codebase,
+                                        &config.root_dir,
+                                        interner
+                                    )
                                 ),
                                 *pos,
                                 &Some(FunctionLikeIdentifier::Method(*classlike_name, *method_name_ptr)),
                             );
 
-                        if config.can_add_issue(&issue) {
+                        if config.can_add_issue(&issue, file_path) {
                             *analysis_result
                                 .issue_counts
                                 .entry(issue.kind.clone())
@@ -510,9 +565,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedPrivateProperty,
                                     format!(
-                                        "Unused private property {}::${}",
+                                        "Unused private property {}::${}{}",
                                         interner.lookup(classlike_name),
-                                        interner.lookup(property_name_ptr)
+                                        interner.lookup(property_name_ptr),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        )
                                     ),
                                     property_storage.pos.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -525,9 +587,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedXhpAttribute,
                                     format!(
-                                        "Unused XHP attribute {} in class {}",
+                                        "Unused XHP attribute {} in class {}{}",
                                         interner.lookup(property_name_ptr),
                                         interner.lookup(classlike_name),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        ),
                                     ),
                                     property_storage.pos.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -539,9 +608,16 @@ pub(crate) fn find_unused_definitions(
                                 Issue::new(
                                     IssueKind::UnusedPublicOrProtectedProperty,
                                     format!(
-                                        "Unused public or protected property {}::${}",
+                                        "Unused public or protected property {}::${}{}",
                                         interner.lookup(classlike_name),
-                                        interner.lookup(property_name_ptr)
+                                        interner.lookup(property_name_ptr),
+                                        get_reference_summary(
+                                            &pair,
+                                            &all_back_references,
+                                            codebase,
+                                            &config.root_dir,
+                                            interner
+                                        )
                                     ),
                                     property_storage.pos.unwrap(),
                                     &Some(FunctionLikeIdentifier::Method(
@@ -570,7 +646,7 @@ pub(crate) fn find_unused_definitions(
                                         ),
                                     );
                             }
-                        } else if config.can_add_issue(&issue) {
+                        } else if config.can_add_issue(&issue, file_path) {
                             *analysis_result
                                 .issue_counts
                                 .entry(issue.kind.clone())
@@ -592,12 +668,8 @@ pub(crate) fn find_unused_definitions(
             let pos = &type_definition_info.location;
             let file_path = interner.lookup(&pos.file_path.0);
 
-            if let Some(ignored_paths) = ignored_paths {
-                for ignored_path in ignored_paths {
-                    if file_path.matches(ignored_path.as_str()).count() > 0 {
-                        continue 'outer2;
-                    }
-                }
+            if is_ignored_path(config, ignored_paths, file_path) {
+                continue 'outer2;
             }
 
             if !config.allow_issue_kind_in_file(&IssueKind::UnusedTypeDefinition, file_path) {
@@ -607,7 +679,17 @@ pub(crate) fn find_unused_definitions(
             if !referenced_symbols_and_members.contains(&(*type_name, StrId::EMPTY)) {
                 let issue = Issue::new(
                     IssueKind::UnusedTypeDefinition,
-                    format!("Unused type definition {}", interner.lookup(type_name)),
+                    format!(
+                        "Unused type definition {}{}",
+                        interner.lookup(type_name),
+                        get_reference_summary(
+                            &(*type_name, StrId::EMPTY),
+                            &all_back_references,
+                            codebase,
+                            &config.root_dir,
+                            interner
+                        )
+                    ),
                     *pos,
                     &Some(FunctionLikeIdentifier::Function(*type_name)),
                 );
@@ -628,7 +710,7 @@ pub(crate) fn find_unused_definitions(
                         );
                 }
 
-                if config.can_add_issue(&issue) {
+                if config.can_add_issue(&issue, file_path) {
                     *analysis_result
                         .issue_counts
                         .entry(issue.kind.clone())
@@ -644,6 +726,92 @@ pub(crate) fn find_unused_definitions(
     }
 }
 
+// Lists the symbols Hakana saw referencing a definition, so a developer can tell whether
+// an unused-definition report comes from usage Hakana can't see (e.g. dynamic calls).
+// Returns an empty string when nothing references the definition.
+fn get_reference_summary(
+    symbol: &(StrId, StrId),
+    all_back_references: &[&FxHashMap<(StrId, StrId), FxHashSet<(StrId, StrId)>>],
+    codebase: &CodebaseInfo,
+    root_dir: &str,
+    interner: &Interner,
+) -> String {
+    let mut referencing_symbols = all_back_references
+        .iter()
+        .filter_map(|back_references| back_references.get(symbol))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    if referencing_symbols.is_empty() {
+        return "".to_string();
+    }
+
+    referencing_symbols.sort_by_cached_key(|referencing_symbol| {
+        get_referencing_symbol_name(referencing_symbol, interner)
+    });
+    referencing_symbols.dedup();
+
+    let listed_symbols = referencing_symbols
+        .iter()
+        .take(MAX_LISTED_REFERENCES)
+        .map(|referencing_symbol| {
+            let name = get_referencing_symbol_name(referencing_symbol, interner);
+
+            if let Some(pos) = get_referencing_symbol_pos(referencing_symbol, codebase) {
+                format!(
+                    "{} ({}:{})",
+                    name,
+                    pos.file_path.get_relative_path(interner, root_dir),
+                    pos.start_line
+                )
+            } else {
+                name
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match referencing_symbols.len() {
+        1 => format!(" (referenced by 1 symbol: {})", listed_symbols),
+        count if count <= MAX_LISTED_REFERENCES => {
+            format!(" (referenced by {} symbols: {})", count, listed_symbols)
+        }
+        count => format!(
+            " (referenced by {} symbols: {} and {} more)",
+            count,
+            listed_symbols,
+            count - MAX_LISTED_REFERENCES
+        ),
+    }
+}
+
+fn get_referencing_symbol_name(referencing_symbol: &(StrId, StrId), interner: &Interner) -> String {
+    if referencing_symbol.1 == StrId::EMPTY {
+        interner.lookup(&referencing_symbol.0).to_string()
+    } else {
+        format!(
+            "{}::{}",
+            interner.lookup(&referencing_symbol.0),
+            interner.lookup(&referencing_symbol.1)
+        )
+    }
+}
+
+fn get_referencing_symbol_pos(
+    referencing_symbol: &(StrId, StrId),
+    codebase: &CodebaseInfo,
+) -> Option<HPos> {
+    if let Some(functionlike_info) = codebase.functionlike_infos.get(referencing_symbol) {
+        return Some(functionlike_info.def_location);
+    }
+
+    codebase
+        .classlike_infos
+        .get(&referencing_symbol.0)
+        .map(|classlike_info| classlike_info.name_location)
+}
+
 fn is_method_referenced_somewhere_else(
     classlike_name: &StrId,
     method_name_ptr: &StrId,
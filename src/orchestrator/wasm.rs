@@ -38,6 +38,73 @@ pub fn scan_and_analyze_single_file(
         GraphKind::FunctionBody
     };
 
+    scan_and_analyze_single_file_with_config(
+        codebase,
+        interner,
+        file_name,
+        file_contents,
+        &analysis_config,
+    )
+}
+
+/// Analyzes a single file's source against the embedded HHI and HSL stubs without touching
+/// the filesystem, returning its issues in file order along with the interner used to
+/// resolve any names in them.
+pub fn analyze_single_source(source: &str, config: &Config) -> (Vec<Issue>, Interner) {
+    let (mut codebase, interner, _) = get_single_file_codebase(vec![]);
+
+    let file_name = "input.hack".to_string();
+
+    match scan_and_analyze_single_file_with_config(
+        &mut codebase,
+        &interner,
+        file_name.clone(),
+        source.to_string(),
+        config,
+    ) {
+        Ok((analysis_result, interner)) => {
+            let mut issues = analysis_result
+                .emitted_issues
+                .into_values()
+                .flatten()
+                .collect::<Vec<_>>();
+
+            issues.sort_by_key(|issue| (issue.pos.start_offset, issue.pos.end_offset));
+
+            (issues, interner)
+        }
+        Err(message) => {
+            let mut interner = interner;
+            let file_path = FilePath(interner.intern(file_name));
+
+            (
+                vec![Issue::new(
+                    IssueKind::InvalidHackFile,
+                    message,
+                    HPos {
+                        file_path,
+                        start_offset: 1,
+                        end_offset: 1,
+                        start_line: 1,
+                        end_line: 1,
+                        start_column: 1,
+                        end_column: 1,
+                    },
+                    &None,
+                )],
+                interner,
+            )
+        }
+    }
+}
+
+fn scan_and_analyze_single_file_with_config(
+    codebase: &mut CodebaseInfo,
+    interner: &Interner,
+    file_name: String,
+    file_contents: String,
+    analysis_config: &Config,
+) -> std::result::Result<(AnalysisResult, Interner), String> {
     let mut interner = ThreadedInterner::new(Arc::new(Mutex::new(interner.clone())));
 
     let resolved_names = scan_single_file(
@@ -61,7 +128,7 @@ pub fn scan_and_analyze_single_file(
         &mut symbol_references,
         FxHashSet::default(),
         FxHashSet::default(),
-        &analysis_config,
+        analysis_config,
     );
 
     let mut analysis_result = analyze_single_file(
@@ -70,13 +137,13 @@ pub fn scan_and_analyze_single_file(
         codebase,
         &interner,
         &resolved_names,
-        &analysis_config,
+        analysis_config,
     )?;
 
     if matches!(analysis_config.graph_kind, GraphKind::WholeProgram(_)) {
         let issues = find_tainted_data(
             &analysis_result.program_dataflow_graph,
-            &analysis_config,
+            analysis_config,
             &Logger::DevNull,
             &interner,
         );
@@ -122,6 +189,7 @@ pub fn get_single_file_codebase(
             empty_name_context.clone(),
             false,
             false,
+            None,
             &silent_logger,
         )
         .unwrap();
@@ -143,6 +211,7 @@ pub fn get_single_file_codebase(
             empty_name_context.clone(),
             false,
             false,
+            None,
             &silent_logger,
         )
         .unwrap();
@@ -163,6 +232,7 @@ pub fn get_single_file_codebase(
             empty_name_context.clone(),
             false,
             false,
+            None,
             &silent_logger,
         )
         .unwrap();
@@ -52,6 +52,13 @@ impl Interner {
         self.map.get_index(id.0 as usize).unwrap()
     }
 
+    /// Whether every string in `other` has the same id in `self`, so that ids from an
+    /// interner that started out as a copy of `other` are also valid here.
+    pub fn extends(&self, other: &Interner) -> bool {
+        self.map.len() >= other.map.len()
+            && self.map.iter().zip(other.map.iter()).all(|(a, b)| a == b)
+    }
+
     pub fn get_map(&self) -> FxHashMap<String, StrId> {
         self.map
             .iter()